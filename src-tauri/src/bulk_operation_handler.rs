@@ -0,0 +1,22 @@
+use crate::bulk_operation_service::{self, BulkAction};
+use crate::error::AppError;
+use tauri::AppHandle;
+
+/// Start the given instances, or all when `instance_names` is omitted.
+/// Returns the bulk id; results arrive with `bulk-operation-progress` and `-finished`.
+#[tauri::command]
+pub async fn start_lima_instances_cmd(
+    app: AppHandle,
+    instance_names: Option<Vec<String>>,
+) -> Result<String, AppError> {
+    bulk_operation_service::run_bulk_operation(&app, BulkAction::Start, instance_names).await
+}
+
+/// Stop the given instances, or all when `instance_names` is omitted
+#[tauri::command]
+pub async fn stop_lima_instances_cmd(
+    app: AppHandle,
+    instance_names: Option<Vec<String>>,
+) -> Result<String, AppError> {
+    bulk_operation_service::run_bulk_operation(&app, BulkAction::Stop, instance_names).await
+}
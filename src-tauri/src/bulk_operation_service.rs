@@ -0,0 +1,315 @@
+use crate::error::AppError;
+use crate::instance_registry_service::{self, LimaInstance};
+use crate::lima_backend::lima_backend;
+use crate::lima_instance_service;
+use crate::operation_service::{OperationKind, OperationOutcome, OutcomeWatcher};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Runtime};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Instances started or stopped at the same time. Booting VMs compete for host
+/// CPU and disk I/O, so starting everything at once is slower than a few at a time.
+const BULK_CONCURRENCY: usize = 3;
+
+/// Lifecycle operation applied to several instances
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BulkAction {
+    Start,
+    Stop,
+}
+
+impl BulkAction {
+    fn kind(self) -> OperationKind {
+        match self {
+            BulkAction::Start => OperationKind::Start,
+            BulkAction::Stop => OperationKind::Stop,
+        }
+    }
+
+    /// Status of instances the action has nothing to do for
+    fn target_status(self) -> &'static str {
+        match self {
+            BulkAction::Start => "Running",
+            BulkAction::Stop => "Stopped",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkResultStatus {
+    Success,
+    /// Started, but the readiness conditions were not met
+    NotReady,
+    Error,
+    Cancelled,
+    /// The instance already had the target status
+    Skipped,
+}
+
+/// How the operation went for one instance
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BulkInstanceResult {
+    pub instance_name: String,
+    pub status: BulkResultStatus,
+    /// The error message for `Error`, the failed readiness conditions for `NotReady`
+    pub message: Option<String>,
+}
+
+impl BulkInstanceResult {
+    fn new(instance_name: &str, status: BulkResultStatus, message: Option<String>) -> Self {
+        Self {
+            instance_name: instance_name.to_string(),
+            status,
+            message,
+        }
+    }
+
+    fn from_outcome(instance_name: &str, outcome: OperationOutcome) -> Self {
+        match outcome {
+            OperationOutcome::Success => Self::new(instance_name, BulkResultStatus::Success, None),
+            OperationOutcome::NotReady(message) => {
+                Self::new(instance_name, BulkResultStatus::NotReady, Some(message))
+            }
+            OperationOutcome::Error(message) => {
+                Self::new(instance_name, BulkResultStatus::Error, Some(message))
+            }
+            OperationOutcome::Cancelled => {
+                Self::new(instance_name, BulkResultStatus::Cancelled, None)
+            }
+        }
+    }
+}
+
+/// Payload of `bulk-operation-progress`, emitted whenever an instance is done, and of
+/// `bulk-operation-finished` once all are
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkProgress {
+    pub bulk_id: String,
+    pub action: BulkAction,
+    pub total: usize,
+    pub completed: usize,
+    pub failed: usize,
+    /// In the order the instances finished
+    pub results: Vec<BulkInstanceResult>,
+}
+
+impl BulkProgress {
+    fn record<R: Runtime>(&mut self, app: &AppHandle<R>, result: BulkInstanceResult) {
+        self.completed += 1;
+        if matches!(
+            result.status,
+            BulkResultStatus::NotReady | BulkResultStatus::Error | BulkResultStatus::Cancelled
+        ) {
+            self.failed += 1;
+        }
+        self.results.push(result);
+        let _ = app.emit("bulk-operation-progress", self.clone());
+    }
+}
+
+/// Start or stop the given instances, or all of them, a few at a time. Each instance
+/// goes through the regular lifecycle operation and its events. Returns the bulk id
+/// used in `bulk-operation-progress` and `bulk-operation-finished`.
+pub async fn run_bulk_operation<R: Runtime>(
+    app: &AppHandle<R>,
+    action: BulkAction,
+    instance_names: Option<Vec<String>>,
+) -> Result<String, AppError> {
+    let instances =
+        instance_registry_service::get_all_lima_instances(lima_backend(app).as_ref()).await?;
+    let mut names = instance_names
+        .unwrap_or_else(|| instances.iter().map(|i| i.name.clone()).collect::<Vec<_>>());
+    let mut seen = std::collections::HashSet::new();
+    names.retain(|name| seen.insert(name.clone()));
+
+    let progress = BulkProgress {
+        bulk_id: uuid::Uuid::new_v4().to_string(),
+        action,
+        total: names.len(),
+        completed: 0,
+        failed: 0,
+        results: Vec::new(),
+    };
+    let bulk_id = progress.bulk_id.clone();
+    let app = app.clone();
+    tokio::spawn(async move {
+        let progress = run_bulk(app.clone(), progress, names, instances).await;
+        let _ = app.emit("bulk-operation-finished", progress);
+    });
+    Ok(bulk_id)
+}
+
+async fn run_bulk<R: Runtime>(
+    app: AppHandle<R>,
+    progress: BulkProgress,
+    names: Vec<String>,
+    instances: Vec<LimaInstance>,
+) -> BulkProgress {
+    let action = progress.action;
+    let progress = Arc::new(Mutex::new(progress));
+    let semaphore = Arc::new(Semaphore::new(BULK_CONCURRENCY));
+    let mut tasks = JoinSet::new();
+
+    for name in names {
+        let skipped = match instances.iter().find(|i| i.name == name) {
+            None => Some(BulkInstanceResult::new(
+                &name,
+                BulkResultStatus::Error,
+                Some(
+                    AppError::InstanceNotFound {
+                        instance_name: name.clone(),
+                    }
+                    .to_string(),
+                ),
+            )),
+            Some(instance) if instance.status == action.target_status() => Some(
+                BulkInstanceResult::new(&name, BulkResultStatus::Skipped, None),
+            ),
+            Some(_) => None,
+        };
+        if let Some(result) = skipped {
+            if let Ok(mut progress) = progress.lock() {
+                progress.record(&app, result);
+            }
+            continue;
+        }
+
+        let (app, progress, semaphore) = (app.clone(), progress.clone(), semaphore.clone());
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let result = run_one(&app, action, &name).await;
+            if let Ok(mut progress) = progress.lock() {
+                progress.record(&app, result);
+            }
+        });
+    }
+    while tasks.join_next().await.is_some() {}
+
+    let progress = progress.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    progress
+}
+
+async fn run_one<R: Runtime>(
+    app: &AppHandle<R>,
+    action: BulkAction,
+    instance_name: &str,
+) -> BulkInstanceResult {
+    // Listen before starting, so an operation that fails right away is not missed
+    let mut watcher = OutcomeWatcher::new(app, action.kind());
+    let started = match action {
+        BulkAction::Start => {
+            lima_instance_service::start_lima_instance(app.clone(), instance_name.to_string()).await
+        }
+        BulkAction::Stop => {
+            lima_instance_service::stop_lima_instance(app.clone(), instance_name.to_string(), false)
+                .await
+        }
+    };
+    let outcome = match started {
+        Ok(operation_id) => watcher.wait(&operation_id).await,
+        Err(e) => OperationOutcome::Error(e.to_string()),
+    };
+    BulkInstanceResult::from_outcome(instance_name, outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_lima_backend::FakeLimaBackend;
+    use crate::lima_backend::{LimaBackend, LimaBackendState};
+    use crate::operation_service::OperationRegistry;
+    use tauri::{Listener, Manager};
+
+    #[tokio::test]
+    async fn test_bulk_start_reports_each_instance() {
+        let backend = Arc::new(
+            FakeLimaBackend::new()
+                .with_instance("a", "Stopped")
+                .with_instance("b", "Stopped")
+                .with_instance("c", "Stopped")
+                .with_instance("d", "Stopped")
+                .with_instance("running", "Running"),
+        );
+        backend.fail_next("start", "FATA[0000] boot failed");
+        let app = tauri::test::mock_app();
+        app.manage(LimaBackendState(backend));
+        app.manage(OperationRegistry::default());
+
+        let updates = Arc::new(Mutex::new(Vec::new()));
+        {
+            let updates = updates.clone();
+            app.listen_any("bulk-operation-progress", move |event| {
+                let progress: serde_json::Value = serde_json::from_str(event.payload()).unwrap();
+                updates
+                    .lock()
+                    .unwrap()
+                    .push(progress["completed"].as_u64().unwrap());
+            });
+        }
+
+        let (sender, finished) = tokio::sync::oneshot::channel();
+        let sender = Mutex::new(Some(sender));
+        app.listen_any("bulk-operation-finished", move |event| {
+            if let Some(sender) = sender.lock().unwrap().take() {
+                let _ = sender.send(event.payload().to_string());
+            }
+        });
+
+        let names = ["a", "b", "c", "d", "running", "gone", "a"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let bulk_id = run_bulk_operation(app.handle(), BulkAction::Start, Some(names))
+            .await
+            .unwrap();
+        let finished = tokio::time::timeout(std::time::Duration::from_secs(5), finished)
+            .await
+            .unwrap()
+            .unwrap();
+        let finished: serde_json::Value = serde_json::from_str(&finished).unwrap();
+
+        assert_eq!(finished["bulk_id"], bulk_id);
+        assert_eq!(finished["total"], 6);
+        assert_eq!(finished["completed"], 6);
+        // One failed start and the missing instance
+        assert_eq!(finished["failed"], 2);
+        let status_of = |name: &str| {
+            finished["results"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|r| r["instance_name"] == name)
+                .map(|r| r["status"].as_str().unwrap().to_string())
+                .unwrap()
+        };
+        assert_eq!(status_of("running"), "skipped");
+        assert_eq!(status_of("gone"), "error");
+        assert_eq!(*updates.lock().unwrap(), [1, 2, 3, 4, 5, 6]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_bulk_start_reports_an_instance_that_is_not_ready() {
+        let backend = Arc::new(FakeLimaBackend::new().with_instance("dev", "Stopped"));
+        std::fs::write(
+            backend.lima_home().unwrap().join("dev").join("lima.yaml"),
+            "probes:\n- description: k0s to be running\n  script: exit 1\n",
+        )
+        .unwrap();
+        let app = tauri::test::mock_app();
+        app.manage(LimaBackendState(backend));
+        app.manage(OperationRegistry::default());
+
+        // The paused clock skips the readiness retries until the start gives up
+        let result = run_one(app.handle(), BulkAction::Start, "dev").await;
+
+        assert_eq!(result.status, BulkResultStatus::NotReady);
+        assert!(result
+            .message
+            .unwrap()
+            .contains("probe 'k0s to be running'"));
+    }
+}
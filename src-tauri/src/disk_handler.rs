@@ -0,0 +1,56 @@
+use crate::disk_service::{self, LimaDisk};
+use crate::error::AppError;
+use tauri::AppHandle;
+
+/// List all disks with the instance each is locked to
+#[tauri::command]
+pub async fn list_disks_cmd(app: AppHandle) -> Result<Vec<LimaDisk>, AppError> {
+    disk_service::list_disks(&app).await
+}
+
+/// Create a named disk, e.g. size "50GiB" and format "qcow2" or "raw"
+#[tauri::command]
+pub async fn create_disk_cmd(
+    app: AppHandle,
+    disk_name: String,
+    size: String,
+    format: Option<String>,
+) -> Result<LimaDisk, AppError> {
+    disk_service::create_disk(&app, &disk_name, &size, format.as_deref()).await
+}
+
+/// Grow a disk that is not in use
+#[tauri::command]
+pub async fn resize_disk_cmd(
+    app: AppHandle,
+    disk_name: String,
+    size: String,
+) -> Result<LimaDisk, AppError> {
+    disk_service::resize_disk(&app, &disk_name, &size).await
+}
+
+/// Delete a disk that no instance uses
+#[tauri::command]
+pub async fn delete_disk_cmd(app: AppHandle, disk_name: String) -> Result<(), AppError> {
+    disk_service::delete_disk(&app, &disk_name).await
+}
+
+/// Attach a disk to an instance from its next start
+#[tauri::command]
+pub async fn attach_disk_cmd(
+    app: AppHandle,
+    instance_name: String,
+    disk_name: String,
+) -> Result<(), AppError> {
+    disk_service::attach_disk(&app, &instance_name, &disk_name).await
+}
+
+/// Detach a disk from an instance from its next start
+#[tauri::command]
+pub async fn detach_disk_cmd(
+    app: AppHandle,
+    instance_name: String,
+    disk_name: String,
+) -> Result<(), AppError> {
+    disk_service::detach_disk(&app, &instance_name, &disk_name).await
+}
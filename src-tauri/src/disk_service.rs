@@ -0,0 +1,333 @@
+use crate::error::AppError;
+use crate::instance_registry_service;
+use crate::lima_backend::{lima_backend, LimaBackend};
+use crate::lima_config::AdditionalDisk;
+use crate::lima_config_service::write_lima_yaml;
+use crate::lima_instance_service::read_instance_config;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Runtime};
+
+/// Symlink in a disk's dir naming the instance dir that holds the disk
+const DISK_LOCK_FILE: &str = "in_use_by";
+
+/// A disk made by `limactl disk create`, living in `$LIMA_HOME/_disks/<name>`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LimaDisk {
+    pub name: String,
+    /// Virtual size in bytes
+    pub size_bytes: u64,
+    pub dir: String,
+    /// Instance holding the disk's lock, i.e. the running instance it is attached to
+    pub locked_by: Option<String>,
+    /// Instances whose lima.yaml lists the disk in `additionalDisks`
+    pub attached_to: Vec<String>,
+}
+
+/// One line of `limactl disk list --json`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DiskListEntry {
+    name: String,
+    #[serde(default)]
+    size: u64,
+    #[serde(default)]
+    dir: String,
+    /// Empty when the disk is not locked
+    #[serde(default)]
+    instance: String,
+}
+
+/// Point the locks of disks held by the instance in `old_dir` at `new_dir`. Lima keeps a
+/// disk's lock as an `in_use_by` symlink to the instance dir in `$LIMA_HOME/_disks/<name>`.
+/// Returns the moved locks; on failure the locks moved so far are moved back.
+pub fn retarget_disk_locks(
+    lima_home: &Path,
+    old_dir: &Path,
+    new_dir: &Path,
+) -> Result<Vec<PathBuf>, AppError> {
+    let Ok(entries) = std::fs::read_dir(lima_home.join("_disks")) else {
+        return Ok(Vec::new());
+    };
+    let mut moved = Vec::new();
+    for entry in entries.flatten() {
+        let lock = entry.path().join(DISK_LOCK_FILE);
+        if std::fs::read_link(&lock).ok().as_deref() != Some(old_dir) {
+            continue;
+        }
+        let relinked =
+            std::fs::remove_file(&lock).and_then(|()| std::os::unix::fs::symlink(new_dir, &lock));
+        if let Err(e) = relinked {
+            let _ = std::os::unix::fs::symlink(old_dir, &lock);
+            restore_disk_locks(&moved, old_dir);
+            return Err(AppError::io(
+                &format!("Failed to move the lock of {}", entry.path().display()),
+                e,
+            ));
+        }
+        moved.push(lock);
+    }
+    Ok(moved)
+}
+
+/// Point disk locks moved by `retarget_disk_locks` back at the instance dir `dir`
+pub fn restore_disk_locks(locks: &[PathBuf], dir: &Path) {
+    for lock in locks {
+        let _ = std::fs::remove_file(lock);
+        let _ = std::os::unix::fs::symlink(dir, lock);
+    }
+}
+
+/// Run `limactl disk <args...>`, turning a non-zero exit into an error
+async fn run_limactl_disk(backend: &dyn LimaBackend, args: &[&str]) -> Result<String, AppError> {
+    let output = backend.disk(args).await?;
+    if !output.success() {
+        return Err(AppError::command_failed(
+            &format!("limactl disk {}", args.first().copied().unwrap_or_default()),
+            output.code,
+            &output.stderr,
+        ));
+    }
+    Ok(output.stdout)
+}
+
+fn parse_disk_list(output: &str) -> Result<Vec<DiskListEntry>, AppError> {
+    output
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line).map_err(|e| AppError::parse("Failed to parse disk list", e))
+        })
+        .collect()
+}
+
+/// All disks with their lock owner and the instances configured to attach them
+pub async fn list_disks<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<LimaDisk>, AppError> {
+    let backend = lima_backend(app);
+    let entries = parse_disk_list(&run_limactl_disk(backend.as_ref(), &["list", "--json"]).await?)?;
+    let instances = instance_registry_service::get_all_lima_instances(backend.as_ref()).await?;
+    let configs: Vec<(String, Vec<AdditionalDisk>)> = instances
+        .iter()
+        .filter_map(|i| {
+            let disks = read_instance_config(app, &i.name)?.additional_disks?;
+            Some((i.name.clone(), disks))
+        })
+        .collect();
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| LimaDisk {
+            attached_to: configs
+                .iter()
+                .filter(|(_, disks)| disks.iter().any(|d| d.name == entry.name))
+                .map(|(instance_name, _)| instance_name.clone())
+                .collect(),
+            locked_by: (!entry.instance.is_empty()).then_some(entry.instance),
+            name: entry.name,
+            size_bytes: entry.size,
+            dir: entry.dir,
+        })
+        .collect())
+}
+
+async fn find_disk<R: Runtime>(app: &AppHandle<R>, disk_name: &str) -> Result<LimaDisk, AppError> {
+    list_disks(app)
+        .await?
+        .into_iter()
+        .find(|d| d.name == disk_name)
+        .ok_or_else(|| AppError::DiskNotFound {
+            disk_name: disk_name.to_string(),
+        })
+}
+
+/// Create a disk of `size` (e.g. "50GiB"); `format` is "qcow2" or "raw", Lima's default if unset
+pub async fn create_disk<R: Runtime>(
+    app: &AppHandle<R>,
+    disk_name: &str,
+    size: &str,
+    format: Option<&str>,
+) -> Result<LimaDisk, AppError> {
+    if list_disks(app).await?.iter().any(|d| d.name == disk_name) {
+        return Err(AppError::DiskAlreadyExists {
+            disk_name: disk_name.to_string(),
+        });
+    }
+    let mut args = vec!["create", disk_name, "--size", size];
+    if let Some(format) = format {
+        args.extend(["--format", format]);
+    }
+    run_limactl_disk(lima_backend(app).as_ref(), &args).await?;
+    find_disk(app, disk_name).await
+}
+
+/// Grow a disk to `size`. The disk must not be locked by a running instance.
+pub async fn resize_disk<R: Runtime>(
+    app: &AppHandle<R>,
+    disk_name: &str,
+    size: &str,
+) -> Result<LimaDisk, AppError> {
+    let disk = find_disk(app, disk_name).await?;
+    if let Some(instance_name) = disk.locked_by {
+        return Err(AppError::DiskInUse {
+            disk_name: disk_name.to_string(),
+            instance_name,
+        });
+    }
+    run_limactl_disk(
+        lima_backend(app).as_ref(),
+        &["resize", disk_name, "--size", size],
+    )
+    .await?;
+    find_disk(app, disk_name).await
+}
+
+/// Delete a disk and its data. Refused while any instance has the disk locked or
+/// attached in its config, so an instance never fails to start over a missing disk.
+pub async fn delete_disk<R: Runtime>(app: &AppHandle<R>, disk_name: &str) -> Result<(), AppError> {
+    let disk = find_disk(app, disk_name).await?;
+    if let Some(instance_name) = disk.locked_by.or(disk.attached_to.into_iter().next()) {
+        return Err(AppError::DiskInUse {
+            disk_name: disk_name.to_string(),
+            instance_name,
+        });
+    }
+    run_limactl_disk(lima_backend(app).as_ref(), &["delete", disk_name]).await?;
+    Ok(())
+}
+
+/// Add the disk to the instance's `additionalDisks`. Takes effect on the next start;
+/// attaching an already attached disk is a no-op.
+pub async fn attach_disk<R: Runtime>(
+    app: &AppHandle<R>,
+    instance_name: &str,
+    disk_name: &str,
+) -> Result<(), AppError> {
+    find_disk(app, disk_name).await?;
+    let mut config =
+        read_instance_config(app, instance_name).ok_or_else(|| AppError::InstanceNotFound {
+            instance_name: instance_name.to_string(),
+        })?;
+    let disks = config.additional_disks.get_or_insert_with(Vec::new);
+    if disks.iter().any(|d| d.name == disk_name) {
+        return Ok(());
+    }
+    disks.push(AdditionalDisk::named(disk_name));
+    write_lima_yaml(app, &config, instance_name)
+}
+
+/// Remove the disk from the instance's `additionalDisks`. Takes effect on the next
+/// start; the disk and its data are kept.
+pub async fn detach_disk<R: Runtime>(
+    app: &AppHandle<R>,
+    instance_name: &str,
+    disk_name: &str,
+) -> Result<(), AppError> {
+    let mut config =
+        read_instance_config(app, instance_name).ok_or_else(|| AppError::InstanceNotFound {
+            instance_name: instance_name.to_string(),
+        })?;
+    let Some(disks) = config.additional_disks.as_mut() else {
+        return Ok(());
+    };
+    let count = disks.len();
+    disks.retain(|d| d.name != disk_name);
+    if disks.len() == count {
+        return Ok(());
+    }
+    write_lima_yaml(app, &config, instance_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_lima_backend::FakeLimaBackend;
+    use crate::lima_backend::LimaBackendState;
+    use std::sync::Arc;
+    use tauri::Manager;
+
+    fn mock_app(backend: FakeLimaBackend) -> tauri::App<tauri::test::MockRuntime> {
+        let app = tauri::test::mock_app();
+        app.manage(LimaBackendState(Arc::new(backend)));
+        app
+    }
+
+    #[test]
+    fn test_parse_disk_list() {
+        let output = r#"{"name":"data","size":10737418240,"format":"qcow2","dir":"/lima/_disks/data","instance":"","instanceDir":"","mountPoint":"/mnt/lima-data"}
+{"name":"docker","size":53687091200,"dir":"/lima/_disks/docker","instance":"k0s","instanceDir":"/lima/k0s","mountPoint":"/mnt/lima-docker"}
+"#;
+        let entries = parse_disk_list(output).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].size, 10 << 30);
+        assert_eq!(entries[0].instance, "");
+        assert_eq!(entries[1].instance, "k0s");
+        assert!(parse_disk_list("not json").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_disk_lifecycle_and_attach() {
+        let backend = FakeLimaBackend::new().with_instance("dev", "Stopped");
+        let dir = backend.lima_home().unwrap().join("dev");
+        std::fs::write(dir.join("lima.yaml"), "cpus: 2\n").unwrap();
+        let app = mock_app(backend);
+        let app = app.handle();
+
+        let disk = create_disk(app, "data", "10GiB", None).await.unwrap();
+        assert_eq!(disk.size_bytes, 10 << 30);
+        assert_eq!(disk.locked_by, None);
+        assert_eq!(
+            create_disk(app, "data", "10GiB", None)
+                .await
+                .unwrap_err()
+                .kind(),
+            "diskAlreadyExists"
+        );
+
+        let disk = resize_disk(app, "data", "20GiB").await.unwrap();
+        assert_eq!(disk.size_bytes, 20 << 30);
+
+        attach_disk(app, "dev", "data").await.unwrap();
+        attach_disk(app, "dev", "data").await.unwrap();
+        let config = read_instance_config(app, "dev").unwrap();
+        assert_eq!(
+            config.additional_disks,
+            Some(vec![AdditionalDisk::named("data")])
+        );
+        assert_eq!(list_disks(app).await.unwrap()[0].attached_to, ["dev"]);
+        assert_eq!(
+            delete_disk(app, "data").await.unwrap_err().kind(),
+            "diskInUse"
+        );
+
+        detach_disk(app, "dev", "data").await.unwrap();
+        // An empty `additionalDisks` is left out of lima.yaml
+        assert_eq!(
+            read_instance_config(app, "dev").unwrap().additional_disks,
+            None
+        );
+        delete_disk(app, "data").await.unwrap();
+        assert!(list_disks(app).await.unwrap().is_empty());
+        assert_eq!(
+            attach_disk(app, "dev", "data").await.unwrap_err().kind(),
+            "diskNotFound"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_locked_disk_cannot_be_resized_or_deleted() {
+        let app = mock_app(FakeLimaBackend::new().with_disk("docker", 50 << 30, Some("k0s")));
+        let app = app.handle();
+
+        let disks = list_disks(app).await.unwrap();
+        assert_eq!(disks[0].locked_by.as_deref(), Some("k0s"));
+        match resize_disk(app, "docker", "60GiB").await {
+            Err(AppError::DiskInUse { instance_name, .. }) => assert_eq!(instance_name, "k0s"),
+            other => panic!("expected DiskInUse, got {:?}", other),
+        }
+        assert_eq!(
+            delete_disk(app, "docker").await.unwrap_err().kind(),
+            "diskInUse"
+        );
+    }
+}
@@ -0,0 +1,439 @@
+use crate::lima_compat_service::CompatibilityIssue;
+use crate::preflight_service::ResourceIssue;
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use std::fmt;
+
+const LIMA_NOT_FOUND_MESSAGE: &str = "Lima (limactl) not found. Please ensure lima is installed in /usr/local/bin, /opt/homebrew/bin, or is in your PATH.";
+
+/// Error returned by all services and Tauri commands.
+///
+/// Serialized for the frontend as an object with a stable `kind` discriminator,
+/// a human readable `message`, and variant specific fields (camelCase), e.g.
+/// `{ "kind": "commandFailed", "message": "...", "command": "limactl stop", "exitCode": 1, "stderr": "..." }`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppError {
+    /// The limactl executable could not be found
+    LimaNotFound,
+    /// The named Lima instance does not exist
+    InstanceNotFound { instance_name: String },
+    /// An instance with this name already exists
+    InstanceAlreadyExists { instance_name: String },
+    /// The name cannot be used for a Lima instance
+    InvalidInstanceName { instance_name: String },
+    /// The operation needs the instance to be stopped
+    InstanceNotStopped {
+        instance_name: String,
+        status: String,
+    },
+    /// The instance has no snapshot with this tag
+    SnapshotNotFound { instance_name: String, tag: String },
+    /// The instance already has a snapshot with this tag
+    SnapshotAlreadyExists { instance_name: String, tag: String },
+    /// The tag cannot be used for a snapshot
+    InvalidSnapshotTag { tag: String },
+    /// No disk with this name was created with `limactl disk create`
+    DiskNotFound { disk_name: String },
+    /// A disk with this name already exists
+    DiskAlreadyExists { disk_name: String },
+    /// The disk is locked by, or attached in the config of, an instance
+    DiskInUse {
+        disk_name: String,
+        instance_name: String,
+    },
+    /// A template (file, URL without a cached copy, or `template://` name) does not exist
+    TemplateNotFound { location: String },
+    /// The named terminal session does not exist
+    SessionNotFound { session_id: String },
+    /// The lifecycle operation does not exist or has already finished
+    OperationNotFound { operation_id: String },
+    /// Another lifecycle operation is already running on the instance
+    OperationInProgress {
+        instance_name: String,
+        operation_id: String,
+        /// Kind of the running operation, e.g. "start"
+        running_operation: String,
+    },
+    /// An external command ran but exited unsuccessfully
+    CommandFailed {
+        command: String,
+        exit_code: Option<i32>,
+        stderr: String,
+    },
+    /// Output or a file could not be parsed or serialized
+    ParseError { message: String },
+    /// Filesystem, process spawning or other I/O failure
+    Io { message: String },
+    /// An operation did not finish in time
+    Timeout { operation: String, seconds: u64 },
+    /// The installed Lima is too old for the requested config
+    LimaIncompatible {
+        installed_version: String,
+        required_version: Option<String>,
+        issues: Vec<CompatibilityIssue>,
+    },
+    /// A downloaded or local file did not match its expected SHA-256
+    ChecksumMismatch { expected: String, actual: String },
+    /// A user supplied setting was rejected on save
+    InvalidSetting { field: String, message: String },
+    /// The host cannot give the instance the CPUs, memory or disk it asks for
+    InsufficientResources { issues: Vec<ResourceIssue> },
+}
+
+impl AppError {
+    /// I/O error with context, e.g. `AppError::io("Failed to write env.sh", e)`
+    pub fn io(context: &str, err: impl fmt::Display) -> Self {
+        AppError::Io {
+            message: format!("{}: {}", context, err),
+        }
+    }
+
+    /// Parse error with context, e.g. `AppError::parse("Failed to parse YAML", e)`
+    pub fn parse(context: &str, err: impl fmt::Display) -> Self {
+        AppError::ParseError {
+            message: format!("{}: {}", context, err),
+        }
+    }
+
+    /// Error for a command that exited unsuccessfully.
+    /// Recognizes limactl's `instance "<name>" does not exist` and reports it as `InstanceNotFound`.
+    pub fn command_failed(command: &str, exit_code: Option<i32>, stderr: &str) -> Self {
+        if let Some(instance_name) = parse_missing_instance(stderr) {
+            return AppError::InstanceNotFound { instance_name };
+        }
+        AppError::CommandFailed {
+            command: command.to_string(),
+            exit_code,
+            stderr: stderr.trim().to_string(),
+        }
+    }
+
+    /// Stable identifier used by the frontend to branch on error kinds
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AppError::LimaNotFound => "limaNotFound",
+            AppError::InstanceNotFound { .. } => "instanceNotFound",
+            AppError::InstanceAlreadyExists { .. } => "instanceAlreadyExists",
+            AppError::InvalidInstanceName { .. } => "invalidInstanceName",
+            AppError::InstanceNotStopped { .. } => "instanceNotStopped",
+            AppError::SnapshotNotFound { .. } => "snapshotNotFound",
+            AppError::SnapshotAlreadyExists { .. } => "snapshotAlreadyExists",
+            AppError::InvalidSnapshotTag { .. } => "invalidSnapshotTag",
+            AppError::DiskNotFound { .. } => "diskNotFound",
+            AppError::DiskAlreadyExists { .. } => "diskAlreadyExists",
+            AppError::DiskInUse { .. } => "diskInUse",
+            AppError::TemplateNotFound { .. } => "templateNotFound",
+            AppError::SessionNotFound { .. } => "sessionNotFound",
+            AppError::OperationNotFound { .. } => "operationNotFound",
+            AppError::OperationInProgress { .. } => "operationInProgress",
+            AppError::CommandFailed { .. } => "commandFailed",
+            AppError::ParseError { .. } => "parseError",
+            AppError::Io { .. } => "io",
+            AppError::Timeout { .. } => "timeout",
+            AppError::LimaIncompatible { .. } => "limaIncompatible",
+            AppError::ChecksumMismatch { .. } => "checksumMismatch",
+            AppError::InvalidSetting { .. } => "invalidSetting",
+            AppError::InsufficientResources { .. } => "insufficientResources",
+        }
+    }
+}
+
+/// Extract `<name>` from limactl's `instance "<name>" does not exist` message.
+/// Handles both plain output and logrus lines, where the quotes are escaped (`\"`).
+fn parse_missing_instance(stderr: &str) -> Option<String> {
+    let stderr = stderr.replace("\\\"", "\"");
+    let rest = &stderr[stderr.find("instance \"")? + "instance \"".len()..];
+    let (name, tail) = rest.split_once('"')?;
+    if tail.trim_start().starts_with("does not exist") && !name.is_empty() {
+        Some(name.to_string())
+    } else {
+        None
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::LimaNotFound => write!(f, "{}", LIMA_NOT_FOUND_MESSAGE),
+            AppError::InstanceNotFound { instance_name } => {
+                write!(f, "Lima instance '{}' does not exist", instance_name)
+            }
+            AppError::InstanceAlreadyExists { instance_name } => {
+                write!(f, "Lima instance '{}' already exists", instance_name)
+            }
+            AppError::InvalidInstanceName { instance_name } => write!(
+                f,
+                "'{}' is not a valid instance name: use letters, digits, '-', '_' and '.'",
+                instance_name
+            ),
+            AppError::InstanceNotStopped {
+                instance_name,
+                status,
+            } => write!(
+                f,
+                "Lima instance '{}' must be stopped first (it is {})",
+                instance_name, status
+            ),
+            AppError::SnapshotNotFound { instance_name, tag } => write!(
+                f,
+                "Lima instance '{}' has no snapshot '{}'",
+                instance_name, tag
+            ),
+            AppError::SnapshotAlreadyExists { instance_name, tag } => write!(
+                f,
+                "Lima instance '{}' already has a snapshot '{}'",
+                instance_name, tag
+            ),
+            AppError::InvalidSnapshotTag { tag } => write!(
+                f,
+                "'{}' is not a valid snapshot tag: use letters, digits, '-', '_' and '.'",
+                tag
+            ),
+            AppError::DiskNotFound { disk_name } => {
+                write!(f, "Disk '{}' does not exist", disk_name)
+            }
+            AppError::DiskAlreadyExists { disk_name } => {
+                write!(f, "Disk '{}' already exists", disk_name)
+            }
+            AppError::DiskInUse {
+                disk_name,
+                instance_name,
+            } => write!(
+                f,
+                "Disk '{}' is in use by Lima instance '{}'",
+                disk_name, instance_name
+            ),
+            AppError::TemplateNotFound { location } => {
+                write!(f, "Template '{}' does not exist", location)
+            }
+            AppError::SessionNotFound { .. } => write!(f, "Session not found"),
+            AppError::OperationNotFound { .. } => {
+                write!(f, "Operation not found or already finished")
+            }
+            AppError::OperationInProgress {
+                instance_name,
+                running_operation,
+                ..
+            } => write!(
+                f,
+                "Lima instance '{}' is busy: {} is still in progress",
+                instance_name, running_operation
+            ),
+            AppError::CommandFailed {
+                command,
+                exit_code,
+                stderr,
+            } => {
+                if stderr.is_empty() {
+                    write!(
+                        f,
+                        "{} failed with exit code {}",
+                        command,
+                        exit_code.unwrap_or(-1)
+                    )
+                } else {
+                    write!(f, "{} failed: {}", command, stderr)
+                }
+            }
+            AppError::ParseError { message } | AppError::Io { message } => {
+                write!(f, "{}", message)
+            }
+            AppError::Timeout { operation, seconds } => {
+                write!(f, "{} timed out after {}s", operation, seconds)
+            }
+            AppError::LimaIncompatible { issues, .. } => {
+                let messages: Vec<&str> = issues.iter().map(|i| i.message.as_str()).collect();
+                write!(f, "Incompatible Lima version: {}", messages.join("; "))
+            }
+            AppError::ChecksumMismatch { expected, actual } => {
+                write!(f, "SHA-256 mismatch: expected {}, got {}", expected, actual)
+            }
+            AppError::InvalidSetting { field, message } => {
+                write!(f, "Invalid {}: {}", field, message)
+            }
+            AppError::InsufficientResources { issues } => {
+                let messages: Vec<&str> = issues.iter().map(|i| i.message.as_str()).collect();
+                write!(f, "Not enough host resources: {}", messages.join("; "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("kind", self.kind())?;
+        map.serialize_entry("message", &self.to_string())?;
+        match self {
+            AppError::InstanceNotFound { instance_name } => {
+                map.serialize_entry("instanceName", instance_name)?;
+            }
+            AppError::InstanceAlreadyExists { instance_name }
+            | AppError::InvalidInstanceName { instance_name } => {
+                map.serialize_entry("instanceName", instance_name)?;
+            }
+            AppError::InstanceNotStopped {
+                instance_name,
+                status,
+            } => {
+                map.serialize_entry("instanceName", instance_name)?;
+                map.serialize_entry("status", status)?;
+            }
+            AppError::SnapshotNotFound { instance_name, tag }
+            | AppError::SnapshotAlreadyExists { instance_name, tag } => {
+                map.serialize_entry("instanceName", instance_name)?;
+                map.serialize_entry("tag", tag)?;
+            }
+            AppError::InvalidSnapshotTag { tag } => {
+                map.serialize_entry("tag", tag)?;
+            }
+            AppError::DiskNotFound { disk_name } | AppError::DiskAlreadyExists { disk_name } => {
+                map.serialize_entry("diskName", disk_name)?;
+            }
+            AppError::DiskInUse {
+                disk_name,
+                instance_name,
+            } => {
+                map.serialize_entry("diskName", disk_name)?;
+                map.serialize_entry("instanceName", instance_name)?;
+            }
+            AppError::TemplateNotFound { location } => {
+                map.serialize_entry("location", location)?;
+            }
+            AppError::SessionNotFound { session_id } => {
+                map.serialize_entry("sessionId", session_id)?;
+            }
+            AppError::OperationNotFound { operation_id } => {
+                map.serialize_entry("operationId", operation_id)?;
+            }
+            AppError::OperationInProgress {
+                instance_name,
+                operation_id,
+                running_operation,
+            } => {
+                map.serialize_entry("instanceName", instance_name)?;
+                map.serialize_entry("operationId", operation_id)?;
+                map.serialize_entry("runningOperation", running_operation)?;
+            }
+            AppError::CommandFailed {
+                command,
+                exit_code,
+                stderr,
+            } => {
+                map.serialize_entry("command", command)?;
+                map.serialize_entry("exitCode", exit_code)?;
+                map.serialize_entry("stderr", stderr)?;
+            }
+            AppError::Timeout { operation, seconds } => {
+                map.serialize_entry("operation", operation)?;
+                map.serialize_entry("seconds", seconds)?;
+            }
+            AppError::LimaIncompatible {
+                installed_version,
+                required_version,
+                issues,
+            } => {
+                map.serialize_entry("installedVersion", installed_version)?;
+                map.serialize_entry("requiredVersion", required_version)?;
+                map.serialize_entry("issues", issues)?;
+            }
+            AppError::ChecksumMismatch { expected, actual } => {
+                map.serialize_entry("expected", expected)?;
+                map.serialize_entry("actual", actual)?;
+            }
+            AppError::InvalidSetting { field, .. } => {
+                map.serialize_entry("field", field)?;
+            }
+            AppError::InsufficientResources { issues } => {
+                map.serialize_entry("issues", issues)?;
+            }
+            AppError::LimaNotFound | AppError::ParseError { .. } | AppError::Io { .. } => {}
+        }
+        map.end()
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(err: std::io::Error) -> Self {
+        AppError::Io {
+            message: err.to_string(),
+        }
+    }
+}
+
+impl From<tauri::Error> for AppError {
+    fn from(err: tauri::Error) -> Self {
+        AppError::Io {
+            message: err.to_string(),
+        }
+    }
+}
+
+impl From<serde_yml::Error> for AppError {
+    fn from(err: serde_yml::Error) -> Self {
+        AppError::ParseError {
+            message: err.to_string(),
+        }
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(err: serde_json::Error) -> Self {
+        AppError::ParseError {
+            message: err.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_failed_detects_missing_instance() {
+        let err = AppError::command_failed(
+            "limactl start",
+            Some(1),
+            "time=\"...\" level=fatal msg=\"instance \\\"k0s\\\" does not exist, run `limactl create --name=k0s` to create a new instance\"",
+        );
+        assert_eq!(
+            err,
+            AppError::InstanceNotFound {
+                instance_name: "k0s".to_string()
+            }
+        );
+
+        let err = AppError::command_failed(
+            "limactl shell",
+            Some(1),
+            "instance \"docker\" does not exist, run `limactl create --name=docker` to create a new instance",
+        );
+        assert_eq!(
+            err,
+            AppError::InstanceNotFound {
+                instance_name: "docker".to_string()
+            }
+        );
+
+        let err = AppError::command_failed("limactl stop", Some(1), "instance \"k0s\" is busy");
+        assert_eq!(err.kind(), "commandFailed");
+    }
+
+    #[test]
+    fn test_serialize_includes_kind_message_and_fields() {
+        let err = AppError::command_failed("limactl stop", Some(2), "boom\n");
+        let json = serde_json::to_value(&err).unwrap();
+        assert_eq!(json["kind"], "commandFailed");
+        assert_eq!(json["message"], "limactl stop failed: boom");
+        assert_eq!(json["exitCode"], 2);
+        assert_eq!(json["stderr"], "boom");
+
+        let json = serde_json::to_value(AppError::LimaNotFound).unwrap();
+        assert_eq!(json["kind"], "limaNotFound");
+        assert!(json["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("Lima (limactl) not found"));
+    }
+}
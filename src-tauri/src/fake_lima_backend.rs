@@ -0,0 +1,660 @@
+use crate::error::AppError;
+use crate::lima_backend::{
+    BackendFuture, BackendStream, LimaBackend, LimaExitStatus, LimaOutput, LimaProcess,
+};
+use crate::lima_config::LimaConfig;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// In-process stand-in for `limactl`, used to exercise services and lifecycle
+/// event sequences without a hypervisor. Instances live in memory; their
+/// directories (with `lima.yaml`) are written under a throwaway LIMA_HOME.
+pub struct FakeLimaBackend {
+    lima_home: PathBuf,
+    version: String,
+    instances: Mutex<BTreeMap<String, FakeInstance>>,
+    /// Operation name ("create", "start", "stop --force", ...) -> stderr to fail the next call with
+    failures: Mutex<HashMap<String, String>>,
+    /// Operation names whose next process runs until killed
+    hangs: Mutex<HashSet<String>>,
+    /// Space-joined shell args -> canned output
+    shell_responses: Mutex<HashMap<String, LimaOutput>>,
+    /// Instance name -> tags of its `limactl snapshot`s, oldest first
+    snapshots: Mutex<BTreeMap<String, Vec<String>>>,
+    /// Disks made by `limactl disk create`
+    disks: Mutex<BTreeMap<String, FakeDisk>>,
+}
+
+struct FakeInstance {
+    status: String,
+    config: Option<LimaConfig>,
+}
+
+struct FakeDisk {
+    size: u64,
+}
+
+impl FakeLimaBackend {
+    /// Create a fake backend with a fresh LIMA_HOME under the system temp dir
+    pub fn new() -> Self {
+        let lima_home =
+            std::env::temp_dir().join(format!("0ma-fake-lima-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&lima_home).expect("Failed to create fake LIMA_HOME");
+        Self {
+            lima_home,
+            version: "2.0.0".to_string(),
+            instances: Mutex::new(BTreeMap::new()),
+            failures: Mutex::new(HashMap::new()),
+            hangs: Mutex::new(HashSet::new()),
+            shell_responses: Mutex::new(HashMap::new()),
+            snapshots: Mutex::new(BTreeMap::new()),
+            disks: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Register an existing instance with the given status
+    pub fn with_instance(self, instance_name: &str, status: &str) -> Self {
+        std::fs::create_dir_all(self.lima_home.join(instance_name))
+            .expect("Failed to create fake instance dir");
+        self.instances.lock().unwrap().insert(
+            instance_name.to_string(),
+            FakeInstance {
+                status: status.to_string(),
+                config: None,
+            },
+        );
+        self
+    }
+
+    /// Register an existing disk of `size` bytes, locked to `instance` if given.
+    /// Like Lima, the lock is an `in_use_by` symlink to the instance dir.
+    pub fn with_disk(self, disk_name: &str, size: u64, instance: Option<&str>) -> Self {
+        let disk_dir = self.lima_home.join("_disks").join(disk_name);
+        std::fs::create_dir_all(&disk_dir).expect("Failed to create fake disk dir");
+        if let Some(instance) = instance {
+            std::os::unix::fs::symlink(self.lima_home.join(instance), disk_dir.join("in_use_by"))
+                .expect("Failed to lock fake disk");
+        }
+        self.disks
+            .lock()
+            .unwrap()
+            .insert(disk_name.to_string(), FakeDisk { size });
+        self
+    }
+
+    /// Instance dir the disk is locked to, from its `in_use_by` symlink
+    fn disk_lock(&self, disk_name: &str) -> Option<PathBuf> {
+        std::fs::read_link(
+            self.lima_home
+                .join("_disks")
+                .join(disk_name)
+                .join("in_use_by"),
+        )
+        .ok()
+    }
+
+    /// Install a built-in template, reported by `limactl info` as `template://<name>`
+    pub fn with_template(self, template_name: &str, yaml: &str) -> Self {
+        let path = self.templates_dir().join(format!("{}.yaml", template_name));
+        std::fs::create_dir_all(path.parent().unwrap()).expect("Failed to create templates dir");
+        std::fs::write(path, yaml).expect("Failed to write template");
+        self
+    }
+
+    /// Stands in for `<prefix>/share/lima/templates`
+    fn templates_dir(&self) -> PathBuf {
+        self.lima_home.join("_templates")
+    }
+
+    /// Report `version` from `limactl --version`
+    pub fn with_version(mut self, version: &str) -> Self {
+        self.version = version.to_string();
+        self
+    }
+
+    /// Make the next call of `operation` exit with code 1 and the given stderr
+    pub fn fail_next(&self, operation: &str, stderr: &str) {
+        self.failures
+            .lock()
+            .unwrap()
+            .insert(operation.to_string(), stderr.to_string());
+    }
+
+    /// Make the next call of `operation` run until it is killed
+    pub fn hang_next(&self, operation: &str) {
+        self.hangs.lock().unwrap().insert(operation.to_string());
+    }
+
+    /// Answer `shell(instance, args)` with `stdout` whenever `args` joined by spaces equals `command`
+    pub fn set_shell_response(&self, command: &str, stdout: &str) {
+        self.shell_responses.lock().unwrap().insert(
+            command.to_string(),
+            LimaOutput {
+                code: Some(0),
+                stdout: stdout.to_string(),
+                stderr: String::new(),
+            },
+        );
+    }
+
+    /// Current status of an instance, `None` if it does not exist
+    pub fn status(&self, instance_name: &str) -> Option<String> {
+        self.instances
+            .lock()
+            .unwrap()
+            .get(instance_name)
+            .map(|i| i.status.clone())
+    }
+
+    fn take_failure(&self, operation: &str) -> Option<String> {
+        self.failures.lock().unwrap().remove(operation)
+    }
+
+    fn log_line(level: &str, msg: &str) -> String {
+        format!(
+            "time=\"2025-01-01T00:00:00Z\" level={} msg=\"{}\"\n",
+            level, msg
+        )
+    }
+
+    fn process(code: i32, stdout: String, stderr: String) -> Box<dyn LimaProcess> {
+        Box::new(FakeProcess {
+            stdout: Some(stdout.into_bytes()),
+            stderr: Some(stderr.into_bytes()),
+            code,
+            hang: false,
+        })
+    }
+
+    /// Like `process`, but hangs instead of exiting if `hang_next(operation)` was called
+    fn operation_process(
+        &self,
+        operation: &str,
+        code: i32,
+        stdout: String,
+        stderr: String,
+    ) -> Box<dyn LimaProcess> {
+        Box::new(FakeProcess {
+            stdout: Some(stdout.into_bytes()),
+            stderr: Some(stderr.into_bytes()),
+            code,
+            hang: self.hangs.lock().unwrap().remove(operation),
+        })
+    }
+
+    /// Run `operation` on an existing instance, applying `apply` on success
+    fn transition(
+        &self,
+        operation: &str,
+        instance_name: &str,
+        log: &[&str],
+        apply: impl FnOnce(&mut BTreeMap<String, FakeInstance>),
+    ) -> Result<Box<dyn LimaProcess>, AppError> {
+        if let Some(stderr) = self.take_failure(operation) {
+            return Ok(Self::process(
+                1,
+                String::new(),
+                Self::log_line("fatal", &stderr),
+            ));
+        }
+
+        let mut instances = self.instances.lock().unwrap();
+        if !instances.contains_key(instance_name) {
+            let msg = format!("instance \"{}\" does not exist", instance_name);
+            return Ok(Self::process(
+                1,
+                String::new(),
+                Self::log_line("fatal", &msg),
+            ));
+        }
+        apply(&mut instances);
+
+        let stderr: String = log.iter().map(|msg| Self::log_line("info", msg)).collect();
+        Ok(self.operation_process(operation, 0, String::new(), stderr))
+    }
+}
+
+impl LimaBackend for FakeLimaBackend {
+    fn list(&self) -> BackendFuture<'_, Result<LimaOutput, AppError>> {
+        Box::pin(async move {
+            let instances = self.instances.lock().unwrap();
+            let stdout = instances
+                .iter()
+                .map(|(name, instance)| {
+                    serde_json::json!({
+                        "name": name,
+                        "status": instance.status,
+                        "dir": self.lima_home.join(name),
+                        "arch": std::env::consts::ARCH,
+                        "config": instance.config,
+                    })
+                    .to_string()
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            Ok(LimaOutput {
+                code: Some(0),
+                stdout,
+                stderr: String::new(),
+            })
+        })
+    }
+
+    fn info(&self) -> BackendFuture<'_, Result<LimaOutput, AppError>> {
+        Box::pin(async move {
+            let templates: Vec<_> = ["default", "docker"]
+                .iter()
+                .map(|name| {
+                    serde_json::json!({
+                        "name": name,
+                        "location": self.templates_dir().join(format!("{}.yaml", name)),
+                    })
+                })
+                .collect();
+            let stdout = serde_json::json!({
+                "version": self.version,
+                "limaHome": self.lima_home,
+                "vmTypes": ["qemu", "vz"],
+                "templates": templates,
+                "guestAgents": {
+                    "aarch64": {"location": "/usr/share/lima/lima-guestagent.Linux-aarch64.gz"},
+                    "x86_64": {"location": "/usr/share/lima/lima-guestagent.Linux-x86_64.gz"},
+                },
+            })
+            .to_string();
+            Ok(LimaOutput {
+                code: Some(0),
+                stdout,
+                stderr: String::new(),
+            })
+        })
+    }
+
+    fn version(&self) -> BackendFuture<'_, Result<LimaOutput, AppError>> {
+        Box::pin(async move {
+            Ok(LimaOutput {
+                code: Some(0),
+                stdout: format!("limactl version {}\n", self.version),
+                stderr: String::new(),
+            })
+        })
+    }
+
+    fn lima_home(&self) -> Option<PathBuf> {
+        Some(self.lima_home.clone())
+    }
+
+    fn create(
+        &self,
+        instance_name: &str,
+        config_path: &Path,
+    ) -> Result<Box<dyn LimaProcess>, AppError> {
+        if let Some(stderr) = self.take_failure("create") {
+            return Ok(Self::process(
+                1,
+                String::new(),
+                Self::log_line("fatal", &stderr),
+            ));
+        }
+
+        let mut instances = self.instances.lock().unwrap();
+        if instances.contains_key(instance_name) {
+            let msg = format!("instance \"{}\" already exists", instance_name);
+            return Ok(Self::process(
+                1,
+                String::new(),
+                Self::log_line("fatal", &msg),
+            ));
+        }
+
+        let yaml = std::fs::read_to_string(config_path)
+            .map_err(|e| AppError::io("Failed to read config", e))?;
+        let instance_dir = self.lima_home.join(instance_name);
+        std::fs::create_dir_all(&instance_dir)?;
+        std::fs::write(instance_dir.join("lima.yaml"), &yaml)?;
+
+        instances.insert(
+            instance_name.to_string(),
+            FakeInstance {
+                status: "Stopped".to_string(),
+                config: LimaConfig::from_yaml(&yaml).ok(),
+            },
+        );
+
+        let stderr = [
+            Self::log_line("info", "Attempting to download the image"),
+            Self::log_line(
+                "info",
+                &format!(
+                    "Run `limactl start {}` to start the instance.",
+                    instance_name
+                ),
+            ),
+        ]
+        .concat();
+        Ok(self.operation_process("create", 0, String::new(), stderr))
+    }
+
+    fn start(&self, instance_name: &str) -> Result<Box<dyn LimaProcess>, AppError> {
+        let ready = format!(
+            "READY. Run `limactl shell {}` to open the shell.",
+            instance_name
+        );
+        self.transition(
+            "start",
+            instance_name,
+            &["Starting the instance", "SSH Local Port: 60022", &ready],
+            |instances| {
+                if let Some(instance) = instances.get_mut(instance_name) {
+                    instance.status = "Running".to_string();
+                }
+            },
+        )
+    }
+
+    fn stop(&self, instance_name: &str, force: bool) -> Result<Box<dyn LimaProcess>, AppError> {
+        self.transition(
+            if force { "stop --force" } else { "stop" },
+            instance_name,
+            &[
+                "Sending SIGINT to hostagent process",
+                "Waiting for the host agent and the driver processes to shut down",
+            ],
+            |instances| {
+                if let Some(instance) = instances.get_mut(instance_name) {
+                    instance.status = "Stopped".to_string();
+                }
+            },
+        )
+    }
+
+    fn delete(&self, instance_name: &str, force: bool) -> Result<Box<dyn LimaProcess>, AppError> {
+        let instance_dir = self.lima_home.join(instance_name);
+        self.transition(
+            if force { "delete --force" } else { "delete" },
+            instance_name,
+            &["Deleted instance"],
+            |instances| {
+                instances.remove(instance_name);
+                let _ = std::fs::remove_dir_all(&instance_dir);
+            },
+        )
+    }
+
+    fn clone_instance(
+        &self,
+        source_name: &str,
+        target_name: &str,
+    ) -> Result<Box<dyn LimaProcess>, AppError> {
+        if let Some(stderr) = self.take_failure("clone") {
+            return Ok(Self::process(
+                1,
+                String::new(),
+                Self::log_line("fatal", &stderr),
+            ));
+        }
+
+        let mut instances = self.instances.lock().unwrap();
+        let Some(config) = instances.get(source_name).map(|i| i.config.clone()) else {
+            let msg = format!("instance \"{}\" does not exist", source_name);
+            return Ok(Self::process(
+                1,
+                String::new(),
+                Self::log_line("fatal", &msg),
+            ));
+        };
+        if instances.contains_key(target_name) {
+            let msg = format!("instance \"{}\" already exists", target_name);
+            return Ok(Self::process(
+                1,
+                String::new(),
+                Self::log_line("fatal", &msg),
+            ));
+        }
+
+        let source_dir = self.lima_home.join(source_name);
+        let target_dir = self.lima_home.join(target_name);
+        std::fs::create_dir_all(&target_dir)?;
+        for entry in std::fs::read_dir(&source_dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_file() {
+                std::fs::copy(entry.path(), target_dir.join(entry.file_name()))?;
+            }
+        }
+        instances.insert(
+            target_name.to_string(),
+            FakeInstance {
+                status: "Stopped".to_string(),
+                config,
+            },
+        );
+
+        let stderr = Self::log_line(
+            "info",
+            &format!("Cloned instance to {}", target_dir.display()),
+        );
+        Ok(self.operation_process("clone", 0, String::new(), stderr))
+    }
+
+    fn shell<'a>(
+        &'a self,
+        instance_name: &'a str,
+        args: &'a [&'a str],
+    ) -> BackendFuture<'a, Result<LimaOutput, AppError>> {
+        Box::pin(async move {
+            if self.status(instance_name).as_deref() != Some("Running") {
+                return Ok(LimaOutput {
+                    code: Some(1),
+                    stdout: String::new(),
+                    stderr: format!("instance \"{}\" is not running", instance_name),
+                });
+            }
+            let command = args.join(" ");
+            Ok(self
+                .shell_responses
+                .lock()
+                .unwrap()
+                .get(&command)
+                .cloned()
+                .unwrap_or_else(|| match command.as_str() {
+                    // The SSH reachability check
+                    "true" => LimaOutput {
+                        code: Some(0),
+                        ..Default::default()
+                    },
+                    _ => LimaOutput {
+                        code: Some(127),
+                        stdout: String::new(),
+                        stderr: format!("fake: no response for `{}`", command),
+                    },
+                }))
+        })
+    }
+
+    fn snapshot<'a>(
+        &'a self,
+        action: &'a str,
+        instance_name: &'a str,
+        tag: Option<&'a str>,
+    ) -> BackendFuture<'a, Result<LimaOutput, AppError>> {
+        Box::pin(async move {
+            let fail = |stderr: String| LimaOutput {
+                code: Some(1),
+                stdout: String::new(),
+                stderr: Self::log_line("fatal", &stderr),
+            };
+            if let Some(stderr) = self.take_failure(&format!("snapshot {}", action)) {
+                return Ok(fail(stderr));
+            }
+            if self.status(instance_name).is_none() {
+                return Ok(fail(format!(
+                    "instance \"{}\" does not exist",
+                    instance_name
+                )));
+            }
+
+            let mut snapshots = self.snapshots.lock().unwrap();
+            let tags = snapshots.entry(instance_name.to_string()).or_default();
+            let position = tag.and_then(|tag| tags.iter().position(|t| t == tag));
+            match (action, tag, position) {
+                ("list", _, _) => {
+                    // Same table as `qemu-img snapshot --list`
+                    let mut stdout = "Snapshot list:\nID        TAG               VM SIZE                DATE     VM CLOCK     ICOUNT\n".to_string();
+                    for (i, tag) in tags.iter().enumerate() {
+                        stdout.push_str(&format!(
+                            "{:<9} {:<17} 1.5 MiB 2025-01-01 00:00:{:02} 00:00:00.000          0\n",
+                            i + 1,
+                            tag,
+                            i
+                        ));
+                    }
+                    return Ok(LimaOutput {
+                        code: Some(0),
+                        stdout,
+                        stderr: String::new(),
+                    });
+                }
+                ("create", Some(tag), None) => tags.push(tag.to_string()),
+                ("apply", Some(_), Some(_)) => {}
+                ("delete", Some(_), Some(i)) => {
+                    tags.remove(i);
+                }
+                _ => return Ok(fail(format!("snapshot {} {:?} failed", action, tag))),
+            }
+            Ok(LimaOutput {
+                code: Some(0),
+                stdout: String::new(),
+                stderr: String::new(),
+            })
+        })
+    }
+
+    fn disk<'a>(&'a self, args: &'a [&'a str]) -> BackendFuture<'a, Result<LimaOutput, AppError>> {
+        Box::pin(async move {
+            let output = |code: i32, stdout: String, stderr: String| LimaOutput {
+                code: Some(code),
+                stdout,
+                stderr,
+            };
+            let fail = |msg: String| output(1, String::new(), Self::log_line("fatal", &msg));
+            let action = args.first().copied().unwrap_or_default();
+            if let Some(stderr) = self.take_failure(&format!("disk {}", action)) {
+                return Ok(fail(stderr));
+            }
+            // "10GiB" -> bytes; the fake only understands GiB and MiB
+            let size_arg = || {
+                let size = args.iter().skip_while(|a| **a != "--size").nth(1)?;
+                let (value, unit) = size.split_at(size.find(|c: char| !c.is_ascii_digit())?);
+                let value: u64 = value.parse().ok()?;
+                match unit {
+                    "GiB" => Some(value << 30),
+                    "MiB" => Some(value << 20),
+                    _ => None,
+                }
+            };
+
+            let mut disks = self.disks.lock().unwrap();
+            let name = args.get(1).copied().unwrap_or_default();
+            match action {
+                "list" => {
+                    let stdout = disks
+                        .iter()
+                        .map(|(name, disk)| {
+                            let lock = self.disk_lock(name);
+                            serde_json::json!({
+                                "name": name,
+                                "size": disk.size,
+                                "dir": self.lima_home.join("_disks").join(name),
+                                "instance": lock
+                                    .as_ref()
+                                    .and_then(|dir| dir.file_name())
+                                    .map(|name| name.to_string_lossy().to_string())
+                                    .unwrap_or_default(),
+                                "instanceDir": lock
+                                    .map(|dir| dir.display().to_string())
+                                    .unwrap_or_default(),
+                                "mountPoint": format!("/mnt/lima-{}", name),
+                            })
+                            .to_string()
+                                + "\n"
+                        })
+                        .collect();
+                    return Ok(output(0, stdout, String::new()));
+                }
+                "create" if disks.contains_key(name) => {
+                    return Ok(fail(format!("disk \"{}\" already exists", name)));
+                }
+                "create" => {
+                    let Some(size) = size_arg() else {
+                        return Ok(fail("invalid size".to_string()));
+                    };
+                    std::fs::create_dir_all(self.lima_home.join("_disks").join(name))?;
+                    disks.insert(name.to_string(), FakeDisk { size });
+                }
+                "resize" | "delete" if !disks.contains_key(name) => {
+                    return Ok(fail(format!("disk \"{}\" does not exist", name)));
+                }
+                "resize" | "delete" if self.disk_lock(name).is_some() => {
+                    return Ok(fail(format!("disk \"{}\" is in use", name)));
+                }
+                "resize" => match size_arg() {
+                    Some(size) if size > disks[name].size => {
+                        disks.get_mut(name).unwrap().size = size;
+                    }
+                    _ => return Ok(fail("disk can only be grown".to_string())),
+                },
+                "delete" => {
+                    disks.remove(name);
+                    let _ = std::fs::remove_dir_all(self.lima_home.join("_disks").join(name));
+                }
+                _ => return Ok(fail(format!("unknown disk command {:?}", args))),
+            }
+            Ok(output(0, String::new(), String::new()))
+        })
+    }
+}
+
+impl Drop for FakeLimaBackend {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.lima_home);
+    }
+}
+
+struct FakeProcess {
+    stdout: Option<Vec<u8>>,
+    stderr: Option<Vec<u8>>,
+    code: i32,
+    /// Run until killed instead of exiting with `code`
+    hang: bool,
+}
+
+impl LimaProcess for FakeProcess {
+    fn take_stdout(&mut self) -> Option<BackendStream> {
+        self.stdout
+            .take()
+            .map(|b| Box::new(std::io::Cursor::new(b)) as BackendStream)
+    }
+
+    fn take_stderr(&mut self) -> Option<BackendStream> {
+        self.stderr
+            .take()
+            .map(|b| Box::new(std::io::Cursor::new(b)) as BackendStream)
+    }
+
+    fn wait(&mut self) -> BackendFuture<'_, Result<LimaExitStatus, AppError>> {
+        let code = self.code;
+        let hang = self.hang;
+        Box::pin(async move {
+            if hang {
+                std::future::pending::<()>().await;
+            }
+            Ok(LimaExitStatus { code: Some(code) })
+        })
+    }
+
+    fn kill(&mut self) -> BackendFuture<'_, Result<(), AppError>> {
+        self.hang = false;
+        Box::pin(async move { Ok(()) })
+    }
+}
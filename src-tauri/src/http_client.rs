@@ -0,0 +1,36 @@
+use crate::error::AppError;
+use std::time::Duration;
+
+/// reqwest is built without a bundled TLS provider; use ring like the updater does
+fn ensure_crypto_provider() {
+    if rustls::crypto::CryptoProvider::get_default().is_none() {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+    }
+}
+
+/// GET `url`, turning a non-success status into an error
+pub async fn http_get(url: &str) -> Result<reqwest::Response, AppError> {
+    ensure_crypto_provider();
+    let response = reqwest::get(url)
+        .await
+        .map_err(|e| AppError::io(&format!("Failed to download {}", url), e))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(AppError::command_failed(
+            &format!("GET {}", url),
+            Some(status.as_u16() as i32),
+            status.canonical_reason().unwrap_or_default(),
+        ));
+    }
+    Ok(response)
+}
+
+/// Client for a service forwarded from a guest to localhost, which serves a certificate
+/// of its own (e.g. the cluster CA's), so certificates are not verified
+pub fn local_guest_client(timeout: Duration) -> reqwest::Result<reqwest::Client> {
+    ensure_crypto_provider();
+    reqwest::Client::builder()
+        .tls_danger_accept_invalid_certs(true)
+        .timeout(timeout)
+        .build()
+}
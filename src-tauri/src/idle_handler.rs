@@ -0,0 +1,9 @@
+use crate::error::AppError;
+use crate::idle_service::{self, IdleStatus};
+use tauri::AppHandle;
+
+/// Get each idle policy with the last activity seen on its instance
+#[tauri::command]
+pub async fn get_idle_status_cmd(app: AppHandle) -> Result<Vec<IdleStatus>, AppError> {
+    idle_service::get_idle_status(&app)
+}
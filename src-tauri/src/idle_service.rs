@@ -0,0 +1,397 @@
+use crate::error::AppError;
+use crate::instance_registry_service;
+use crate::lima_backend::{lima_backend, LimaBackend};
+use crate::lima_instance_service;
+use crate::operation_service;
+use crate::settings_service::{self, IdlePolicy};
+use crate::terminal_manager::PtyManager;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Listener, Manager, Runtime};
+
+/// How often running instances with an idle policy are looked at
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Prints "<running containers> <1-minute load average>". Counts both docker and
+/// nerdctl (containerd) containers, whichever the guest has.
+const SAMPLE_SCRIPT: &str = r#"n=0
+for cli in docker nerdctl; do
+  if command -v "$cli" >/dev/null 2>&1; then
+    n=$((n + $("$cli" ps -q 2>/dev/null | wc -l)))
+  fi
+done
+echo "$n $(cut -d' ' -f1 /proc/loadavg)""#;
+
+/// What the monitor last saw of a running instance with an idle policy
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceActivity {
+    /// Milliseconds since the epoch the instance was last in use, or first seen running
+    pub last_active_at: u64,
+    /// Milliseconds since the epoch of the last check
+    pub last_checked_at: u64,
+    /// Open terminal sessions into the instance
+    pub terminals: usize,
+    /// `None` if the guest could not be queried
+    pub containers: Option<u32>,
+    pub load: Option<f64>,
+}
+
+/// An instance's idle policy with what the monitor knows about it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdleStatus {
+    pub instance_name: String,
+    pub policy: IdlePolicy,
+    /// `None` until the instance was seen running
+    pub activity: Option<InstanceActivity>,
+    /// Milliseconds since the epoch the instance gets stopped if it stays idle
+    pub stops_at: Option<u64>,
+}
+
+/// Payload of `instance-idle-stopped`
+#[derive(Clone, Serialize)]
+struct IdleStoppedPayload {
+    instance_name: String,
+    idle_minutes: u32,
+    message: String,
+    /// Id of the stop operation, whose events follow as usual
+    operation_id: String,
+}
+
+/// Managed activity of the monitored instances, keyed by instance name
+#[derive(Default)]
+pub struct IdleMonitorState(Mutex<HashMap<String, InstanceActivity>>);
+
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Parse the output of `SAMPLE_SCRIPT`
+fn parse_sample(stdout: &str) -> Option<(u32, f64)> {
+    let mut parts = stdout.split_whitespace();
+    let containers = parts.next()?.parse().ok()?;
+    let load = parts.next()?.parse().ok()?;
+    Some((containers, load))
+}
+
+impl InstanceActivity {
+    fn new(now: u64) -> Self {
+        Self {
+            last_active_at: now,
+            last_checked_at: now,
+            terminals: 0,
+            containers: None,
+            load: None,
+        }
+    }
+
+    /// Record a check; an instance that could not be queried counts as in use.
+    /// Returns whether it has now been idle for the policy's time.
+    fn record(
+        &mut self,
+        terminals: usize,
+        guest: Option<(u32, f64)>,
+        policy: &IdlePolicy,
+        now: u64,
+    ) -> bool {
+        self.last_checked_at = now;
+        self.terminals = terminals;
+        self.containers = guest.map(|(containers, _)| containers);
+        self.load = guest.map(|(_, load)| load);
+
+        let idle = terminals == 0
+            && guest.is_some_and(|(containers, load)| containers == 0 && load <= policy.max_load);
+        if !idle {
+            self.last_active_at = now;
+        }
+        now.saturating_sub(self.last_active_at) >= idle_millis(policy)
+    }
+}
+
+fn idle_millis(policy: &IdlePolicy) -> u64 {
+    u64::from(policy.idle_minutes) * 60 * 1000
+}
+
+async fn sample_guest(backend: &dyn LimaBackend, instance_name: &str) -> Option<(u32, f64)> {
+    match backend
+        .shell(instance_name, &["sh", "-c", SAMPLE_SCRIPT])
+        .await
+    {
+        Ok(output) if output.success() => parse_sample(&output.stdout),
+        Ok(output) => {
+            log::debug!(
+                "Idle check of '{}' failed: {}",
+                instance_name,
+                output.stderr
+            );
+            None
+        }
+        Err(e) => {
+            log::debug!("Idle check of '{}' failed: {}", instance_name, e);
+            None
+        }
+    }
+}
+
+/// Check the running instances that have an idle policy every minute and stop the
+/// ones idle for long enough, and keep policies attached to their instance across
+/// renames and deletes. Called from the `setup` hook.
+pub fn start_idle_monitor<R: Runtime>(app: &AppHandle<R>) {
+    app.manage(IdleMonitorState::default());
+
+    let handle = app.clone();
+    app.listen_any("lima-instance-rename-success", move |event| {
+        #[derive(serde::Deserialize)]
+        struct Renamed {
+            instance_name: String,
+            source_name: String,
+        }
+        if let Ok(renamed) = serde_json::from_str::<Renamed>(event.payload()) {
+            if let Err(e) = settings_service::rename_idle_policy(
+                &handle,
+                &renamed.source_name,
+                &renamed.instance_name,
+            ) {
+                log::warn!(
+                    "Failed to move idle policy of '{}': {}",
+                    renamed.source_name,
+                    e
+                );
+            }
+        }
+    });
+
+    let handle = app.clone();
+    app.listen_any("lima-instance-delete-success", move |event| {
+        #[derive(serde::Deserialize)]
+        struct Deleted {
+            instance_name: String,
+        }
+        if let Ok(deleted) = serde_json::from_str::<Deleted>(event.payload()) {
+            if settings_service::load_idle_policies(&handle).contains_key(&deleted.instance_name) {
+                let _ = settings_service::save_idle_policy(&handle, &deleted.instance_name, None);
+            }
+        }
+    });
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            let policies = settings_service::load_idle_policies(&app);
+            for (instance_name, policy) in check_idle_instances(&app, &policies, now_millis()).await
+            {
+                stop_idle_instance(&app, &instance_name, &policy).await;
+            }
+        }
+    });
+}
+
+/// Sample the running instances that have a policy at `now` (milliseconds since the
+/// epoch) and return the ones idle for long enough, which are forgotten so their clock
+/// starts over should the stop fail
+async fn check_idle_instances<R: Runtime>(
+    app: &AppHandle<R>,
+    policies: &BTreeMap<String, IdlePolicy>,
+    now: u64,
+) -> Vec<(String, IdlePolicy)> {
+    let Some(state) = app.try_state::<IdleMonitorState>() else {
+        return Vec::new();
+    };
+    if policies.is_empty() {
+        if let Ok(mut activity) = state.0.lock() {
+            activity.clear();
+        }
+        return Vec::new();
+    }
+
+    let backend = lima_backend(app);
+    let instances = match instance_registry_service::get_all_lima_instances(backend.as_ref()).await
+    {
+        Ok(instances) => instances,
+        Err(e) => {
+            log::warn!("Idle check skipped, failed to list instances: {}", e);
+            return Vec::new();
+        }
+    };
+    // Instances that are busy, stopped or without a policy start over next time
+    let monitored: Vec<(String, IdlePolicy)> = instances
+        .into_iter()
+        .filter(|i| i.status == "Running")
+        .filter(|i| operation_service::current_operation(app, &i.name).is_none())
+        .filter_map(|i| Some((i.name.clone(), *policies.get(&i.name)?)))
+        .collect();
+    if let Ok(mut activity) = state.0.lock() {
+        activity.retain(|name, _| monitored.iter().any(|(n, _)| n == name));
+    }
+
+    let mut idle = Vec::new();
+    for (instance_name, policy) in monitored {
+        let terminals = app
+            .try_state::<PtyManager>()
+            .map(|manager| manager.instance_session_count(&instance_name))
+            .unwrap_or(0);
+        // Terminals are enough to tell the instance is in use
+        let guest = if terminals == 0 {
+            sample_guest(backend.as_ref(), &instance_name).await
+        } else {
+            None
+        };
+
+        let Ok(mut activity) = state.0.lock() else {
+            continue;
+        };
+        let idle_long_enough = activity
+            .entry(instance_name.clone())
+            .or_insert_with(|| InstanceActivity::new(now))
+            .record(terminals, guest, &policy, now);
+        if idle_long_enough {
+            activity.remove(&instance_name);
+            idle.push((instance_name, policy));
+        }
+    }
+    idle
+}
+
+/// Stop through the regular stop operation and tell the UI why
+async fn stop_idle_instance<R: Runtime>(
+    app: &AppHandle<R>,
+    instance_name: &str,
+    policy: &IdlePolicy,
+) {
+    log::info!(
+        "Stopping '{}' after {} idle minutes",
+        instance_name,
+        policy.idle_minutes
+    );
+    match lima_instance_service::stop_lima_instance(app.clone(), instance_name.to_string(), false)
+        .await
+    {
+        Ok(operation_id) => {
+            let _ = app.emit(
+                "instance-idle-stopped",
+                IdleStoppedPayload {
+                    instance_name: instance_name.to_string(),
+                    idle_minutes: policy.idle_minutes,
+                    message: format!(
+                        "Stopped '{}' after {} minutes without terminals, containers or load",
+                        instance_name, policy.idle_minutes
+                    ),
+                    operation_id,
+                },
+            );
+        }
+        Err(e) => log::warn!("Failed to stop idle instance '{}': {}", instance_name, e),
+    }
+}
+
+/// Every idle policy with the monitored activity of its instance
+pub fn get_idle_status<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<IdleStatus>, AppError> {
+    let activity = match app.try_state::<IdleMonitorState>() {
+        Some(state) => state
+            .0
+            .lock()
+            .map_err(|e| AppError::io("Failed to lock idle monitor state", e))?
+            .clone(),
+        None => HashMap::new(),
+    };
+    Ok(settings_service::load_idle_policies(app)
+        .into_iter()
+        .map(|(instance_name, policy)| {
+            let activity = activity.get(&instance_name).cloned();
+            IdleStatus {
+                stops_at: activity
+                    .as_ref()
+                    .map(|a| a.last_active_at + idle_millis(&policy)),
+                instance_name,
+                policy,
+                activity,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_lima_backend::FakeLimaBackend;
+    use crate::lima_backend::LimaBackendState;
+    use crate::operation_service::OperationRegistry;
+    use std::sync::Arc;
+
+    const MINUTE: u64 = 60 * 1000;
+
+    #[test]
+    fn test_parse_sample() {
+        assert_eq!(parse_sample("0 0.08\n"), Some((0, 0.08)));
+        assert_eq!(parse_sample("3 1.50"), Some((3, 1.5)));
+        assert_eq!(parse_sample("sh: docker: not found"), None);
+    }
+
+    #[test]
+    fn test_activity_resets_the_idle_clock() {
+        let policy = IdlePolicy {
+            idle_minutes: 10,
+            max_load: 0.5,
+        };
+        let mut activity = InstanceActivity::new(0);
+
+        assert!(!activity.record(0, Some((0, 0.1)), &policy, 5 * MINUTE));
+        // A terminal, a container, high load or an unreachable guest each count as use
+        assert!(!activity.record(1, None, &policy, 8 * MINUTE));
+        assert!(!activity.record(0, Some((2, 0.1)), &policy, 12 * MINUTE));
+        assert!(!activity.record(0, Some((0, 0.9)), &policy, 14 * MINUTE));
+        assert!(!activity.record(0, None, &policy, 16 * MINUTE));
+        assert_eq!(activity.last_active_at, 16 * MINUTE);
+
+        assert!(!activity.record(0, Some((0, 0.5)), &policy, 25 * MINUTE));
+        assert!(activity.record(0, Some((0, 0.0)), &policy, 26 * MINUTE));
+    }
+
+    #[tokio::test]
+    async fn test_check_returns_instances_idle_long_enough() {
+        let backend = Arc::new(
+            FakeLimaBackend::new()
+                .with_instance("idle", "Running")
+                .with_instance("busy", "Running")
+                .with_instance("unmanaged", "Running"),
+        );
+        backend.set_shell_response(&format!("sh -c {}", SAMPLE_SCRIPT), "0 0.01\n");
+        let app = tauri::test::mock_app();
+        app.manage(LimaBackendState(backend.clone()));
+        app.manage(OperationRegistry::default());
+        app.manage(IdleMonitorState::default());
+
+        let policy = IdlePolicy {
+            idle_minutes: 1,
+            max_load: 0.2,
+        };
+        // The fake answers every instance alike, so "busy" differs by its longer idle time
+        let patient = IdlePolicy {
+            idle_minutes: 5,
+            ..policy
+        };
+        let policies =
+            BTreeMap::from([("idle".to_string(), policy), ("busy".to_string(), patient)]);
+        let start = 100 * MINUTE;
+        // The first check starts the clock
+        assert!(check_idle_instances(app.handle(), &policies, start)
+            .await
+            .is_empty());
+        assert_eq!(app.state::<IdleMonitorState>().0.lock().unwrap().len(), 2);
+
+        assert_eq!(
+            check_idle_instances(app.handle(), &policies, start + 2 * MINUTE).await,
+            [("idle".to_string(), policy)]
+        );
+        let activity = app.state::<IdleMonitorState>().0.lock().unwrap().clone();
+        assert!(!activity.contains_key("idle"));
+        assert_eq!(activity["busy"].containers, Some(0));
+    }
+}
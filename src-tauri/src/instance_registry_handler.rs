@@ -1,14 +1,16 @@
+use crate::error::AppError;
 use crate::instance_registry_service::{
     get_all_lima_instances, get_disk_usage, DiskUsage, LimaInstance,
 };
+use crate::lima_backend::lima_backend;
 use tauri::AppHandle;
 
 /// Get all registered ZeroMa instances with their current status
 /// Returns instances directly from limactl list --json (the source of truth)
 #[tauri::command]
-pub async fn get_all_lima_instances_cmd(_app: AppHandle) -> Result<Vec<LimaInstance>, String> {
+pub async fn get_all_lima_instances_cmd(app: AppHandle) -> Result<Vec<LimaInstance>, AppError> {
     // Get instances from limactl (source of truth)
-    let instances = get_all_lima_instances().await?;
+    let instances = get_all_lima_instances(lima_backend(&app).as_ref()).await?;
 
     Ok(instances)
 }
@@ -16,37 +18,50 @@ pub async fn get_all_lima_instances_cmd(_app: AppHandle) -> Result<Vec<LimaInsta
 /// Check if an instance is registered
 #[tauri::command]
 pub async fn is_instance_registered_cmd(
-    _app: AppHandle,
+    app: AppHandle,
     instance_name: String,
-) -> Result<bool, String> {
-    let instances = get_all_lima_instances().await?;
+) -> Result<bool, AppError> {
+    let instances = get_all_lima_instances(lima_backend(&app).as_ref()).await?;
     Ok(instances.iter().any(|inst| inst.name == instance_name))
 }
 
 /// Get disk usage for a Lima instance
 #[tauri::command]
-pub async fn get_instance_disk_usage_cmd(instance_name: String) -> Result<DiskUsage, String> {
-    get_disk_usage(&instance_name).await
+pub async fn get_instance_disk_usage_cmd(
+    app: AppHandle,
+    instance_name: String,
+) -> Result<DiskUsage, AppError> {
+    get_disk_usage(lima_backend(&app).as_ref(), &instance_name).await
 }
 
 /// Get the internal IP address of a Lima instance
 #[tauri::command]
 pub async fn get_instance_ip_cmd(
+    app: AppHandle,
     instance_name: String,
-) -> Result<Vec<crate::instance_registry_service::NetworkInterface>, String> {
-    crate::instance_registry_service::get_instance_ip(&instance_name).await
+) -> Result<Vec<crate::instance_registry_service::NetworkInterface>, AppError> {
+    crate::instance_registry_service::get_instance_ip(lima_backend(&app).as_ref(), &instance_name)
+        .await
 }
 
 /// Get instance uptime
 #[tauri::command]
-pub async fn get_instance_uptime_cmd(instance_name: String) -> Result<String, String> {
-    crate::instance_registry_service::get_uptime(&instance_name).await
+pub async fn get_instance_uptime_cmd(
+    app: AppHandle,
+    instance_name: String,
+) -> Result<String, AppError> {
+    crate::instance_registry_service::get_uptime(lima_backend(&app).as_ref(), &instance_name).await
 }
 
 /// Get rich guest diagnostics (OS, Kernel)
 #[tauri::command]
 pub async fn get_instance_guest_diagnostics_cmd(
+    app: AppHandle,
     instance_name: String,
-) -> Result<crate::instance_registry_service::GuestDiagnostics, String> {
-    crate::instance_registry_service::get_guest_diagnostics(&instance_name).await
+) -> Result<crate::instance_registry_service::GuestDiagnostics, AppError> {
+    crate::instance_registry_service::get_guest_diagnostics(
+        lima_backend(&app).as_ref(),
+        &instance_name,
+    )
+    .await
 }
@@ -1,7 +1,7 @@
-use crate::find_lima_executable;
+use crate::error::AppError;
+use crate::lima_backend::LimaBackend;
 use crate::lima_config::LimaConfig;
 use serde::{Deserialize, Serialize};
-use tokio::process::Command;
 
 /// Kubernetes information
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -77,26 +77,26 @@ pub struct GuestDiagnostics {
 }
 
 /// Get all Lima instances from limactl list --json (async)
-async fn get_lima_instances() -> Result<Vec<LimaInstance>, String> {
-    let lima_cmd = find_lima_executable()
-        .ok_or_else(|| "Lima (limactl) not found. Please ensure lima is installed.".to_string())?;
-
-    let output = Command::new(&lima_cmd)
-        .args(["list", "--format", "json"])
-        .output()
-        .await
-        .map_err(|e| format!("Failed to run limactl list: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Failed to list Lima instances: {}", stderr));
+async fn get_lima_instances(backend: &dyn LimaBackend) -> Result<Vec<LimaInstance>, AppError> {
+    let output = backend.list().await?;
+
+    if !output.success() {
+        return Err(AppError::command_failed(
+            "limactl list",
+            output.code,
+            &output.stderr,
+        ));
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(parse_lima_list_output(&output.stdout))
+}
+
+/// Parse `limactl list --format json` output into instances
+fn parse_lima_list_output(stdout: &str) -> Vec<LimaInstance> {
     let stdout = stdout.trim();
 
     if stdout.is_empty() {
-        return Ok(vec![]);
+        return vec![];
     }
 
     // limactl list --format json returns newline-delimited JSON (NDJSON)
@@ -160,12 +160,14 @@ async fn get_lima_instances() -> Result<Vec<LimaInstance>, String> {
         }
     }
 
-    Ok(instances)
+    instances
 }
 
 /// Get all Lima instances from limactl list --json (the source of truth)
-pub async fn get_all_lima_instances() -> Result<Vec<LimaInstance>, String> {
-    let mut instances = get_lima_instances().await?;
+pub async fn get_all_lima_instances(
+    backend: &dyn LimaBackend,
+) -> Result<Vec<LimaInstance>, AppError> {
+    let mut instances = get_lima_instances(backend).await?;
 
     // Sort instances by name
     instances.sort_by(|a, b| a.name.cmp(&b.name));
@@ -174,31 +176,24 @@ pub async fn get_all_lima_instances() -> Result<Vec<LimaInstance>, String> {
 }
 
 /// Get disk usage for a Lima instance by running df inside the instance (async)
-pub async fn get_disk_usage(instance_name: &str) -> Result<DiskUsage, String> {
-    let lima_cmd = find_lima_executable()
-        .ok_or_else(|| "Lima (limactl) not found. Please ensure lima is installed.".to_string())?;
-
+pub async fn get_disk_usage(
+    backend: &dyn LimaBackend,
+    instance_name: &str,
+) -> Result<DiskUsage, AppError> {
     // Use --output for reliable parsing, avoiding locale and formatting issues
     // -BG ensures sizes are in gigabytes for consistency
-    let output = Command::new(&lima_cmd)
-        .args([
-            "shell",
+    let output = backend
+        .shell(
             instance_name,
-            "df",
-            "-BG",
-            "--output=size,used,avail,pcent",
-            "/",
-        ])
-        .output()
-        .await
-        .map_err(|e| format!("Failed to run df command: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Failed to get disk usage: {}", stderr));
+            &["df", "-BG", "--output=size,used,avail,pcent", "/"],
+        )
+        .await?;
+
+    if !output.success() {
+        return Err(AppError::command_failed("df", output.code, &output.stderr));
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stdout = output.stdout;
     // Parse df output, expecting two lines: header and data
     // Example output:
     //   Size  Used Avail Use%
@@ -206,7 +201,9 @@ pub async fn get_disk_usage(instance_name: &str) -> Result<DiskUsage, String> {
     let lines: Vec<&str> = stdout.lines().collect();
 
     if lines.len() < 2 {
-        return Err(format!("Unexpected df output format: {}", stdout));
+        return Err(AppError::ParseError {
+            message: format!("Unexpected df output format: {}", stdout),
+        });
     }
 
     // Get the data line (skip header)
@@ -214,10 +211,12 @@ pub async fn get_disk_usage(instance_name: &str) -> Result<DiskUsage, String> {
     let parts: Vec<&str> = data_line.split_whitespace().collect();
 
     if parts.len() < 4 {
-        return Err(format!(
-            "Failed to parse disk usage: expected 4 columns, got {}",
-            parts.len()
-        ));
+        return Err(AppError::ParseError {
+            message: format!(
+                "Failed to parse disk usage: expected 4 columns, got {}",
+                parts.len()
+            ),
+        });
     }
 
     Ok(DiskUsage {
@@ -229,35 +228,29 @@ pub async fn get_disk_usage(instance_name: &str) -> Result<DiskUsage, String> {
 }
 
 /// Get the internal IP address of a Lima instance (async)
-pub async fn get_instance_ip(instance_name: &str) -> Result<Vec<NetworkInterface>, String> {
-    let lima_cmd = find_lima_executable()
-        .ok_or_else(|| "Lima (limactl) not found. Please ensure lima is installed.".to_string())?;
-
+pub async fn get_instance_ip(
+    backend: &dyn LimaBackend,
+    instance_name: &str,
+) -> Result<Vec<NetworkInterface>, AppError> {
     // Use `ip -o addr show` to get interface name + IP in one shot.
     // Each line: "idx: <iface>  inet <ip>/<prefix> ..."
-    let output = Command::new(&lima_cmd)
-        .args([
-            "shell",
+    let output = backend
+        .shell(
             instance_name,
-            "ip",
-            "-o",
-            "-4",
-            "addr",
-            "show",
-            "scope",
-            "global",
-        ])
-        .output()
-        .await
-        .map_err(|e| format!("Failed to run ip command: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Failed to get instance IP: {}", stderr));
+            &["ip", "-o", "-4", "addr", "show", "scope", "global"],
+        )
+        .await?;
+
+    if !output.success() {
+        return Err(AppError::command_failed(
+            "ip addr",
+            output.code,
+            &output.stderr,
+        ));
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let interfaces: Vec<NetworkInterface> = stdout
+    let interfaces: Vec<NetworkInterface> = output
+        .stdout
         .lines()
         .filter_map(|line| {
             // Format: "2: eth0    inet 192.168.5.15/24 brd ..."
@@ -276,46 +269,36 @@ pub async fn get_instance_ip(instance_name: &str) -> Result<Vec<NetworkInterface
 }
 
 /// Get instance uptime (async)
-pub async fn get_uptime(instance_name: &str) -> Result<String, String> {
-    let lima_cmd = find_lima_executable()
-        .ok_or_else(|| "Lima (limactl) not found. Please ensure lima is installed.".to_string())?;
-
-    let output = Command::new(&lima_cmd)
-        .args(["shell", instance_name, "uptime", "-p"])
-        .output()
-        .await
-        .map_err(|e| format!("Failed to run uptime command: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Failed to get uptime: {}", stderr));
+pub async fn get_uptime(
+    backend: &dyn LimaBackend,
+    instance_name: &str,
+) -> Result<String, AppError> {
+    let output = backend.shell(instance_name, &["uptime", "-p"]).await?;
+
+    if !output.success() {
+        return Err(AppError::command_failed(
+            "uptime",
+            output.code,
+            &output.stderr,
+        ));
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    Ok(stdout.trim().replace("up ", "").to_string())
+    Ok(output.stdout.trim().replace("up ", "").to_string())
 }
 
 /// Get guest diagnostics like OS and Kernel (async)
-pub async fn get_guest_diagnostics(instance_name: &str) -> Result<GuestDiagnostics, String> {
-    let lima_cmd = find_lima_executable()
-        .ok_or_else(|| "Lima (limactl) not found. Please ensure lima is installed.".to_string())?;
-
+pub async fn get_guest_diagnostics(
+    backend: &dyn LimaBackend,
+    instance_name: &str,
+) -> Result<GuestDiagnostics, AppError> {
     // Get OS info
-    let os_output = Command::new(&lima_cmd)
-        .args([
-            "shell",
-            instance_name,
-            "grep",
-            "^PRETTY_NAME=",
-            "/etc/os-release",
-        ])
-        .output()
-        .await
-        .map_err(|e| format!("Failed to run os-release command: {}", e))?;
-
-    let os_pretty_name = if os_output.status.success() {
-        let stdout = String::from_utf8_lossy(&os_output.stdout);
-        stdout
+    let os_output = backend
+        .shell(instance_name, &["grep", "^PRETTY_NAME=", "/etc/os-release"])
+        .await?;
+
+    let os_pretty_name = if os_output.success() {
+        os_output
+            .stdout
             .trim()
             .replace("PRETTY_NAME=", "")
             .replace("\"", "")
@@ -325,15 +308,10 @@ pub async fn get_guest_diagnostics(instance_name: &str) -> Result<GuestDiagnosti
     };
 
     // Get Kernel info
-    let kernel_output = Command::new(&lima_cmd)
-        .args(["shell", instance_name, "uname", "-r"])
-        .output()
-        .await
-        .map_err(|e| format!("Failed to run uname command: {}", e))?;
-
-    let kernel_version = if kernel_output.status.success() {
-        let stdout = String::from_utf8_lossy(&kernel_output.stdout);
-        stdout.trim().to_string()
+    let kernel_output = backend.shell(instance_name, &["uname", "-r"]).await?;
+
+    let kernel_version = if kernel_output.success() {
+        kernel_output.stdout.trim().to_string()
     } else {
         "Unknown Kernel".to_string()
     };
@@ -343,3 +321,44 @@ pub async fn get_guest_diagnostics(instance_name: &str) -> Result<GuestDiagnosti
         kernel_version,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_lima_backend::FakeLimaBackend;
+
+    #[test]
+    fn test_parse_lima_list_output() {
+        let stdout = r#"
+{"name":"k0s","status":"Running","dir":"/Users/me/.lima/k0s","arch":"aarch64","limaVersion":"2.0.0","sshLocalPort":60022,"config":{"cpus":4,"memory":"8GiB","disk":"40GiB"}}
+not json
+{"name":"docker","status":"Stopped"}
+"#;
+        let instances = parse_lima_list_output(stdout);
+        assert_eq!(instances.len(), 2);
+        assert_eq!(instances[0].name, "k0s");
+        assert_eq!(instances[0].cpus, 4);
+        assert_eq!(instances[0].memory, "8GiB");
+        assert_eq!(instances[0].ssh_local_port, Some(60022));
+        assert_eq!(instances[1].status, "Stopped");
+        assert_eq!(instances[1].disk, "-");
+    }
+
+    #[tokio::test]
+    async fn test_registry_queries_against_fake_backend() {
+        let backend = FakeLimaBackend::new()
+            .with_instance("b", "Stopped")
+            .with_instance("a", "Running");
+        backend.set_shell_response("uptime -p", "up 2 hours, 5 minutes\n");
+
+        let instances = get_all_lima_instances(&backend).await.unwrap();
+        let names: Vec<_> = instances.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, vec!["a", "b"]);
+
+        assert_eq!(
+            get_uptime(&backend, "a").await.unwrap(),
+            "2 hours, 5 minutes"
+        );
+        assert!(get_uptime(&backend, "b").await.is_err());
+    }
+}
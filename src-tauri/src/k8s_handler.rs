@@ -1,16 +1,25 @@
+use crate::error::AppError;
 use crate::k8s_service::{check_k0s_available, get_k8s_pods, get_k8s_services, Pod, Service};
+use crate::lima_backend::lima_backend;
+use tauri::AppHandle;
 
 #[tauri::command]
-pub async fn check_k0s_available_cmd(instance_name: String) -> Result<bool, String> {
-    check_k0s_available(&instance_name)
+pub async fn check_k0s_available_cmd(
+    app: AppHandle,
+    instance_name: String,
+) -> Result<bool, AppError> {
+    check_k0s_available(lima_backend(&app).as_ref(), &instance_name).await
 }
 
 #[tauri::command]
-pub async fn get_k8s_pods_cmd(instance_name: String) -> Result<Vec<Pod>, String> {
-    get_k8s_pods(&instance_name).map_err(|e| e.to_string())
+pub async fn get_k8s_pods_cmd(app: AppHandle, instance_name: String) -> Result<Vec<Pod>, AppError> {
+    get_k8s_pods(lima_backend(&app).as_ref(), &instance_name).await
 }
 
 #[tauri::command]
-pub async fn get_k8s_services_cmd(instance_name: String) -> Result<Vec<Service>, String> {
-    get_k8s_services(&instance_name).map_err(|e| e.to_string())
+pub async fn get_k8s_services_cmd(
+    app: AppHandle,
+    instance_name: String,
+) -> Result<Vec<Service>, AppError> {
+    get_k8s_services(lima_backend(&app).as_ref(), &instance_name).await
 }
@@ -1,6 +1,6 @@
-use crate::find_lima_executable;
+use crate::error::AppError;
+use crate::lima_backend::LimaBackend;
 use serde::{Deserialize, Serialize};
-use std::process::Command;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PodList {
@@ -131,24 +131,27 @@ pub struct LoadBalancerIngress {
     pub hostname: Option<String>,
 }
 
-pub fn check_k0s_available(instance_name: &str) -> Result<bool, String> {
-    let lima_cmd = find_lima_executable().ok_or_else(|| "Lima (limactl) not found".to_string())?;
-    let output = Command::new(&lima_cmd)
-        .args([
-            "shell",
+pub async fn check_k0s_available(
+    backend: &dyn LimaBackend,
+    instance_name: &str,
+) -> Result<bool, AppError> {
+    let output = backend
+        .shell(
             instance_name,
-            "sh",
-            "-c",
-            "command -v k0s >/dev/null 2>&1 || command -v kubectl >/dev/null 2>&1",
-        ])
-        .output()
-        .map_err(|e| format!("Failed to execute limactl shell: {}", e))?;
-    Ok(output.status.success())
+            &[
+                "sh",
+                "-c",
+                "command -v k0s >/dev/null 2>&1 || command -v kubectl >/dev/null 2>&1",
+            ],
+        )
+        .await?;
+    Ok(output.success())
 }
 
-pub fn get_k8s_pods(instance_name: &str) -> Result<Vec<Pod>, String> {
-    let lima_cmd = find_lima_executable().ok_or_else(|| "Lima (limactl) not found".to_string())?;
-
+pub async fn get_k8s_pods(
+    backend: &dyn LimaBackend,
+    instance_name: &str,
+) -> Result<Vec<Pod>, AppError> {
     // Dynamic detection of k0s vs standard kubectl
     // We use a shell script inside the Lima instance to determine which command to run
     let script = r#"
@@ -159,28 +162,29 @@ else
 fi
 "#;
 
-    let output = Command::new(&lima_cmd)
-        .args(["shell", instance_name, "sh", "-c", script])
-        .output()
-        .map_err(|e| format!("Failed to execute limactl shell: {}", e))?;
+    let output = backend.shell(instance_name, &["sh", "-c", script]).await?;
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Command failed: {}", stderr));
+    if !output.success() {
+        return Err(AppError::command_failed(
+            "kubectl",
+            output.code,
+            &output.stderr,
+        ));
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stdout = output.stdout;
 
     // Parse JSON output
     let pod_list: PodList = serde_json::from_str(&stdout)
-        .map_err(|e| format!("Failed to parse kubectl JSON output: {}", e))?;
+        .map_err(|e| AppError::parse("Failed to parse kubectl JSON output", e))?;
 
     Ok(pod_list.items)
 }
 
-pub fn get_k8s_services(instance_name: &str) -> Result<Vec<Service>, String> {
-    let lima_cmd = find_lima_executable().ok_or_else(|| "Lima (limactl) not found".to_string())?;
-
+pub async fn get_k8s_services(
+    backend: &dyn LimaBackend,
+    instance_name: &str,
+) -> Result<Vec<Service>, AppError> {
     let script = r#"
 if command -v k0s >/dev/null 2>&1; then
     k0s kubectl get services -A -o json
@@ -189,20 +193,20 @@ else
 fi
 "#;
 
-    let output = Command::new(&lima_cmd)
-        .args(["shell", instance_name, "sh", "-c", script])
-        .output()
-        .map_err(|e| format!("Failed to execute limactl shell: {}", e))?;
+    let output = backend.shell(instance_name, &["sh", "-c", script]).await?;
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Command failed: {}", stderr));
+    if !output.success() {
+        return Err(AppError::command_failed(
+            "kubectl",
+            output.code,
+            &output.stderr,
+        ));
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stdout = output.stdout;
 
     let service_list: ServiceList = serde_json::from_str(&stdout)
-        .map_err(|e| format!("Failed to parse kubectl JSON output: {}", e))?;
+        .map_err(|e| AppError::parse("Failed to parse kubectl JSON output", e))?;
 
     Ok(service_list.items)
 }
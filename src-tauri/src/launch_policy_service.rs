@@ -0,0 +1,264 @@
+use crate::instance_registry_service;
+use crate::lima_backend::lima_backend;
+use crate::lima_instance_service;
+use crate::operation_service::{self, OperationKind, OperationOutcome, OutcomeWatcher};
+use crate::settings_service::{self, LaunchPolicy};
+use std::time::Duration;
+use tauri::{AppHandle, Listener, Runtime};
+
+/// Start the instances whose policy says so, and keep policies attached to their
+/// instance across renames and deletes. Called from the `setup` hook.
+pub fn setup<R: Runtime>(app: &AppHandle<R>) {
+    let handle = app.clone();
+    app.listen_any("lima-instance-rename-success", move |event| {
+        #[derive(serde::Deserialize)]
+        struct Renamed {
+            instance_name: String,
+            source_name: String,
+        }
+        if let Ok(renamed) = serde_json::from_str::<Renamed>(event.payload()) {
+            if let Err(e) = settings_service::rename_launch_policy(
+                &handle,
+                &renamed.source_name,
+                &renamed.instance_name,
+            ) {
+                log::warn!(
+                    "Failed to move launch policy of '{}': {}",
+                    renamed.source_name,
+                    e
+                );
+            }
+        }
+    });
+
+    let handle = app.clone();
+    app.listen_any("lima-instance-delete-success", move |event| {
+        #[derive(serde::Deserialize)]
+        struct Deleted {
+            instance_name: String,
+        }
+        if let Ok(deleted) = serde_json::from_str::<Deleted>(event.payload()) {
+            if settings_service::load_launch_policies(&handle).contains_key(&deleted.instance_name)
+            {
+                let _ = settings_service::save_launch_policy(
+                    &handle,
+                    &deleted.instance_name,
+                    LaunchPolicy::default(),
+                );
+            }
+        }
+    });
+
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let names = policy_instances(&handle, |policy| policy.start_on_launch);
+        if !names.is_empty() {
+            log::info!("Starting instances on launch: {}", names.join(", "));
+            start_instances(&handle, &names).await;
+        }
+    });
+}
+
+/// Time a stop on quit gets after its escalation to `limactl stop --force`
+const QUIT_FORCE_STOP_GRACE: Duration = Duration::from_secs(15);
+
+/// Stop the running instances whose policy says so and wait until they are down, at most
+/// the lifecycle stop timeout plus a grace period for the forced stop.
+/// Called from the tray "quit" path before the app exits.
+pub async fn stop_instances_on_quit<R: Runtime>(app: &AppHandle<R>) {
+    let names = policy_instances(app, |policy| policy.stop_on_quit);
+    if names.is_empty() {
+        return;
+    }
+    log::info!("Stopping instances on quit: {}", names.join(", "));
+    let deadline =
+        Duration::from_secs(settings_service::load_lifecycle_settings(app).stop_timeout_secs)
+            + QUIT_FORCE_STOP_GRACE;
+    let still_stopping = stop_instances_within(app, &names, deadline).await;
+    if !still_stopping.is_empty() {
+        log::warn!(
+            "Quitting after {}s while still stopping: {}",
+            deadline.as_secs(),
+            still_stopping.join(", ")
+        );
+    }
+}
+
+/// Stop the instances, giving up on waiting after `deadline`.
+/// Returns the names of the instances that were still stopping by then.
+async fn stop_instances_within<R: Runtime>(
+    app: &AppHandle<R>,
+    names: &[String],
+    deadline: Duration,
+) -> Vec<String> {
+    if tokio::time::timeout(deadline, stop_instances(app, names))
+        .await
+        .is_ok()
+    {
+        return Vec::new();
+    }
+    operation_service::list_operations(app)
+        .into_iter()
+        .filter(|operation| {
+            operation.kind == OperationKind::Stop && names.contains(&operation.instance_name)
+        })
+        .map(|operation| operation.instance_name)
+        .collect()
+}
+
+fn policy_instances<R: Runtime>(
+    app: &AppHandle<R>,
+    applies: impl Fn(&LaunchPolicy) -> bool,
+) -> Vec<String> {
+    settings_service::load_launch_policies(app)
+        .into_iter()
+        .filter(|(_, policy)| applies(policy))
+        .map(|(name, _)| name)
+        .collect()
+}
+
+/// Names among `names` of existing instances whose status is (or is not) "Running"
+async fn instances_with_status<R: Runtime>(
+    app: &AppHandle<R>,
+    names: &[String],
+    running: bool,
+) -> Vec<String> {
+    let instances =
+        match instance_registry_service::get_all_lima_instances(lima_backend(app).as_ref()).await {
+            Ok(instances) => instances,
+            Err(e) => {
+                log::warn!(
+                    "Launch policies not applied, failed to list instances: {}",
+                    e
+                );
+                return Vec::new();
+            }
+        };
+    names
+        .iter()
+        .filter(|name| match instances.iter().find(|i| &i.name == *name) {
+            Some(instance) => (instance.status == "Running") == running,
+            None => {
+                log::warn!("Launch policy for missing instance '{}' skipped", name);
+                false
+            }
+        })
+        .cloned()
+        .collect()
+}
+
+/// Start stopped instances one at a time, each through the regular start event flow.
+/// A failed start is logged and does not hold up the next instance.
+async fn start_instances<R: Runtime>(
+    app: &AppHandle<R>,
+    names: &[String],
+) -> Vec<(String, OperationOutcome)> {
+    let mut outcomes = Vec::new();
+    for name in instances_with_status(app, names, false).await {
+        // Listen before starting, so a start that fails right away is not missed
+        let mut watcher = OutcomeWatcher::new(app, OperationKind::Start);
+        let outcome =
+            match lima_instance_service::start_lima_instance(app.clone(), name.clone()).await {
+                Ok(operation_id) => watcher.wait(&operation_id).await,
+                Err(e) => OperationOutcome::Error(e.to_string()),
+            };
+        match &outcome {
+            OperationOutcome::Success => {}
+            OperationOutcome::NotReady(message) => log::warn!("On launch: {}", message),
+            _ => log::warn!("Failed to start '{}' on launch: {:?}", name, outcome),
+        }
+        outcomes.push((name, outcome));
+    }
+    outcomes
+}
+
+/// Stop running instances side by side and wait for all of them
+async fn stop_instances<R: Runtime>(
+    app: &AppHandle<R>,
+    names: &[String],
+) -> Vec<(String, OperationOutcome)> {
+    let mut watcher = OutcomeWatcher::new(app, OperationKind::Stop);
+    let mut outcomes = Vec::new();
+    let mut stopping = Vec::new();
+    for name in instances_with_status(app, names, true).await {
+        match lima_instance_service::stop_lima_instance(app.clone(), name.clone(), false).await {
+            Ok(operation_id) => stopping.push((name, operation_id)),
+            Err(e) => outcomes.push((name, OperationOutcome::Error(e.to_string()))),
+        }
+    }
+    for (name, operation_id) in stopping {
+        let outcome = watcher.wait(&operation_id).await;
+        outcomes.push((name, outcome));
+    }
+    for (name, outcome) in &outcomes {
+        if *outcome != OperationOutcome::Success {
+            log::warn!("Failed to stop '{}' on quit: {:?}", name, outcome);
+        }
+    }
+    outcomes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_lima_backend::FakeLimaBackend;
+    use crate::lima_backend::LimaBackendState;
+    use crate::operation_service::OperationRegistry;
+    use std::sync::Arc;
+    use tauri::Manager;
+
+    #[tokio::test]
+    async fn test_failed_start_does_not_block_the_others() {
+        let backend = Arc::new(
+            FakeLimaBackend::new()
+                .with_instance("broken", "Stopped")
+                .with_instance("dev", "Stopped")
+                .with_instance("k0s", "Running"),
+        );
+        backend.fail_next("start", "FATA[0000] exiting, status={Running:false}");
+        let app = tauri::test::mock_app();
+        app.manage(LimaBackendState(backend.clone()));
+        app.manage(OperationRegistry::default());
+
+        let names: Vec<String> = ["broken", "dev", "k0s", "gone"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let outcomes = start_instances(app.handle(), &names).await;
+
+        // Running and missing instances are skipped
+        assert_eq!(outcomes.len(), 2);
+        assert_eq!(outcomes[0].0, "broken");
+        assert!(matches!(outcomes[0].1, OperationOutcome::Error(_)));
+        assert_eq!(outcomes[1], ("dev".to_string(), OperationOutcome::Success));
+        assert_eq!(
+            instances_with_status(app.handle(), &names, true).await,
+            ["dev", "k0s"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_quit_stop_gives_up_after_the_deadline() {
+        let backend = Arc::new(
+            FakeLimaBackend::new()
+                .with_instance("dev", "Running")
+                .with_instance("hung", "Running"),
+        );
+        backend.hang_next("stop");
+        // Stop reads its escalation timeout from the settings store
+        let app = tauri::test::mock_builder()
+            .plugin(tauri_plugin_store::Builder::new().build())
+            .build(tauri::test::mock_context(tauri::test::noop_assets()))
+            .unwrap();
+        app.manage(LimaBackendState(backend.clone()));
+        app.manage(OperationRegistry::default());
+
+        // The first stop hangs
+        let names = ["hung".to_string(), "dev".to_string()];
+        let still_stopping =
+            stop_instances_within(app.handle(), &names, Duration::from_millis(200)).await;
+
+        assert_eq!(still_stopping, ["hung"]);
+        assert_eq!(backend.status("dev").as_deref(), Some("Stopped"));
+    }
+}
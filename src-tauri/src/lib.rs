@@ -1,17 +1,48 @@
-use tauri::{Listener, Manager};
+use tauri::{Emitter, Listener, Manager};
 
+mod bulk_operation_handler;
+mod bulk_operation_service;
+mod disk_handler;
+mod disk_service;
+mod error;
+#[cfg(test)]
+mod fake_lima_backend;
+mod http_client;
+mod idle_handler;
+mod idle_service;
 mod instance_registry_handler;
 mod instance_registry_service;
 mod k8s_handler;
 mod k8s_service;
+mod launch_policy_service;
+mod lima_backend;
+mod lima_compat_service;
 mod lima_config;
 mod lima_config_handler;
 mod lima_config_service;
 mod lima_handler;
+mod lima_info_service;
+mod lima_install_service;
 mod lima_instance_handler;
 mod lima_instance_service;
+mod lima_log;
 mod lima_service;
+mod operation_handler;
+mod operation_log_handler;
+mod operation_log_service;
+mod operation_service;
+mod preflight_handler;
+mod preflight_service;
+mod readiness_handler;
+mod readiness_service;
+mod settings_handler;
+mod settings_service;
+mod snapshot_handler;
+mod snapshot_service;
 mod state;
+mod status_watcher_service;
+mod template_handler;
+mod template_service;
 mod terminal_manager;
 mod tray_handler;
 mod yaml_handler;
@@ -36,6 +67,40 @@ pub fn run() {
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
         .setup(|app| {
+            lima_install_service::init_managed_prefix(app.handle());
+            // Resolve Lima locations once per session; refreshed when settings change
+            let lima_paths = lima_service::LimaPathsState(std::sync::Arc::new(
+                std::sync::RwLock::new(lima_service::resolve_lima_paths(
+                    &settings_service::load_lima_path_settings(app.handle()),
+                )),
+            ));
+            app.manage(lima_backend::LimaBackendState(std::sync::Arc::new(
+                lima_backend::LimactlBackend::new(lima_paths.clone()),
+            )));
+            app.manage(lima_paths);
+            app.manage(lima_info_service::HostLimaInfoState::default());
+            // Tray and UI disable actions on instances that are busy
+            let handle = app.handle().clone();
+            app.manage(operation_service::OperationRegistry::with_listener(
+                move |instance_name, operation| {
+                    let _ = handle.emit(
+                        "instance-operation-changed",
+                        operation_service::InstanceOperationChanged {
+                            instance_name: instance_name.to_string(),
+                            operation: operation.cloned(),
+                        },
+                    );
+                },
+            ));
+            // Lifecycle output survives a hidden window or an app restart
+            match app.path().app_log_dir() {
+                Ok(dir) => {
+                    app.manage(operation_log_service::OperationLogState::new(
+                        dir.join("operations"),
+                    ));
+                }
+                Err(e) => log::warn!("Operation logs will not be persisted: {}", e),
+            }
             app.manage(state::AppState {
                 // Initialize last_tray_menu_refresh to 60 seconds ago to ensure the first refresh happens immediately
                 last_tray_menu_refresh: std::sync::Mutex::new(
@@ -69,6 +134,9 @@ pub fn run() {
 
             tray_handler::setup_tray(app)?;
             tray_handler::setup_listeners(app);
+            launch_policy_service::setup(app.handle());
+            idle_service::start_idle_monitor(app.handle());
+            status_watcher_service::start_status_watcher(app.handle());
 
             Ok(())
         })
@@ -101,9 +169,22 @@ pub fn run() {
         })
         .invoke_handler(tauri::generate_handler![
             lima_handler::lima_version_cmd,
+            lima_handler::check_lima_compatibility_cmd,
             lima_handler::get_system_capabilities_cmd,
+            lima_handler::get_host_lima_info_cmd,
             lima_handler::check_lima_installed_cmd,
             lima_handler::install_lima_cmd,
+            lima_handler::install_lima_from_archive_cmd,
+            settings_handler::get_lima_path_settings_cmd,
+            settings_handler::set_lima_path_settings_cmd,
+            settings_handler::get_lima_paths_cmd,
+            settings_handler::get_lifecycle_settings_cmd,
+            settings_handler::set_lifecycle_settings_cmd,
+            settings_handler::get_launch_policies_cmd,
+            settings_handler::set_launch_policy_cmd,
+            settings_handler::get_idle_policies_cmd,
+            settings_handler::set_idle_policy_cmd,
+            idle_handler::get_idle_status_cmd,
             lima_config_handler::read_lima_yaml_cmd,
             lima_config_handler::write_lima_yaml_cmd,
             lima_config_handler::get_lima_yaml_path_cmd,
@@ -127,6 +208,31 @@ pub fn run() {
             lima_instance_handler::start_lima_instance_cmd,
             lima_instance_handler::stop_lima_instance_cmd,
             lima_instance_handler::delete_lima_instance_cmd,
+            lima_instance_handler::restart_lima_instance_cmd,
+            lima_instance_handler::recreate_lima_instance_cmd,
+            lima_instance_handler::clone_lima_instance_cmd,
+            lima_instance_handler::rename_lima_instance_cmd,
+            preflight_handler::preflight_lima_instance_cmd,
+            bulk_operation_handler::start_lima_instances_cmd,
+            bulk_operation_handler::stop_lima_instances_cmd,
+            disk_handler::list_disks_cmd,
+            disk_handler::create_disk_cmd,
+            disk_handler::resize_disk_cmd,
+            disk_handler::delete_disk_cmd,
+            disk_handler::attach_disk_cmd,
+            disk_handler::detach_disk_cmd,
+            snapshot_handler::list_snapshots_cmd,
+            snapshot_handler::create_snapshot_cmd,
+            snapshot_handler::apply_snapshot_cmd,
+            snapshot_handler::delete_snapshot_cmd,
+            template_handler::resolve_template_cmd,
+            readiness_handler::check_instance_readiness_cmd,
+            operation_handler::cancel_operation_cmd,
+            operation_handler::list_operations_cmd,
+            operation_handler::get_instance_operation_cmd,
+            operation_log_handler::list_operation_logs_cmd,
+            operation_log_handler::read_operation_log_cmd,
+            operation_log_handler::tail_operation_log_cmd,
             k8s_handler::check_k0s_available_cmd,
             k8s_handler::get_k8s_pods_cmd,
             k8s_handler::get_k8s_services_cmd,
@@ -0,0 +1,393 @@
+use crate::error::AppError;
+use crate::lima_service::{lima_paths, LimaPaths, LimaPathsState};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::io::AsyncRead;
+use tokio::process::{Child, Command as TokioCommand};
+
+/// Upper bound for one-shot queries (list, info, shell, ...), so a hung guest
+/// cannot block a command forever. Lifecycle operations are not bounded by this.
+const ONE_SHOT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Upper bound for `limactl snapshot create` and `apply`, which copy the whole disk
+/// state and take minutes on a large disk
+const SNAPSHOT_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// How long a killed lifecycle operation gets to exit after SIGTERM before SIGKILL
+const KILL_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+/// Boxed future returned by backend methods, so the trait stays object-safe
+pub type BackendFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Boxed output stream of a running lifecycle operation
+pub type BackendStream = Box<dyn AsyncRead + Send + Unpin>;
+
+/// Captured result of a one-shot backend call (list, info, shell, ...)
+#[derive(Debug, Clone, Default)]
+pub struct LimaOutput {
+    /// Exit code, `None` if the process was terminated by a signal
+    pub code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl LimaOutput {
+    pub fn success(&self) -> bool {
+        self.code == Some(0)
+    }
+}
+
+/// Exit status of a lifecycle operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LimaExitStatus {
+    /// Exit code, `None` if the process was terminated by a signal
+    pub code: Option<i32>,
+}
+
+impl LimaExitStatus {
+    pub fn success(&self) -> bool {
+        self.code == Some(0)
+    }
+}
+
+/// A running create/start/stop/delete operation whose output is streamed line by line
+pub trait LimaProcess: Send {
+    /// Take the stdout stream. Returns `None` if already taken.
+    fn take_stdout(&mut self) -> Option<BackendStream>;
+
+    /// Take the stderr stream. Returns `None` if already taken.
+    fn take_stderr(&mut self) -> Option<BackendStream>;
+
+    /// Wait for the operation to finish
+    fn wait(&mut self) -> BackendFuture<'_, Result<LimaExitStatus, AppError>>;
+
+    /// Terminate the operation together with any processes it spawned, and reap it
+    fn kill(&mut self) -> BackendFuture<'_, Result<(), AppError>>;
+}
+
+/// Everything the app needs from Lima. `LimactlBackend` drives the real `limactl`
+/// binary; other implementations (e.g. an in-process fake for tests) can be
+/// swapped in through `LimaBackendState`.
+pub trait LimaBackend: Send + Sync {
+    /// Raw `limactl list --format json` output (NDJSON, one instance per line)
+    fn list(&self) -> BackendFuture<'_, Result<LimaOutput, AppError>>;
+
+    /// Raw `limactl info` output (JSON)
+    fn info(&self) -> BackendFuture<'_, Result<LimaOutput, AppError>>;
+
+    /// Raw `limactl --version` output
+    fn version(&self) -> BackendFuture<'_, Result<LimaOutput, AppError>>;
+
+    /// Directory where the backend keeps its instances (LIMA_HOME), if it can tell.
+    /// Blocking, since it is needed by synchronous path helpers.
+    fn lima_home(&self) -> Option<PathBuf>;
+
+    /// Create an instance named `instance_name` from the config file at `config_path`
+    fn create(
+        &self,
+        instance_name: &str,
+        config_path: &Path,
+    ) -> Result<Box<dyn LimaProcess>, AppError>;
+
+    /// Start an existing instance
+    fn start(&self, instance_name: &str) -> Result<Box<dyn LimaProcess>, AppError>;
+
+    /// Stop a running instance. `force` kills the VM instead of shutting the guest down.
+    fn stop(&self, instance_name: &str, force: bool) -> Result<Box<dyn LimaProcess>, AppError>;
+
+    /// Delete an instance. `force` also deletes a running (or hung) instance.
+    fn delete(&self, instance_name: &str, force: bool) -> Result<Box<dyn LimaProcess>, AppError>;
+
+    /// Copy a stopped instance to a new name (`limactl clone`)
+    fn clone_instance(
+        &self,
+        source_name: &str,
+        target_name: &str,
+    ) -> Result<Box<dyn LimaProcess>, AppError>;
+
+    /// Run a command inside the instance (`limactl shell <instance> <args...>`)
+    fn shell<'a>(
+        &'a self,
+        instance_name: &'a str,
+        args: &'a [&'a str],
+    ) -> BackendFuture<'a, Result<LimaOutput, AppError>>;
+
+    /// Raw `limactl snapshot <action> <instance> [--tag <tag>]` output, where `action`
+    /// is one of create, apply, delete or list. Only the qemu driver supports snapshots.
+    fn snapshot<'a>(
+        &'a self,
+        action: &'a str,
+        instance_name: &'a str,
+        tag: Option<&'a str>,
+    ) -> BackendFuture<'a, Result<LimaOutput, AppError>>;
+
+    /// Raw `limactl disk <args...>` output (create, list --json, resize, delete)
+    fn disk<'a>(&'a self, args: &'a [&'a str]) -> BackendFuture<'a, Result<LimaOutput, AppError>>;
+}
+
+/// Managed state holding the backend used by all services
+pub struct LimaBackendState(pub Arc<dyn LimaBackend>);
+
+/// Get the backend registered in managed state, falling back to `limactl`
+pub fn lima_backend<R: tauri::Runtime>(app: &AppHandle<R>) -> Arc<dyn LimaBackend> {
+    app.try_state::<LimaBackendState>()
+        .map(|state| state.0.clone())
+        .unwrap_or_else(|| {
+            Arc::new(LimactlBackend::new(LimaPathsState(Arc::new(
+                std::sync::RwLock::new(lima_paths(app)),
+            ))))
+        })
+}
+
+// -- limactl backend --
+
+/// Backend driving the `limactl` executable from the cached `LimaPaths`.
+/// Every spawned process gets the configured LIMA_HOME.
+pub struct LimactlBackend {
+    paths: LimaPathsState,
+}
+
+impl LimactlBackend {
+    pub fn new(paths: LimaPathsState) -> Self {
+        Self { paths }
+    }
+
+    fn paths(&self) -> LimaPaths {
+        self.paths.0.read().map(|p| p.clone()).unwrap_or_default()
+    }
+
+    fn command(&self) -> Result<TokioCommand, AppError> {
+        let paths = self.paths();
+        let lima_cmd = paths.limactl.ok_or(AppError::LimaNotFound)?;
+        let mut command = TokioCommand::new(lima_cmd);
+        if let Some(lima_home) = paths.lima_home {
+            command.env("LIMA_HOME", lima_home);
+        }
+        Ok(command)
+    }
+
+    fn run<'a>(
+        &'a self,
+        args: Vec<String>,
+        timeout: Duration,
+    ) -> BackendFuture<'a, Result<LimaOutput, AppError>> {
+        Box::pin(async move {
+            let operation = format!(
+                "limactl {}",
+                args.first().map(String::as_str).unwrap_or_default()
+            );
+            // kill_on_drop so a timed out query does not leave limactl behind
+            let output = self
+                .command()?
+                .args(&args)
+                .stdin(Stdio::null())
+                .kill_on_drop(true)
+                .output();
+            let output = tokio::time::timeout(timeout, output)
+                .await
+                .map_err(|_| AppError::Timeout {
+                    operation: operation.clone(),
+                    seconds: timeout.as_secs(),
+                })?
+                .map_err(|e| AppError::io(&format!("Failed to run {}", operation), e))?;
+
+            Ok(LimaOutput {
+                code: output.status.code(),
+                stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+                stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            })
+        })
+    }
+
+    fn spawn(&self, args: &[&str]) -> Result<Box<dyn LimaProcess>, AppError> {
+        // Own process group, so cancelling can signal limactl and its helpers at once
+        let child = self
+            .command()?
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .process_group(0)
+            .spawn()
+            .map_err(|e| {
+                AppError::io(&format!("Failed to start limactl {} process", args[0]), e)
+            })?;
+
+        Ok(Box::new(LimactlProcess { child }))
+    }
+}
+
+impl LimaBackend for LimactlBackend {
+    fn list(&self) -> BackendFuture<'_, Result<LimaOutput, AppError>> {
+        self.run(
+            vec!["list".into(), "--format".into(), "json".into()],
+            ONE_SHOT_TIMEOUT,
+        )
+    }
+
+    fn info(&self) -> BackendFuture<'_, Result<LimaOutput, AppError>> {
+        self.run(vec!["info".into()], ONE_SHOT_TIMEOUT)
+    }
+
+    fn version(&self) -> BackendFuture<'_, Result<LimaOutput, AppError>> {
+        self.run(vec!["--version".into()], ONE_SHOT_TIMEOUT)
+    }
+
+    fn lima_home(&self) -> Option<PathBuf> {
+        self.paths().lima_home
+    }
+
+    fn create(
+        &self,
+        instance_name: &str,
+        config_path: &Path,
+    ) -> Result<Box<dyn LimaProcess>, AppError> {
+        self.spawn(&[
+            "create",
+            "--tty=false",
+            "--name",
+            instance_name,
+            &config_path.to_string_lossy(),
+        ])
+    }
+
+    fn start(&self, instance_name: &str) -> Result<Box<dyn LimaProcess>, AppError> {
+        self.spawn(&["start", "--tty=false", instance_name])
+    }
+
+    fn stop(&self, instance_name: &str, force: bool) -> Result<Box<dyn LimaProcess>, AppError> {
+        if force {
+            self.spawn(&["stop", "--tty=false", "--force", instance_name])
+        } else {
+            self.spawn(&["stop", "--tty=false", instance_name])
+        }
+    }
+
+    fn delete(&self, instance_name: &str, force: bool) -> Result<Box<dyn LimaProcess>, AppError> {
+        if force {
+            self.spawn(&["delete", "--force", instance_name])
+        } else {
+            self.spawn(&["delete", instance_name])
+        }
+    }
+
+    fn clone_instance(
+        &self,
+        source_name: &str,
+        target_name: &str,
+    ) -> Result<Box<dyn LimaProcess>, AppError> {
+        self.spawn(&["clone", "--tty=false", source_name, target_name])
+    }
+
+    fn shell<'a>(
+        &'a self,
+        instance_name: &'a str,
+        args: &'a [&'a str],
+    ) -> BackendFuture<'a, Result<LimaOutput, AppError>> {
+        let mut full_args = vec!["shell".to_string(), instance_name.to_string()];
+        full_args.extend(args.iter().map(|a| a.to_string()));
+        self.run(full_args, ONE_SHOT_TIMEOUT)
+    }
+
+    fn snapshot<'a>(
+        &'a self,
+        action: &'a str,
+        instance_name: &'a str,
+        tag: Option<&'a str>,
+    ) -> BackendFuture<'a, Result<LimaOutput, AppError>> {
+        let mut args = vec![
+            "snapshot".to_string(),
+            action.to_string(),
+            instance_name.to_string(),
+        ];
+        if let Some(tag) = tag {
+            args.extend(["--tag".to_string(), tag.to_string()]);
+        }
+        let timeout = if matches!(action, "create" | "apply") {
+            SNAPSHOT_TIMEOUT
+        } else {
+            ONE_SHOT_TIMEOUT
+        };
+        self.run(args, timeout)
+    }
+
+    fn disk<'a>(&'a self, args: &'a [&'a str]) -> BackendFuture<'a, Result<LimaOutput, AppError>> {
+        let mut full_args = vec!["disk".to_string()];
+        full_args.extend(args.iter().map(|a| a.to_string()));
+        self.run(full_args, ONE_SHOT_TIMEOUT)
+    }
+}
+
+struct LimactlProcess {
+    child: Child,
+}
+
+impl LimaProcess for LimactlProcess {
+    fn take_stdout(&mut self) -> Option<BackendStream> {
+        self.child
+            .stdout
+            .take()
+            .map(|s| Box::new(s) as BackendStream)
+    }
+
+    fn take_stderr(&mut self) -> Option<BackendStream> {
+        self.child
+            .stderr
+            .take()
+            .map(|s| Box::new(s) as BackendStream)
+    }
+
+    fn wait(&mut self) -> BackendFuture<'_, Result<LimaExitStatus, AppError>> {
+        Box::pin(async move {
+            self.child
+                .wait()
+                .await
+                .map(|status| LimaExitStatus {
+                    code: status.code(),
+                })
+                .map_err(|e| AppError::io("Failed to wait for limactl process", e))
+        })
+    }
+
+    fn kill(&mut self) -> BackendFuture<'_, Result<(), AppError>> {
+        Box::pin(async move {
+            // `None` once the process has been reaped
+            let Some(pgid) = self.child.id() else {
+                return Ok(());
+            };
+            signal_process_group(pgid, libc::SIGTERM);
+            let exited = tokio::time::timeout(KILL_GRACE_PERIOD, self.child.wait()).await;
+            // Also takes down group members that outlived limactl itself
+            signal_process_group(pgid, libc::SIGKILL);
+            if exited.is_err() {
+                self.child
+                    .wait()
+                    .await
+                    .map_err(|e| AppError::io("Failed to wait for killed limactl process", e))?;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Whether a process with this pid exists (it may belong to another user)
+pub fn process_exists(pid: i32) -> bool {
+    if pid <= 0 {
+        return false;
+    }
+    // SAFETY: signal 0 only checks for existence and permission
+    let result = unsafe { libc::kill(pid as libc::pid_t, 0) };
+    result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+fn signal_process_group(pgid: u32, signal: libc::c_int) {
+    // SAFETY: killpg only sends a signal; a stale group id fails with ESRCH
+    unsafe {
+        libc::killpg(pgid as libc::pid_t, signal);
+    }
+}
@@ -0,0 +1,268 @@
+use crate::error::AppError;
+use crate::lima_backend::LimaBackend;
+use crate::lima_config::LimaConfig;
+use semver::Version;
+use serde::Serialize;
+
+/// A config field that needs a newer Lima than the one installed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompatibilityIssue {
+    /// Config field, e.g. "vmType" or "provision[2].mode"
+    pub field: String,
+    /// Lima version that introduced support for the field/value
+    pub required_version: String,
+    pub message: String,
+}
+
+/// Result of comparing the installed Lima against a config
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LimaCompatibility {
+    /// Installed Lima version, `None` if it could not be determined
+    pub installed_version: Option<String>,
+    /// The config's `minimumLimaVersion`, if set
+    pub required_version: Option<String>,
+    pub compatible: bool,
+    pub issues: Vec<CompatibilityIssue>,
+}
+
+/// Config fields (or values) and the Lima version that first supported them
+const FEATURE_VERSIONS: &[(&str, Option<&str>, &str)] = &[
+    ("vmType", Some("vz"), "0.14.0"),
+    ("vmType", Some("krunkit"), "2.0.0"),
+    ("rosetta", None, "0.14.0"),
+    ("copyToHost", None, "0.17.0"),
+    ("minimumLimaVersion", None, "1.0.0"),
+    ("provision.mode", Some("data"), "1.0.0"),
+    ("provision.mode", Some("yq"), "2.0.0"),
+];
+
+/// Parse a Lima version string such as "2.0.0", "v1.2.1" or "limactl version 1.2.1".
+/// Dev builds described by git (`1.2.1-12-gabcdef`) count as their base release.
+pub fn parse_lima_version(text: &str) -> Option<Version> {
+    let token = text.split_whitespace().last()?.trim_start_matches('v');
+    let mut version = Version::parse(token).ok()?;
+    if is_git_describe_suffix(version.pre.as_str()) {
+        version.pre = semver::Prerelease::EMPTY;
+    }
+    Some(version)
+}
+
+/// `<commits>-g<sha>` as appended by `git describe`
+fn is_git_describe_suffix(pre: &str) -> bool {
+    pre.split_once("-g").is_some_and(|(commits, sha)| {
+        !commits.is_empty()
+            && commits.chars().all(|c| c.is_ascii_digit())
+            && !sha.is_empty()
+            && sha.chars().all(|c| c.is_ascii_hexdigit())
+    })
+}
+
+/// Compare `config` against the installed Lima version.
+/// An unknown installed version (e.g. an untagged dev build) is treated as compatible.
+pub fn check_compatibility(installed: Option<&Version>, config: &LimaConfig) -> LimaCompatibility {
+    let mut issues = Vec::new();
+
+    if let Some(installed) = installed {
+        if let Some(required) = config.minimum_lima_version.as_deref() {
+            match parse_lima_version(required) {
+                Some(min) if *installed < min => issues.push(CompatibilityIssue {
+                    field: "minimumLimaVersion".to_string(),
+                    required_version: min.to_string(),
+                    message: format!(
+                        "This config requires Lima {} or newer, but {} is installed",
+                        min, installed
+                    ),
+                }),
+                Some(_) => {}
+                None => issues.push(CompatibilityIssue {
+                    field: "minimumLimaVersion".to_string(),
+                    required_version: required.to_string(),
+                    message: format!("'{}' is not a valid version", required),
+                }),
+            }
+        }
+
+        for (field, value) in used_features(config) {
+            let Some((_, _, since)) = FEATURE_VERSIONS
+                .iter()
+                .find(|(f, v, _)| *f == feature_key(&field) && v.is_none_or(|v| Some(v) == value))
+            else {
+                continue;
+            };
+            let since = Version::parse(since).expect("valid feature version");
+            if *installed < since {
+                let what = match value {
+                    Some(value) => format!("{} '{}'", field, value),
+                    None => field.clone(),
+                };
+                issues.push(CompatibilityIssue {
+                    field,
+                    required_version: since.to_string(),
+                    message: format!(
+                        "{} requires Lima {} or newer, but {} is installed",
+                        what, since, installed
+                    ),
+                });
+            }
+        }
+    }
+
+    LimaCompatibility {
+        installed_version: installed.map(Version::to_string),
+        required_version: config.minimum_lima_version.clone(),
+        compatible: issues.is_empty(),
+        issues,
+    }
+}
+
+/// Fields set in `config` as (field path, value) pairs
+fn used_features(config: &LimaConfig) -> Vec<(String, Option<&str>)> {
+    let mut features = Vec::new();
+    if let Some(vm_type) = config.vm_type.as_deref() {
+        features.push(("vmType".to_string(), Some(vm_type)));
+    }
+    if config.rosetta.as_ref().is_some_and(|r| r.enabled) {
+        features.push(("rosetta".to_string(), None));
+    }
+    if config.copy_to_host.as_ref().is_some_and(|c| !c.is_empty()) {
+        features.push(("copyToHost".to_string(), None));
+    }
+    if config.minimum_lima_version.is_some() {
+        features.push(("minimumLimaVersion".to_string(), None));
+    }
+    for (i, provision) in config.provision.iter().flatten().enumerate() {
+        features.push((
+            format!("provision[{}].mode", i),
+            Some(provision.mode.as_str()),
+        ));
+    }
+    features
+}
+
+/// "provision[2].mode" -> "provision.mode"
+fn feature_key(field: &str) -> String {
+    match (field.find('['), field.find(']')) {
+        (Some(start), Some(end)) => format!("{}{}", &field[..start], &field[end + 1..]),
+        _ => field.to_string(),
+    }
+}
+
+/// Query the installed Lima version from the backend
+pub async fn installed_lima_version(
+    backend: &dyn LimaBackend,
+) -> Result<Option<Version>, AppError> {
+    let output = backend.version().await?;
+    if !output.success() {
+        return Err(AppError::command_failed(
+            "limactl --version",
+            output.code,
+            &output.stderr,
+        ));
+    }
+    Ok(parse_lima_version(&output.stdout))
+}
+
+/// Check `config` against the installed Lima
+pub async fn get_lima_compatibility(
+    backend: &dyn LimaBackend,
+    config: &LimaConfig,
+) -> Result<LimaCompatibility, AppError> {
+    let installed = installed_lima_version(backend).await?;
+    Ok(check_compatibility(installed.as_ref(), config))
+}
+
+/// Fail with `AppError::LimaIncompatible` if the installed Lima cannot run `config`
+pub async fn ensure_compatible(
+    backend: &dyn LimaBackend,
+    config: &LimaConfig,
+) -> Result<(), AppError> {
+    let compatibility = get_lima_compatibility(backend, config).await?;
+    if compatibility.compatible {
+        return Ok(());
+    }
+    Err(AppError::LimaIncompatible {
+        installed_version: compatibility.installed_version.unwrap_or_default(),
+        required_version: compatibility.required_version,
+        issues: compatibility.issues,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lima_config::{CopyToHost, Provision};
+
+    fn version(v: &str) -> Version {
+        Version::parse(v).unwrap()
+    }
+
+    #[test]
+    fn test_parse_lima_version() {
+        assert_eq!(
+            parse_lima_version("limactl version 2.0.0\n"),
+            Some(version("2.0.0"))
+        );
+        assert_eq!(parse_lima_version("v1.2.1"), Some(version("1.2.1")));
+        assert_eq!(
+            parse_lima_version("limactl version 1.2.1-12-gabcdef0"),
+            Some(version("1.2.1"))
+        );
+        assert_eq!(
+            parse_lima_version("limactl version 2.0.0-beta.1"),
+            Some(version("2.0.0-beta.1"))
+        );
+        assert_eq!(parse_lima_version("limactl version HEAD-abcdef"), None);
+    }
+
+    #[test]
+    fn test_check_compatibility_minimum_version() {
+        let config = LimaConfig {
+            minimum_lima_version: Some("2.0.0".to_string()),
+            ..Default::default()
+        };
+
+        let result = check_compatibility(Some(&version("2.0.1")), &config);
+        assert!(result.compatible);
+        assert_eq!(result.installed_version.as_deref(), Some("2.0.1"));
+
+        let result = check_compatibility(Some(&version("1.2.1")), &config);
+        assert!(!result.compatible);
+        assert_eq!(result.issues[0].field, "minimumLimaVersion");
+        assert_eq!(result.issues[0].required_version, "2.0.0");
+
+        // Unknown installed version does not block
+        assert!(check_compatibility(None, &config).compatible);
+    }
+
+    #[test]
+    fn test_check_compatibility_flags_unsupported_fields() {
+        let config = LimaConfig {
+            vm_type: Some("krunkit".to_string()),
+            copy_to_host: Some(vec![CopyToHost {
+                guest: "/etc/k0s/kubeconfig".to_string(),
+                host: "{{.Dir}}/kubeconfig.yaml".to_string(),
+                delete_on_stop: None,
+            }]),
+            provision: Some(vec![
+                Provision {
+                    mode: "system".to_string(),
+                    script: String::new(),
+                },
+                Provision {
+                    mode: "data".to_string(),
+                    script: String::new(),
+                },
+            ]),
+            ..Default::default()
+        };
+
+        let result = check_compatibility(Some(&version("0.16.0")), &config);
+        let fields: Vec<_> = result.issues.iter().map(|i| i.field.as_str()).collect();
+        assert_eq!(fields, vec!["vmType", "copyToHost", "provision[1].mode"]);
+
+        let result = check_compatibility(Some(&version("2.0.0")), &config);
+        assert!(result.compatible);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::error::AppError;
 use serde::{Deserialize, Serialize};
 use sysinfo::System;
 
@@ -52,6 +53,17 @@ pub struct LimaConfig {
     /// Disk configuration (e.g., "100GiB", "50GiB")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub disk: Option<String>,
+    /// Named disks from `limactl disk create` to attach, e.g.
+    /// - name: "docker-data"
+    ///   format: true
+    ///   fsType: "ext4"
+    #[serde(
+        rename = "additionalDisks",
+        default,
+        deserialize_with = "deserialize_additional_disks",
+        skip_serializing_if = "skip_vec_none"
+    )]
+    pub additional_disks: Option<Vec<AdditionalDisk>>,
     /// Image configurations
     /// e.g.
     /// - location: "https://cloud-images.ubuntu.com/releases/noble/release/ubuntu-24.04-server-cloudimg-arm64.img"
@@ -82,6 +94,60 @@ pub struct LimaConfig {
     pub port_forwards: Option<Vec<PortForward>>,
 }
 
+/// Additional disk attached to the instance, mounted at `/mnt/lima-<name>`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdditionalDisk {
+    /// Disk name as given to `limactl disk create`
+    pub name: String,
+    /// Whether Lima formats the disk if it has no filesystem yet (default true)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<bool>,
+    /// Filesystem to format with (e.g., "ext4", "xfs")
+    #[serde(rename = "fsType", skip_serializing_if = "Option::is_none")]
+    pub fs_type: Option<String>,
+    /// Extra mkfs arguments
+    #[serde(rename = "fsArgs", skip_serializing_if = "skip_vec_none")]
+    pub fs_args: Option<Vec<String>>,
+}
+
+impl AdditionalDisk {
+    /// Attach the disk with Lima's defaults
+    pub fn named(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            format: None,
+            fs_type: None,
+            fs_args: None,
+        }
+    }
+}
+
+/// `additionalDisks` entries may also be bare disk names
+fn deserialize_additional_disks<'de, D>(
+    deserializer: D,
+) -> Result<Option<Vec<AdditionalDisk>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Entry {
+        Name(String),
+        Disk(AdditionalDisk),
+    }
+
+    let entries = Option::<Vec<Entry>>::deserialize(deserializer)?;
+    Ok(entries.map(|entries| {
+        entries
+            .into_iter()
+            .map(|entry| match entry {
+                Entry::Name(name) => AdditionalDisk::named(&name),
+                Entry::Disk(disk) => disk,
+            })
+            .collect()
+    }))
+}
+
 /// Mount configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Mount {
@@ -195,6 +261,7 @@ impl Default for LimaConfig {
             cpus: None,
             memory: None,
             disk: None,
+            additional_disks: None,
         }
     }
 }
@@ -252,10 +319,108 @@ impl LimaConfig {
         self.probes = Self::merge_vecs(self.probes, other.probes);
         self.copy_to_host = Self::merge_vecs(self.copy_to_host, other.copy_to_host);
         self.port_forwards = Self::merge_vecs(self.port_forwards, other.port_forwards);
+        self.additional_disks = Self::merge_vecs(self.additional_disks, other.additional_disks);
 
         self
     }
 
+    /// Apply a `base:` template under this one, like Lima does: values set here win,
+    /// provision scripts and probes of the base run first, and for the other lists
+    /// (where Lima uses the first match) the entries from here come first
+    pub fn inherit(self, base: LimaConfig) -> Self {
+        LimaConfig {
+            minimum_lima_version: self.minimum_lima_version.or(base.minimum_lima_version),
+            vm_type: self.vm_type.or(base.vm_type),
+            rosetta: self.rosetta.or(base.rosetta),
+            cpus: self.cpus.or(base.cpus),
+            memory: self.memory.or(base.memory),
+            disk: self.disk.or(base.disk),
+            additional_disks: Self::merge_vecs(self.additional_disks, base.additional_disks),
+            images: Self::merge_vecs(self.images, base.images),
+            mounts: Self::merge_vecs(self.mounts, base.mounts),
+            containerd: self.containerd.or(base.containerd),
+            provision: Self::merge_vecs(base.provision, self.provision),
+            probes: Self::merge_vecs(base.probes, self.probes),
+            copy_to_host: Self::merge_vecs(self.copy_to_host, base.copy_to_host),
+            port_forwards: Self::merge_vecs(self.port_forwards, base.port_forwards),
+        }
+    }
+
+    /// Point instance-specific values at a copy of the instance named `target_name`:
+    /// the kubeconfig context set by the k0s host-access script, and absolute paths
+    /// into the source instance dir (e.g. a `hostSocket` for docker.sock).
+    /// Paths using `{{.Dir}}` follow the instance on their own.
+    pub fn retarget_instance(
+        &mut self,
+        source_name: &str,
+        target_name: &str,
+        source_dir: &str,
+        target_dir: &str,
+    ) {
+        let source_prefix = format!("{}/", source_dir.trim_end_matches('/'));
+        let target_prefix = format!("{}/", target_dir.trim_end_matches('/'));
+        let retarget_path = |path: &mut String| {
+            if let Some(rest) = path.strip_prefix(&source_prefix) {
+                *path = format!("{}{}", target_prefix, rest);
+            }
+        };
+
+        for provision in self.provision.iter_mut().flatten() {
+            provision.script = provision
+                .script
+                .replace(
+                    &format!("name: {}/", source_name),
+                    &format!("name: {}/", target_name),
+                )
+                .replace(
+                    &format!("current-context: {}/", source_name),
+                    &format!("current-context: {}/", target_name),
+                )
+                .replace(&source_prefix, &target_prefix);
+        }
+        for copy in self.copy_to_host.iter_mut().flatten() {
+            retarget_path(&mut copy.host);
+        }
+        for forward in self.port_forwards.iter_mut().flatten() {
+            if let Some(socket) = forward.host_socket.as_mut() {
+                retarget_path(socket);
+            }
+        }
+    }
+
+    /// Move forwarded host ports for which `is_taken` returns true to the next port that
+    /// is not, so a copy can run next to the original. Provision scripts mentioning
+    /// `127.0.0.1:<port>` (e.g. the k0s kubeconfig server) are updated to match.
+    /// Returns the `(old, new)` port pairs.
+    pub fn remap_host_ports(&mut self, mut is_taken: impl FnMut(u16) -> bool) -> Vec<(u16, u16)> {
+        let mut remapped = Vec::new();
+        for forward in self.port_forwards.iter_mut().flatten() {
+            if forward.ignore == Some(true) {
+                continue;
+            }
+            let Some(port) = forward.host_port else {
+                continue;
+            };
+            if !is_taken(port) {
+                continue;
+            }
+            let Some(free) = (port.saturating_add(1)..=u16::MAX).find(|p| !is_taken(*p)) else {
+                continue;
+            };
+            forward.host_port = Some(free);
+            remapped.push((port, free));
+        }
+
+        for provision in self.provision.iter_mut().flatten() {
+            for (old, new) in &remapped {
+                provision.script = provision
+                    .script
+                    .replace(&format!("127.0.0.1:{}", old), &format!("127.0.0.1:{}", new));
+            }
+        }
+        remapped
+    }
+
     fn merge_vecs<T>(v1: Option<Vec<T>>, v2: Option<Vec<T>>) -> Option<Vec<T>> {
         match (v1, v2) {
             (Some(mut a), Some(b)) => {
@@ -269,13 +434,44 @@ impl LimaConfig {
     }
 }
 
+/// VM type for the default templates: vz on macOS, qemu (KVM) elsewhere
+pub fn host_default_vm_type() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "vz"
+    } else {
+        "qemu"
+    }
+}
+
+/// Rosetta for the default templates. Only available with vz, so macOS only.
+fn host_default_rosetta() -> Option<RosettaConfig> {
+    cfg!(target_os = "macos").then_some(RosettaConfig {
+        enabled: true,
+        binfmt: true,
+    })
+}
+
+/// Host user the docker provisioning grants socket access to; Lima creates a guest
+/// user of the same name
+fn host_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("LOGNAME"))
+        .or_else(|_| {
+            std::process::Command::new("whoami")
+                .output()
+                .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+                .map_err(|_| std::env::VarError::NotPresent)
+        })
+        .unwrap_or_default()
+}
+
 /// Get the default k0s Lima configuration
 pub fn get_default_k0s_lima_config<R: tauri::Runtime>(
     _app: &tauri::AppHandle<R>,
     instance_name: &str,
     install_helm: bool,
     install_local_path_provisioner: bool,
-) -> Result<LimaConfig, String> {
+) -> Result<LimaConfig, AppError> {
     // Get system information
     let mut sys = System::new_all();
     sys.refresh_all();
@@ -292,11 +488,8 @@ pub fn get_default_k0s_lima_config<R: tauri::Runtime>(
     // 1. Base Configuration (VM specs and Core k0s installation)
     let base_config = LimaConfig {
         minimum_lima_version: Some("2.0.0".to_string()),
-        vm_type: Some("vz".to_string()),
-        rosetta: Some(RosettaConfig {
-            enabled: true,
-            binfmt: true,
-        }),
+        vm_type: Some(host_default_vm_type().to_string()),
+        rosetta: host_default_rosetta(),
         cpus: Some(vm_cpus),
         memory: Some(vm_memory),
         disk: Some("40GiB".to_string()),
@@ -417,15 +610,7 @@ chmod 644 /var/lib/k0s/pki/external-admin.conf
     };
 
     // 3. Docker installation and socket forwarding
-    let host_user = std::env::var("USER")
-        .or_else(|_| std::env::var("LOGNAME"))
-        .or_else(|_| {
-            std::process::Command::new("whoami")
-                .output()
-                .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
-                .map_err(|_| std::env::VarError::NotPresent)
-        })
-        .unwrap_or_default();
+    let host_user = host_user();
     let docker_config = LimaConfig {
         provision: Some(vec![Provision {
             mode: "system".to_string(),
@@ -511,7 +696,7 @@ k0s kubectl patch storageclass local-path -p '{"metadata": {"annotations":{"stor
 pub fn get_default_docker_lima_config<R: tauri::Runtime>(
     _app: &tauri::AppHandle<R>,
     _instance_name: &str,
-) -> Result<LimaConfig, String> {
+) -> Result<LimaConfig, AppError> {
     // Get system information
     let mut sys = System::new_all();
     sys.refresh_all();
@@ -528,11 +713,8 @@ pub fn get_default_docker_lima_config<R: tauri::Runtime>(
     // 1. Base Configuration (VM specs + btop only)
     let base_config = LimaConfig {
         minimum_lima_version: Some("2.0.0".to_string()),
-        vm_type: Some("vz".to_string()),
-        rosetta: Some(RosettaConfig {
-            enabled: true,
-            binfmt: true,
-        }),
+        vm_type: Some(host_default_vm_type().to_string()),
+        rosetta: host_default_rosetta(),
         cpus: Some(vm_cpus),
         memory: Some(vm_memory),
         disk: Some("40GiB".to_string()),
@@ -568,15 +750,7 @@ fi
     };
 
     // 2. Docker installation and socket forwarding
-    let host_user = std::env::var("USER")
-        .or_else(|_| std::env::var("LOGNAME"))
-        .or_else(|_| {
-            std::process::Command::new("whoami")
-                .output()
-                .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
-                .map_err(|_| std::env::VarError::NotPresent)
-        })
-        .unwrap_or_default();
+    let host_user = host_user();
     let docker_config = LimaConfig {
         provision: Some(vec![Provision {
             mode: "system".to_string(),
@@ -666,6 +840,7 @@ mod tests {
                 delete_on_stop: Some(true),
             }]),
             port_forwards: Some(vec![]),
+            additional_disks: Some(vec![AdditionalDisk::named("docker-data")]),
         };
 
         // Mutate all fields
@@ -706,6 +881,11 @@ mod tests {
             copy_to_host[0].delete_on_stop = Some(false);
         }
 
+        // Update additional_disks
+        if let Some(ref mut disks) = config.additional_disks {
+            disks[0].fs_type = Some("xfs".to_string());
+        }
+
         // Serialize to YAML
         let yaml = config.to_yaml().expect("Failed to serialize to YAML");
 
@@ -731,8 +911,8 @@ mod tests {
 
         assert!(deserialized.containerd.is_some());
         let containerd = deserialized.containerd.unwrap();
-        assert_eq!(containerd.system, true);
-        assert_eq!(containerd.user, true);
+        assert!(containerd.system);
+        assert!(containerd.user);
 
         assert!(deserialized.provision.is_some());
         let provision = deserialized.provision.unwrap();
@@ -745,6 +925,34 @@ mod tests {
         assert!(deserialized.copy_to_host.is_some());
         let copy_to_host = deserialized.copy_to_host.unwrap();
         assert_eq!(copy_to_host[0].delete_on_stop, Some(false));
+
+        let disks = deserialized.additional_disks.unwrap();
+        assert_eq!(disks[0].name, "docker-data");
+        assert_eq!(disks[0].fs_type, Some("xfs".to_string()));
+    }
+
+    #[test]
+    fn test_additional_disks_accept_names_and_objects() {
+        let config = LimaConfig::from_yaml(
+            "additionalDisks:\n- data\n- name: docker\n  format: false\n  fsArgs: [\"-L\", \"docker\"]\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config.additional_disks.unwrap(),
+            vec![
+                AdditionalDisk::named("data"),
+                AdditionalDisk {
+                    name: "docker".to_string(),
+                    format: Some(false),
+                    fs_type: None,
+                    fs_args: Some(vec!["-L".to_string(), "docker".to_string()]),
+                },
+            ]
+        );
+        assert_eq!(
+            LimaConfig::from_yaml("cpus: 2\n").unwrap().additional_disks,
+            None
+        );
     }
 
     #[test]
@@ -802,8 +1010,8 @@ probes:
         // Verify containerd
         assert!(config.containerd.is_some());
         let containerd = config.containerd.as_ref().unwrap();
-        assert_eq!(containerd.system, false);
-        assert_eq!(containerd.user, false);
+        assert!(!containerd.system);
+        assert!(!containerd.user);
 
         // Verify provision
         assert!(config.provision.is_some());
@@ -854,6 +1062,7 @@ probes:
             probes: Some(vec![]),
             copy_to_host: Some(vec![]),
             port_forwards: Some(vec![]),
+            additional_disks: Some(vec![]),
         };
 
         let yaml = config.to_yaml().expect("Failed to serialize");
@@ -865,6 +1074,7 @@ probes:
         assert!(!yaml.contains("probes: []"));
         assert!(!yaml.contains("copyToHost: []"));
         assert!(!yaml.contains("portForwards: []"));
+        assert!(!yaml.contains("additionalDisks: []"));
 
         // Fields with values should appear
         assert!(yaml.contains("vmType: vz"));
@@ -939,18 +1149,18 @@ probes:
         // Capture dynamic host-specific values to insert into the snapshot
         let cpus = config.cpus.unwrap();
         let memory = config.memory.clone().unwrap();
-        let host_user = std::env::var("USER")
-            .or_else(|_| std::env::var("LOGNAME"))
-            .unwrap_or_default();
+        let host_user = host_user();
+        // vz with Rosetta on macOS, qemu without Rosetta on Linux
+        let vm = if cfg!(target_os = "macos") {
+            "vmType: vz\nrosetta:\n  enabled: true\n  binfmt: true\n"
+        } else {
+            "vmType: qemu\n"
+        };
 
         let expected_whole_file = format!(
             r#"
 minimumLimaVersion: '2.0.0'
-vmType: vz
-rosetta:
-  enabled: true
-  binfmt: true
-cpus: {cpus}
+{vm}cpus: {cpus}
 memory: '{memory}'
 disk: '40GiB'
 images:
@@ -1073,4 +1283,84 @@ portForwards:
 
         assert_eq!(yaml.trim(), expected_whole_file.trim());
     }
+
+    #[test]
+    fn test_default_configs_use_host_vm_type() {
+        let app = tauri::test::mock_app();
+        let k0s = get_default_k0s_lima_config(app.handle(), "k0s", false, false).unwrap();
+        let docker = get_default_docker_lima_config(app.handle(), "docker").unwrap();
+
+        for config in [k0s, docker] {
+            assert_eq!(config.vm_type.as_deref(), Some(host_default_vm_type()));
+            if cfg!(target_os = "linux") {
+                assert_eq!(config.vm_type.as_deref(), Some("qemu"));
+                assert!(config.rosetta.is_none());
+            }
+        }
+    }
+
+    #[test]
+    fn test_retarget_instance_and_remap_host_ports() {
+        let mut config = LimaConfig {
+            provision: Some(vec![Provision {
+                mode: "system".to_string(),
+                script: concat!(
+                    "sed -i 's|server: https://.*:6443|server: https://127.0.0.1:6443|' f\n",
+                    "sed -i \"s/name: [Dd]efault/name: k0s/g\" f\n",
+                    "sed -i \"s/current-context: [Dd]efault/current-context: k0s/g\" f\n",
+                )
+                .to_string(),
+            }]),
+            port_forwards: Some(vec![
+                PortForward {
+                    guest_ip_must_be_zero: Some(true),
+                    guest_ip: None,
+                    guest_port: Some(6443),
+                    guest_port_range: None,
+                    guest_socket: None,
+                    host_ip: Some("127.0.0.1".to_string()),
+                    host_port: Some(6443),
+                    host_port_range: None,
+                    host_socket: None,
+                    proto: Some("tcp".to_string()),
+                    ignore: None,
+                },
+                PortForward {
+                    guest_ip_must_be_zero: None,
+                    guest_ip: None,
+                    guest_port: None,
+                    guest_port_range: None,
+                    guest_socket: Some("/var/run/docker.sock".to_string()),
+                    host_ip: None,
+                    host_port: None,
+                    host_port_range: None,
+                    host_socket: Some("/Users/dev/.lima/k0s/docker.sock".to_string()),
+                    proto: None,
+                    ignore: None,
+                },
+            ]),
+            ..Default::default()
+        };
+
+        config.retarget_instance(
+            "k0s",
+            "k0s-copy",
+            "/Users/dev/.lima/k0s",
+            "/Users/dev/.lima/k0s-copy",
+        );
+        let remapped = config.remap_host_ports(|port| port == 6443 || port == 6444);
+
+        assert_eq!(remapped, vec![(6443, 6445)]);
+        let forwards = config.port_forwards.as_ref().unwrap();
+        assert_eq!(forwards[0].host_port, Some(6445));
+        assert_eq!(
+            forwards[1].host_socket.as_deref(),
+            Some("/Users/dev/.lima/k0s-copy/docker.sock")
+        );
+        let script = &config.provision.as_ref().unwrap()[0].script;
+        assert!(script.contains("server: https://127.0.0.1:6445|"));
+        assert!(script.contains("name: k0s-copy/g"));
+        assert!(script.contains("current-context: k0s-copy/g"));
+        assert!(!script.contains("name: k0s/g"));
+    }
 }
@@ -1,15 +1,17 @@
+use crate::error::AppError;
 use crate::k8s_service::check_k0s_available;
+use crate::lima_backend::lima_backend;
 use crate::lima_config::{get_default_docker_lima_config, get_default_k0s_lima_config, LimaConfig};
 use crate::lima_config_service;
 use crate::lima_config_service::{
     append_to_shell_profile, check_env_sh_exists, get_kubeconfig_path, get_lima_yaml_path,
-    write_env_sh, write_lima_yaml,
+    read_lima_yaml, write_env_sh, write_lima_yaml,
 };
 use tauri::AppHandle;
 
 /// Detect orphaned 0ma env entries in shell profiles (instances that no longer exist)
 #[tauri::command]
-pub async fn detect_orphaned_env_entries_cmd(app: AppHandle) -> Result<Vec<String>, String> {
+pub async fn detect_orphaned_env_entries_cmd(app: AppHandle) -> Result<Vec<String>, AppError> {
     lima_config_service::detect_orphaned_env_entries(&app)
 }
 
@@ -18,7 +20,7 @@ pub async fn detect_orphaned_env_entries_cmd(app: AppHandle) -> Result<Vec<Strin
 pub async fn cleanup_orphaned_env_entries_cmd(
     app: AppHandle,
     instance_names: Vec<String>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     lima_config_service::cleanup_orphaned_env_entries(&app, &instance_names)
 }
 
@@ -28,15 +30,10 @@ pub async fn cleanup_orphaned_env_entries_cmd(
 pub async fn read_lima_yaml_cmd(
     app: AppHandle,
     instance_name: String,
-) -> Result<LimaConfig, String> {
-    let yaml_path = get_lima_yaml_path(&app, &instance_name)?;
-
+) -> Result<LimaConfig, AppError> {
     // If the file exists, read it
-    if yaml_path.exists() {
-        let yaml_content = std::fs::read_to_string(&yaml_path)
-            .map_err(|e| format!("Failed to read lima.yaml: {}", e))?;
-        return LimaConfig::from_yaml(&yaml_content)
-            .map_err(|e| format!("Failed to parse YAML: {}", e));
+    if get_lima_yaml_path(&app, &instance_name)?.exists() {
+        return read_lima_yaml(&app, &instance_name);
     }
 
     // Otherwise, generate and return the default config
@@ -49,7 +46,7 @@ pub async fn write_lima_yaml_cmd(
     app: AppHandle,
     config: LimaConfig,
     instance_name: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     write_lima_yaml(&app, &config, &instance_name)
 }
 
@@ -58,7 +55,7 @@ pub async fn write_lima_yaml_cmd(
 pub async fn get_lima_yaml_path_cmd(
     app: AppHandle,
     instance_name: String,
-) -> Result<String, String> {
+) -> Result<String, AppError> {
     let path = get_lima_yaml_path(&app, &instance_name)?;
     Ok(path.to_string_lossy().to_string())
 }
@@ -68,7 +65,7 @@ pub async fn get_lima_yaml_path_cmd(
 pub async fn reset_lima_yaml_cmd(
     app: AppHandle,
     instance_name: String,
-) -> Result<LimaConfig, String> {
+) -> Result<LimaConfig, AppError> {
     // Generate the default config
     let default_config = get_default_k0s_lima_config(&app, &instance_name, true, true)?;
 
@@ -86,7 +83,7 @@ pub async fn get_default_k0s_lima_config_yaml_cmd(
     instance_name: String,
     install_helm: Option<bool>,
     install_local_path_provisioner: Option<bool>,
-) -> Result<LimaConfig, String> {
+) -> Result<LimaConfig, AppError> {
     get_default_k0s_lima_config(
         &app,
         &instance_name,
@@ -100,7 +97,7 @@ pub async fn get_default_k0s_lima_config_yaml_cmd(
 pub async fn get_default_docker_lima_config_yaml_cmd(
     app: AppHandle,
     instance_name: String,
-) -> Result<LimaConfig, String> {
+) -> Result<LimaConfig, AppError> {
     get_default_docker_lima_config(&app, &instance_name)
 }
 
@@ -109,24 +106,26 @@ pub async fn get_default_docker_lima_config_yaml_cmd(
 pub async fn get_kubeconfig_path_cmd(
     app: AppHandle,
     instance_name: String,
-) -> Result<String, String> {
+) -> Result<String, AppError> {
     let kubeconfig_path = get_kubeconfig_path(&app, &instance_name)?;
     Ok(kubeconfig_path.to_string_lossy().to_string())
 }
 
 /// Convert LimaConfig to YAML string for display
 #[tauri::command]
-pub async fn convert_config_to_yaml_cmd(config: LimaConfig) -> Result<String, String> {
+pub async fn convert_config_to_yaml_cmd(config: LimaConfig) -> Result<String, AppError> {
     config
         .to_yaml_pretty()
-        .map_err(|e| format!("Failed to convert config to YAML: {}", e))
+        .map_err(|e| AppError::parse("Failed to convert config to YAML", e))
 }
 
 /// Write env.sh for the given instance and return its absolute path.
 /// Automatically detects whether k8s (k0s/kubectl) is available in the instance.
 #[tauri::command]
-pub async fn write_env_sh_cmd(app: AppHandle, instance_name: String) -> Result<String, String> {
-    let k8s_available = check_k0s_available(&instance_name).unwrap_or(false);
+pub async fn write_env_sh_cmd(app: AppHandle, instance_name: String) -> Result<String, AppError> {
+    let k8s_available = check_k0s_available(lima_backend(&app).as_ref(), &instance_name)
+        .await
+        .unwrap_or(false);
     write_env_sh(&app, &instance_name, k8s_available)
 }
 
@@ -135,7 +134,7 @@ pub async fn write_env_sh_cmd(app: AppHandle, instance_name: String) -> Result<S
 pub async fn check_env_sh_exists_cmd(
     app: AppHandle,
     instance_name: String,
-) -> Result<bool, String> {
+) -> Result<bool, AppError> {
     check_env_sh_exists(&app, &instance_name)
 }
 
@@ -144,6 +143,6 @@ pub async fn check_env_sh_exists_cmd(
 pub async fn append_env_to_shell_profile_cmd(
     app: AppHandle,
     instance_name: String,
-) -> Result<String, String> {
+) -> Result<String, AppError> {
     append_to_shell_profile(&app, &instance_name)
 }
@@ -1,7 +1,8 @@
+use crate::error::AppError;
 use crate::lima_config::LimaConfig;
-use crate::yaml_handler::{get_instance_dir, get_yaml_path, write_yaml};
+use crate::yaml_handler::{get_home_dir, get_instance_dir, get_yaml_path, write_yaml};
 use std::os::unix::fs::PermissionsExt;
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 
 /// The standard filename for Lima configuration for an instance
 /// limactl uses `~/.lima/<instance_name>/lima.yaml` by default
@@ -12,7 +13,7 @@ pub fn write_lima_yaml<R: tauri::Runtime>(
     app: &AppHandle<R>,
     config: &LimaConfig,
     instance_name: &str,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     // Rosetta is only valid with vmType "vz"; clear it for other vm types
     let mut config = config.clone();
     if config.vm_type.as_deref() != Some("vz") {
@@ -21,7 +22,7 @@ pub fn write_lima_yaml<R: tauri::Runtime>(
 
     let yaml_content = config
         .to_yaml_pretty()
-        .map_err(|e| format!("Failed to serialize YAML: {}", e))?;
+        .map_err(|e| AppError::parse("Failed to serialize YAML", e))?;
     write_yaml(app, instance_name, LIMA_CONFIG_FILENAME, yaml_content)
 }
 
@@ -29,16 +30,27 @@ pub fn write_lima_yaml<R: tauri::Runtime>(
 pub fn get_lima_yaml_path<R: tauri::Runtime>(
     app: &AppHandle<R>,
     instance_name: &str,
-) -> Result<std::path::PathBuf, String> {
+) -> Result<std::path::PathBuf, AppError> {
     get_yaml_path(app, instance_name, LIMA_CONFIG_FILENAME)
 }
 
+/// Read and parse the lima.yaml of an existing instance (internal)
+pub fn read_lima_yaml<R: tauri::Runtime>(
+    app: &AppHandle<R>,
+    instance_name: &str,
+) -> Result<LimaConfig, AppError> {
+    let yaml_path = get_lima_yaml_path(app, instance_name)?;
+    let yaml_content = std::fs::read_to_string(&yaml_path)
+        .map_err(|e| AppError::io("Failed to read lima.yaml", e))?;
+    LimaConfig::from_yaml(&yaml_content).map_err(|e| AppError::parse("Failed to parse YAML", e))
+}
+
 /// Get the kubeconfig path for a specific instance (internal)
 /// Uses Lima instance directory: ~/.lima/<instance_name>/kubeconfig.yaml
 pub fn get_kubeconfig_path<R: tauri::Runtime>(
     app: &AppHandle<R>,
     instance_name: &str,
-) -> Result<std::path::PathBuf, String> {
+) -> Result<std::path::PathBuf, AppError> {
     let instance_dir = get_instance_dir(app, instance_name)?;
     Ok(instance_dir.join("kubeconfig.yaml"))
 }
@@ -49,62 +61,69 @@ pub fn write_env_sh<R: tauri::Runtime>(
     app: &AppHandle<R>,
     instance_name: &str,
     k8s: bool,
-) -> Result<String, String> {
+) -> Result<String, AppError> {
     let instance_dir = get_instance_dir(app, instance_name)?;
+    let home = get_home_dir(app)?;
+    // Instance dir as written into the scripts, honoring a custom LIMA_HOME
+    let dir = shell_path(&instance_dir, &home);
 
     // Write POSIX shell version (bash/zsh)
     let env_sh_path = instance_dir.join("env.sh");
     let mut sh_contents = format!(
-        "#!/bin/bash\n# 0ma environment for instance {name}\nexport DOCKER_HOST=\"unix://$HOME/.lima/{name}/docker.sock\"\n",
-        name = instance_name
+        "#!/bin/bash\n# 0ma environment for instance {name}\nexport DOCKER_HOST=\"unix://{dir}/docker.sock\"\n",
+        name = instance_name,
+        dir = dir
     );
     if k8s {
         sh_contents.push_str(&format!(
             concat!(
-                "export KUBECONFIG=\"$HOME/.lima/{name}/kubeconfig.yaml\"\n",
+                "export KUBECONFIG=\"{dir}/kubeconfig.yaml\"\n",
                 "\n",
                 "# Symlink kubeconfig to ~/.kube/ for tools like Lens\n",
                 "if [ ! -L \"$HOME/.kube/{name}\" ]; then\n",
                 "  mkdir -p \"$HOME/.kube\"\n",
-                "  ln -sf \"$HOME/.lima/{name}/kubeconfig.yaml\" \"$HOME/.kube/{name}\"\n",
+                "  ln -sf \"{dir}/kubeconfig.yaml\" \"$HOME/.kube/{name}\"\n",
                 "fi\n",
             ),
-            name = instance_name
+            name = instance_name,
+            dir = dir
         ));
     }
 
     std::fs::write(&env_sh_path, &sh_contents)
-        .map_err(|e| format!("Failed to write env.sh: {}", e))?;
+        .map_err(|e| AppError::io("Failed to write env.sh", e))?;
 
     let perms = std::fs::Permissions::from_mode(0o755);
     std::fs::set_permissions(&env_sh_path, perms.clone())
-        .map_err(|e| format!("Failed to set env.sh permissions: {}", e))?;
+        .map_err(|e| AppError::io("Failed to set env.sh permissions", e))?;
 
     // Write fish version
     let env_fish_path = instance_dir.join("env.fish");
     let mut fish_contents = format!(
-        "# 0ma environment for instance {name}\nset -gx DOCKER_HOST \"unix://$HOME/.lima/{name}/docker.sock\"\n",
-        name = instance_name
+        "# 0ma environment for instance {name}\nset -gx DOCKER_HOST \"unix://{dir}/docker.sock\"\n",
+        name = instance_name,
+        dir = dir
     );
     if k8s {
         fish_contents.push_str(&format!(
             concat!(
-                "set -gx KUBECONFIG \"$HOME/.lima/{name}/kubeconfig.yaml\"\n",
+                "set -gx KUBECONFIG \"{dir}/kubeconfig.yaml\"\n",
                 "\n",
                 "# Symlink kubeconfig to ~/.kube/ for tools like Lens\n",
                 "if not test -L \"$HOME/.kube/{name}\"\n",
                 "  mkdir -p \"$HOME/.kube\"\n",
-                "  ln -sf \"$HOME/.lima/{name}/kubeconfig.yaml\" \"$HOME/.kube/{name}\"\n",
+                "  ln -sf \"{dir}/kubeconfig.yaml\" \"$HOME/.kube/{name}\"\n",
                 "end\n",
             ),
-            name = instance_name
+            name = instance_name,
+            dir = dir
         ));
     }
 
     std::fs::write(&env_fish_path, &fish_contents)
-        .map_err(|e| format!("Failed to write env.fish: {}", e))?;
+        .map_err(|e| AppError::io("Failed to write env.fish", e))?;
     std::fs::set_permissions(&env_fish_path, perms)
-        .map_err(|e| format!("Failed to set env.fish permissions: {}", e))?;
+        .map_err(|e| AppError::io("Failed to set env.fish permissions", e))?;
 
     // Return the path appropriate for the user's shell
     let shell = std::env::var("SHELL").unwrap_or_default();
@@ -117,11 +136,19 @@ pub fn write_env_sh<R: tauri::Runtime>(
     Ok(result_path.to_string_lossy().to_string())
 }
 
+/// Render `path` for a shell script, using `$HOME` for paths under the home directory
+fn shell_path(path: &std::path::Path, home: &std::path::Path) -> String {
+    match path.strip_prefix(home) {
+        Ok(rest) => format!("$HOME/{}", rest.to_string_lossy()),
+        Err(_) => path.to_string_lossy().to_string(),
+    }
+}
+
 /// Check whether the env source line is present in the user's shell profile.
 pub fn check_env_sh_exists<R: tauri::Runtime>(
     app: &AppHandle<R>,
     instance_name: &str,
-) -> Result<bool, String> {
+) -> Result<bool, AppError> {
     let instance_dir = get_instance_dir(app, instance_name)?;
     let shell = std::env::var("SHELL").unwrap_or_default();
     let is_fish = shell.contains("fish");
@@ -139,10 +166,7 @@ pub fn check_env_sh_exists<R: tauri::Runtime>(
         format!(r#"[ -f "{}" ] && source "{}""#, env_path_str, env_path_str)
     };
 
-    let home = app
-        .path()
-        .home_dir()
-        .map_err(|e| format!("Failed to get home directory: {}", e))?;
+    let home = get_home_dir(app)?;
 
     let profile_path = if is_fish {
         home.join(".config/fish/config.fish")
@@ -154,7 +178,7 @@ pub fn check_env_sh_exists<R: tauri::Runtime>(
 
     if profile_path.exists() {
         let existing = std::fs::read_to_string(&profile_path)
-            .map_err(|e| format!("Failed to read shell profile: {}", e))?;
+            .map_err(|e| AppError::io("Failed to read shell profile", e))?;
         return Ok(existing.contains(&source_line));
     }
 
@@ -167,7 +191,7 @@ pub fn check_env_sh_exists<R: tauri::Runtime>(
 pub fn append_to_shell_profile<R: tauri::Runtime>(
     app: &AppHandle<R>,
     instance_name: &str,
-) -> Result<String, String> {
+) -> Result<String, AppError> {
     let instance_dir = get_instance_dir(app, instance_name)?;
     let shell = std::env::var("SHELL").unwrap_or_default();
     let is_fish = shell.contains("fish");
@@ -185,10 +209,7 @@ pub fn append_to_shell_profile<R: tauri::Runtime>(
         format!(r#"[ -f "{}" ] && source "{}""#, env_path_str, env_path_str)
     };
 
-    let home = app
-        .path()
-        .home_dir()
-        .map_err(|e| format!("Failed to get home directory: {}", e))?;
+    let home = get_home_dir(app)?;
 
     let (profile_path, profile_display) = if is_fish {
         (
@@ -203,7 +224,7 @@ pub fn append_to_shell_profile<R: tauri::Runtime>(
 
     if profile_path.exists() {
         let existing = std::fs::read_to_string(&profile_path)
-            .map_err(|e| format!("Failed to read {}: {}", profile_display, e))?;
+            .map_err(|e| AppError::io(&format!("Failed to read {}", profile_display), e))?;
         if existing.contains(&source_line) {
             return Ok(format!("Source line already present in {profile_display}"));
         }
@@ -211,8 +232,12 @@ pub fn append_to_shell_profile<R: tauri::Runtime>(
 
     // Ensure parent directory exists (needed for fish: ~/.config/fish/)
     if let Some(parent) = profile_path.parent() {
-        std::fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create directory for {}: {}", profile_display, e))?;
+        std::fs::create_dir_all(parent).map_err(|e| {
+            AppError::io(
+                &format!("Failed to create directory for {}", profile_display),
+                e,
+            )
+        })?;
     }
 
     use std::io::Write;
@@ -220,12 +245,12 @@ pub fn append_to_shell_profile<R: tauri::Runtime>(
         .create(true)
         .append(true)
         .open(&profile_path)
-        .map_err(|e| format!("Failed to open {}: {}", profile_display, e))?;
+        .map_err(|e| AppError::io(&format!("Failed to open {}", profile_display), e))?;
 
     writeln!(file, "\n# 0ma environment for instance {}", instance_name)
-        .map_err(|e| format!("Failed to write to {}: {}", profile_display, e))?;
+        .map_err(|e| AppError::io(&format!("Failed to write to {}", profile_display), e))?;
     writeln!(file, "{}", source_line)
-        .map_err(|e| format!("Failed to write to {}: {}", profile_display, e))?;
+        .map_err(|e| AppError::io(&format!("Failed to write to {}", profile_display), e))?;
 
     Ok(format!("Added source line to {profile_display}"))
 }
@@ -236,11 +261,8 @@ pub fn append_to_shell_profile<R: tauri::Runtime>(
 pub fn cleanup_env_on_delete<R: tauri::Runtime>(
     app: &AppHandle<R>,
     instance_name: &str,
-) -> Result<(), String> {
-    let home = app
-        .path()
-        .home_dir()
-        .map_err(|e| format!("Failed to get home directory: {}", e))?;
+) -> Result<(), AppError> {
+    let home = get_home_dir(app)?;
 
     // 1. Remove source lines from shell profiles
     let instance_dir = get_instance_dir(app, instance_name)?;
@@ -293,6 +315,123 @@ pub fn cleanup_env_on_delete<R: tauri::Runtime>(
 
 const COMMENT_PREFIX: &str = "# 0ma environment for instance ";
 
+/// Rewrite the 0ma lines of a shell profile for a renamed instance: the comment marker and
+/// source lines pointing at the old instance dir's env files.
+/// Returns `None` if the profile has no lines for the old instance.
+fn rename_profile_env_lines(
+    content: &str,
+    old_name: &str,
+    new_name: &str,
+    old_dir: &std::path::Path,
+    new_dir: &std::path::Path,
+) -> Option<String> {
+    let old_marker = format!("{}{}", COMMENT_PREFIX, old_name);
+    let new_marker = format!("{}{}", COMMENT_PREFIX, new_name);
+    let env_files = ["env.sh", "env.fish"].map(|file| {
+        (
+            old_dir.join(file).to_string_lossy().to_string(),
+            new_dir.join(file).to_string_lossy().to_string(),
+        )
+    });
+
+    let mut changed = false;
+    let lines: Vec<String> = content
+        .lines()
+        .map(|line| {
+            if line.trim() == old_marker {
+                changed = true;
+                return line.replace(&old_marker, &new_marker);
+            }
+            let mut line = line.to_string();
+            for (old_path, new_path) in &env_files {
+                if line.contains(old_path.as_str()) {
+                    changed = true;
+                    line = line.replace(old_path.as_str(), new_path);
+                }
+            }
+            line
+        })
+        .collect();
+
+    changed.then(|| {
+        let mut new_content = lines.join("\n");
+        if content.ends_with('\n') {
+            new_content.push('\n');
+        }
+        new_content
+    })
+}
+
+/// Point the shell profile lines written by `append_to_shell_profile` at a renamed
+/// instance. Returns the original contents of every changed profile, for rollback.
+pub fn rename_env_in_shell_profiles<R: tauri::Runtime>(
+    app: &AppHandle<R>,
+    old_name: &str,
+    new_name: &str,
+    old_dir: &std::path::Path,
+    new_dir: &std::path::Path,
+) -> Result<Vec<(std::path::PathBuf, String)>, AppError> {
+    let home = get_home_dir(app)?;
+    let profiles = [
+        home.join(".zshrc"),
+        home.join(".bashrc"),
+        home.join(".config/fish/config.fish"),
+    ];
+
+    let mut originals = Vec::new();
+    for profile in profiles {
+        let Ok(content) = std::fs::read_to_string(&profile) else {
+            continue;
+        };
+        let Some(new_content) =
+            rename_profile_env_lines(&content, old_name, new_name, old_dir, new_dir)
+        else {
+            continue;
+        };
+        if let Err(e) = std::fs::write(&profile, new_content) {
+            restore_files(&originals);
+            return Err(AppError::io(
+                &format!("Failed to update {}", profile.display()),
+                e,
+            ));
+        }
+        originals.push((profile, content));
+    }
+    Ok(originals)
+}
+
+/// Write back files saved before a multi-step change, ignoring errors
+pub fn restore_files(originals: &[(std::path::PathBuf, String)]) {
+    for (path, content) in originals {
+        let _ = std::fs::write(path, content);
+    }
+}
+
+/// Replace the `~/.kube/<old_name>` symlink with `~/.kube/<new_name>` pointing at the
+/// renamed instance's kubeconfig. Returns the old link target if there was a link.
+pub fn rename_kube_symlink<R: tauri::Runtime>(
+    app: &AppHandle<R>,
+    old_name: &str,
+    new_name: &str,
+) -> Result<Option<std::path::PathBuf>, AppError> {
+    let home = get_home_dir(app)?;
+    let old_link = home.join(".kube").join(old_name);
+    if !old_link.is_symlink() {
+        return Ok(None);
+    }
+
+    let old_target = std::fs::read_link(&old_link)
+        .map_err(|e| AppError::io("Failed to read kubeconfig symlink", e))?;
+    let new_link = home.join(".kube").join(new_name);
+    std::os::unix::fs::symlink(get_kubeconfig_path(app, new_name)?, &new_link)
+        .map_err(|e| AppError::io("Failed to create kubeconfig symlink", e))?;
+    if let Err(e) = std::fs::remove_file(&old_link) {
+        let _ = std::fs::remove_file(&new_link);
+        return Err(AppError::io("Failed to remove old kubeconfig symlink", e));
+    }
+    Ok(Some(old_target))
+}
+
 /// Extract an instance name from a source line like:
 ///   `[ -f "/Users/x/.lima/foo/env.sh" ] && source "/Users/x/.lima/foo/env.sh"`
 ///   `if test -f "/Users/x/.lima/foo/env.fish"; source "/Users/x/.lima/foo/env.fish"; end`
@@ -316,11 +455,8 @@ fn extract_instance_from_source_line(line: &str, lima_home: &std::path::Path) ->
 /// 3. Dangling ~/.kube/ symlinks pointing into lima_home
 pub fn detect_orphaned_env_entries<R: tauri::Runtime>(
     app: &AppHandle<R>,
-) -> Result<Vec<String>, String> {
-    let home = app
-        .path()
-        .home_dir()
-        .map_err(|e| format!("Failed to get home directory: {}", e))?;
+) -> Result<Vec<String>, AppError> {
+    let home = get_home_dir(app)?;
 
     let lima_home = crate::yaml_handler::get_lima_home(app)?;
 
@@ -394,9 +530,48 @@ pub fn detect_orphaned_env_entries<R: tauri::Runtime>(
 pub fn cleanup_orphaned_env_entries<R: tauri::Runtime>(
     app: &AppHandle<R>,
     instance_names: &[String],
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     for name in instance_names {
         cleanup_env_on_delete(app, name)?;
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_rename_profile_env_lines() {
+        let old_dir = Path::new("/Users/dev/.lima/k0s");
+        let new_dir = Path::new("/Users/dev/.lima/cluster");
+        let content = concat!(
+            "export PATH=$PATH:/opt/bin\n",
+            "\n",
+            "# 0ma environment for instance k0s\n",
+            "[ -f \"/Users/dev/.lima/k0s/env.sh\" ] && source \"/Users/dev/.lima/k0s/env.sh\"\n",
+            "# 0ma environment for instance k0s-old\n",
+            "[ -f \"/Users/dev/.lima/k0s-old/env.sh\" ] && source \"/Users/dev/.lima/k0s-old/env.sh\"\n",
+        );
+
+        let renamed =
+            rename_profile_env_lines(content, "k0s", "cluster", old_dir, new_dir).unwrap();
+        assert_eq!(
+            renamed,
+            concat!(
+                "export PATH=$PATH:/opt/bin\n",
+                "\n",
+                "# 0ma environment for instance cluster\n",
+                "[ -f \"/Users/dev/.lima/cluster/env.sh\" ] && source \"/Users/dev/.lima/cluster/env.sh\"\n",
+                "# 0ma environment for instance k0s-old\n",
+                "[ -f \"/Users/dev/.lima/k0s-old/env.sh\" ] && source \"/Users/dev/.lima/k0s-old/env.sh\"\n",
+            )
+        );
+
+        assert_eq!(
+            rename_profile_env_lines("alias k=kubectl\n", "k0s", "cluster", old_dir, new_dir),
+            None
+        );
+    }
+}
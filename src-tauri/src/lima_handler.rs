@@ -1,21 +1,63 @@
+use crate::error::AppError;
+use crate::lima_backend::lima_backend;
+use crate::lima_compat_service::{self, LimaCompatibility};
+use crate::lima_config::LimaConfig;
+use crate::lima_info_service::{self, HostLimaInfo};
+use crate::lima_install_service::{self, LimaArchiveSource};
 use crate::lima_service;
+use tauri::AppHandle;
 
 #[tauri::command]
-pub async fn lima_version_cmd() -> Result<String, String> {
-    lima_service::get_lima_version()
+pub async fn lima_version_cmd(app: AppHandle) -> Result<String, AppError> {
+    lima_service::get_lima_version(lima_backend(&app).as_ref()).await
 }
 
+/// Check whether the installed Lima supports `config` (minimumLimaVersion and used fields)
 #[tauri::command]
-pub async fn get_system_capabilities_cmd() -> lima_service::SystemCapabilities {
-    lima_service::get_system_capabilities()
+pub async fn check_lima_compatibility_cmd(
+    app: AppHandle,
+    config: LimaConfig,
+) -> Result<LimaCompatibility, AppError> {
+    lima_compat_service::get_lima_compatibility(lima_backend(&app).as_ref(), &config).await
 }
 
 #[tauri::command]
-pub async fn check_lima_installed_cmd() -> bool {
-    lima_service::find_lima_executable().is_some()
+pub async fn get_system_capabilities_cmd(app: AppHandle) -> lima_service::SystemCapabilities {
+    let host_info = lima_info_service::get_host_lima_info(&app).await.ok();
+    lima_service::get_system_capabilities(host_info.as_ref())
 }
 
+/// Get the parsed `limactl info` (version, LIMA_HOME, vmTypes, templates, guest agents)
 #[tauri::command]
-pub async fn install_lima_cmd() -> Result<String, String> {
-    lima_service::install_lima().await
+pub async fn get_host_lima_info_cmd(app: AppHandle) -> Result<HostLimaInfo, AppError> {
+    lima_info_service::get_host_lima_info(&app).await
+}
+
+#[tauri::command]
+pub async fn check_lima_installed_cmd(app: AppHandle) -> bool {
+    // Re-resolve when missing, so a Lima installed outside the app is picked up
+    lima_service::lima_paths(&app).limactl.is_some()
+        || lima_service::refresh_lima_paths(&app).limactl.is_some()
+}
+
+/// Install Lima with Homebrew, or from the release archive when Homebrew is missing
+#[tauri::command]
+pub async fn install_lima_cmd(app: AppHandle) -> Result<String, AppError> {
+    let Some(brew) = lima_service::lima_paths(&app).brew else {
+        lima_install_service::install_lima_from_archive(&app, None).await?;
+        return Ok("Lima installed successfully".to_string());
+    };
+    let result = lima_service::install_lima(Some(brew)).await?;
+    lima_service::refresh_lima_paths(&app);
+    Ok(result)
+}
+
+/// Install Lima from a release archive (URL or local file, SHA-256 verified)
+/// into the app-managed prefix. Without a source, the pinned release for this host is used.
+#[tauri::command]
+pub async fn install_lima_from_archive_cmd(
+    app: AppHandle,
+    source: Option<LimaArchiveSource>,
+) -> Result<String, AppError> {
+    lima_install_service::install_lima_from_archive(&app, source).await
 }
@@ -0,0 +1,173 @@
+use crate::error::AppError;
+use crate::lima_backend::lima_backend;
+use crate::lima_config::LimaConfig;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Typed `limactl info` output
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostLimaInfo {
+    /// Lima version, e.g. "2.0.0"
+    pub version: String,
+    /// LIMA_HOME used by limactl
+    pub lima_home: PathBuf,
+    /// VM drivers available on this host, e.g. ["qemu", "vz", "krunkit"]
+    #[serde(default)]
+    pub vm_types: Vec<String>,
+    /// The template Lima applies when none is given, `None` if it could not be parsed
+    #[serde(default, deserialize_with = "lenient")]
+    pub default_template: Option<LimaConfig>,
+    /// Built-in templates (`template://<name>`)
+    #[serde(default)]
+    pub templates: Vec<TemplateInfo>,
+    /// Guest agent binaries keyed by guest architecture
+    #[serde(default)]
+    pub guest_agents: BTreeMap<String, GuestAgentInfo>,
+}
+
+/// A built-in Lima template
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateInfo {
+    pub name: String,
+    /// Path of the template YAML on the host
+    pub location: String,
+}
+
+/// A guest agent binary shipped with Lima
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuestAgentInfo {
+    pub location: String,
+}
+
+impl HostLimaInfo {
+    /// Whether Lima can drive VMs of the given type on this host
+    pub fn supports_vm_type(&self, vm_type: &str) -> bool {
+        self.vm_types.iter().any(|t| t == vm_type)
+    }
+}
+
+/// Deserialize into `Some(T)`, or `None` if the value does not fit `T`.
+/// The default template carries many fields `LimaConfig` does not model.
+fn lenient<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: serde::de::DeserializeOwned,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+    Ok(serde_json::from_value(value).ok())
+}
+
+/// Parse `limactl info` JSON
+pub fn parse_lima_info(stdout: &str) -> Result<HostLimaInfo, AppError> {
+    serde_json::from_str(stdout).map_err(|e| AppError::parse("Failed to parse limactl info", e))
+}
+
+/// Managed cache of `limactl info`, filled on first use and cleared when Lima paths change
+#[derive(Default)]
+pub struct HostLimaInfoState(pub tokio::sync::Mutex<Option<HostLimaInfo>>);
+
+/// Get `limactl info`, querying the backend once per session
+pub async fn get_host_lima_info<R: Runtime>(app: &AppHandle<R>) -> Result<HostLimaInfo, AppError> {
+    let Some(state) = app.try_state::<HostLimaInfoState>() else {
+        return query_host_lima_info(app).await;
+    };
+
+    // Hold the lock while querying so concurrent callers share one limactl run
+    let mut cached = state.0.lock().await;
+    if let Some(info) = cached.as_ref() {
+        return Ok(info.clone());
+    }
+    let info = query_host_lima_info(app).await?;
+    *cached = Some(info.clone());
+    Ok(info)
+}
+
+/// Forget the cached info, e.g. after Lima was installed or its paths changed
+pub fn invalidate_host_lima_info<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(state) = app.try_state::<HostLimaInfoState>() {
+        // If a query holds the lock it already runs against the new paths
+        if let Ok(mut cached) = state.0.try_lock() {
+            *cached = None;
+        }
+    }
+}
+
+async fn query_host_lima_info<R: Runtime>(app: &AppHandle<R>) -> Result<HostLimaInfo, AppError> {
+    let output = lima_backend(app).info().await?;
+    if !output.success() {
+        return Err(AppError::command_failed(
+            "limactl info",
+            output.code,
+            &output.stderr,
+        ));
+    }
+    parse_lima_info(&output.stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_lima_backend::FakeLimaBackend;
+    use crate::lima_backend::LimaBackendState;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_parse_lima_info() {
+        let stdout = r#"{
+            "version": "2.0.0",
+            "templates": [
+                {"name": "default", "location": "/opt/homebrew/share/lima/templates/default.yaml"},
+                {"name": "docker", "location": "/opt/homebrew/share/lima/templates/docker.yaml"}
+            ],
+            "defaultTemplate": {
+                "vmType": "vz",
+                "cpus": 4,
+                "memory": "4GiB",
+                "disk": "100GiB",
+                "containerd": {"system": false, "user": true},
+                "ssh": {"localPort": 0, "loadDotSSHPubKeys": false}
+            },
+            "limaHome": "/Users/dev/.lima",
+            "vmTypes": ["krunkit", "qemu", "vz"],
+            "guestAgents": {
+                "aarch64": {"location": "/opt/homebrew/share/lima/lima-guestagent.Linux-aarch64.gz"}
+            }
+        }"#;
+
+        let info = parse_lima_info(stdout).unwrap();
+        assert_eq!(info.version, "2.0.0");
+        assert_eq!(info.lima_home, PathBuf::from("/Users/dev/.lima"));
+        assert!(info.supports_vm_type("krunkit"));
+        assert!(!info.supports_vm_type("wsl2"));
+        assert_eq!(info.templates[1].name, "docker");
+        assert_eq!(
+            info.default_template.as_ref().unwrap().vm_type.as_deref(),
+            Some("vz")
+        );
+        assert!(info.guest_agents.contains_key("aarch64"));
+
+        // A default template that does not fit LimaConfig does not fail the whole parse
+        let stdout =
+            r#"{"version": "2.0.0", "limaHome": "/tmp", "defaultTemplate": {"cpus": "many"}}"#;
+        let info = parse_lima_info(stdout).unwrap();
+        assert!(info.default_template.is_none());
+        assert!(info.vm_types.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_host_lima_info_from_backend() {
+        let backend = FakeLimaBackend::new();
+        let lima_home = crate::lima_backend::LimaBackend::lima_home(&backend).unwrap();
+        let app = tauri::test::mock_app();
+        app.manage(LimaBackendState(Arc::new(backend)));
+        app.manage(HostLimaInfoState::default());
+
+        let info = get_host_lima_info(app.handle()).await.unwrap();
+        assert_eq!(info.lima_home, lima_home);
+        assert!(info.supports_vm_type("vz"));
+        assert!(app.state::<HostLimaInfoState>().0.lock().await.is_some());
+    }
+}
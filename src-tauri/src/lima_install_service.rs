@@ -0,0 +1,500 @@
+use crate::error::AppError;
+use crate::http_client::http_get;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tokio::io::AsyncWriteExt;
+
+/// Lima release installed when no archive is given
+pub const DEFAULT_LIMA_RELEASE: &str = "2.0.0";
+
+/// Checksum file published next to every Lima release archive
+const CHECKSUMS_FILENAME: &str = "SHA256SUMS";
+
+/// Emit a progress event at most every this many downloaded bytes
+const PROGRESS_STEP_BYTES: u64 = 1024 * 1024;
+
+/// App-managed install prefix (`<app data>/lima`), set once during setup
+static MANAGED_PREFIX: OnceLock<PathBuf> = OnceLock::new();
+
+/// Where to get the Lima release archive from
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum LimaArchiveSource {
+    /// Download from a URL. Without `sha256`, the checksum is looked up in the
+    /// `SHA256SUMS` file next to the archive.
+    #[serde(rename_all = "camelCase")]
+    Url { url: String, sha256: Option<String> },
+    /// Use an archive on disk. Without `sha256`, a sibling `SHA256SUMS` is required.
+    #[serde(rename_all = "camelCase")]
+    File {
+        path: String,
+        sha256: Option<String>,
+    },
+}
+
+#[derive(Clone, Serialize)]
+struct LimaInstallPayload {
+    phase: String,
+    message: String,
+    downloaded_bytes: u64,
+    total_bytes: Option<u64>,
+    message_id: String,
+    timestamp: String,
+}
+
+/// Emits `lima-install*` events, mirroring the instance lifecycle events
+struct InstallProgress<'a, R: Runtime> {
+    app: &'a AppHandle<R>,
+}
+
+impl<R: Runtime> InstallProgress<'_, R> {
+    fn emit(&self, event: &str, phase: &str, message: String, bytes: (u64, Option<u64>)) {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let _ = self.app.emit(
+            event,
+            LimaInstallPayload {
+                phase: phase.to_string(),
+                message,
+                downloaded_bytes: bytes.0,
+                total_bytes: bytes.1,
+                message_id: uuid::Uuid::new_v4().to_string(),
+                timestamp: timestamp.to_string(),
+            },
+        );
+    }
+
+    fn phase(&self, phase: &str, message: String) {
+        self.emit("lima-install-progress", phase, message, (0, None));
+    }
+}
+
+/// Remember the app-managed prefix so `find_lima_executable` searches it
+pub fn init_managed_prefix<R: Runtime>(app: &AppHandle<R>) {
+    match app.path().app_data_dir() {
+        Ok(dir) => {
+            let _ = MANAGED_PREFIX.set(dir.join("lima"));
+        }
+        Err(e) => log::warn!("No app data dir for managed Lima install: {}", e),
+    }
+}
+
+/// `limactl` inside the app-managed prefix, if Lima was installed there
+pub fn managed_limactl() -> Option<PathBuf> {
+    let limactl = MANAGED_PREFIX.get()?.join("bin").join("limactl");
+    limactl.is_file().then_some(limactl)
+}
+
+/// Release archive URL for the given version and host, e.g.
+/// `.../v2.0.0/lima-2.0.0-Darwin-arm64.tar.gz`
+pub fn default_lima_archive_url(version: &str, os: &str, arch: &str) -> String {
+    let os = match os {
+        "macos" => "Darwin",
+        "linux" => "Linux",
+        other => other,
+    };
+    // Lima names Apple Silicon builds arm64 and Linux ones aarch64
+    let arch = match (os, arch) {
+        ("Darwin", "aarch64") => "arm64",
+        (_, arch) => arch,
+    };
+    format!(
+        "https://github.com/lima-vm/lima/releases/download/v{version}/lima-{version}-{os}-{arch}.tar.gz"
+    )
+}
+
+/// Find the checksum for `filename` in `sha256sum` style output (`<hex>  [*]<name>`)
+pub fn parse_sha256sums(content: &str, filename: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let (hash, name) = line.trim().split_once(char::is_whitespace)?;
+        let name = name.trim_start().trim_start_matches('*');
+        (name == filename).then(|| hash.to_lowercase())
+    })
+}
+
+/// Install Lima from a release archive into the app-managed prefix.
+/// Streams `lima-install`, `lima-install-progress`, `lima-install-success` and
+/// `lima-install-error` events. Returns the installed `limactl --version` output.
+pub async fn install_lima_from_archive<R: Runtime>(
+    app: &AppHandle<R>,
+    source: Option<LimaArchiveSource>,
+) -> Result<String, AppError> {
+    let progress = InstallProgress { app };
+    let source = source.unwrap_or_else(|| LimaArchiveSource::Url {
+        url: default_lima_archive_url(
+            DEFAULT_LIMA_RELEASE,
+            std::env::consts::OS,
+            std::env::consts::ARCH,
+        ),
+        sha256: None,
+    });
+    progress.emit(
+        "lima-install",
+        "start",
+        "Installing Lima from release archive...".to_string(),
+        (0, None),
+    );
+
+    let result = install(app, &progress, source).await;
+    match &result {
+        Ok(version) => progress.emit(
+            "lima-install-success",
+            "done",
+            format!("Installed {}", version),
+            (0, None),
+        ),
+        Err(e) => progress.emit("lima-install-error", "error", e.to_string(), (0, None)),
+    }
+    result
+}
+
+async fn install<R: Runtime>(
+    app: &AppHandle<R>,
+    progress: &InstallProgress<'_, R>,
+    source: LimaArchiveSource,
+) -> Result<String, AppError> {
+    let prefix = MANAGED_PREFIX.get().cloned().ok_or_else(|| AppError::Io {
+        message: "App data directory is not available".to_string(),
+    })?;
+    let temp_dir = app
+        .path()
+        .temp_dir()
+        .map_err(|e| AppError::io("Failed to get temp directory", e))?;
+
+    // Download (or locate) the archive and determine the expected checksum
+    let (archive, expected, downloaded) = match source {
+        LimaArchiveSource::Url { url, sha256 } => {
+            // Resolve the checksum first so a failed lookup does not leave a download behind
+            let expected = match sha256 {
+                Some(sha256) => sha256,
+                None => fetch_published_checksum(&url).await?,
+            };
+            let archive = temp_dir.join(format!("0ma-lima-{}.tar.gz", uuid::Uuid::new_v4()));
+            if let Err(e) = download(progress, &url, &archive).await {
+                let _ = std::fs::remove_file(&archive);
+                return Err(e);
+            }
+            (archive, expected, true)
+        }
+        LimaArchiveSource::File { path, sha256 } => {
+            let archive = PathBuf::from(path);
+            let expected = match sha256 {
+                Some(sha256) => sha256,
+                None => local_published_checksum(&archive)?,
+            };
+            (archive, expected, false)
+        }
+    };
+
+    let result = verify_and_extract(progress, &archive, expected, &prefix).await;
+    if downloaded {
+        let _ = std::fs::remove_file(&archive);
+    }
+    let limactl = result?;
+
+    let output = tokio::process::Command::new(&limactl)
+        .arg("--version")
+        .output()
+        .await
+        .map_err(|e| AppError::io("Failed to run installed limactl", e))?;
+    if !output.status.success() {
+        return Err(AppError::command_failed(
+            "limactl --version",
+            output.status.code(),
+            &String::from_utf8_lossy(&output.stderr),
+        ));
+    }
+
+    // Pick up the new limactl unless the user pinned another one
+    crate::lima_service::refresh_lima_paths(app);
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+async fn verify_and_extract<R: Runtime>(
+    progress: &InstallProgress<'_, R>,
+    archive: &Path,
+    expected: String,
+    prefix: &Path,
+) -> Result<PathBuf, AppError> {
+    progress.phase("verify", "Verifying SHA-256 checksum...".to_string());
+    let path = archive.to_path_buf();
+    tokio::task::spawn_blocking(move || verify_archive(&path, &expected))
+        .await
+        .map_err(|e| AppError::io("Checksum task failed", e))??;
+
+    progress.phase("extract", format!("Extracting to {}...", prefix.display()));
+    let (path, prefix) = (archive.to_path_buf(), prefix.to_path_buf());
+    tokio::task::spawn_blocking(move || extract_archive(&path, &prefix))
+        .await
+        .map_err(|e| AppError::io("Extract task failed", e))?
+}
+
+/// Stream `url` into `dest`, emitting download progress
+async fn download<R: Runtime>(
+    progress: &InstallProgress<'_, R>,
+    url: &str,
+    dest: &Path,
+) -> Result<(), AppError> {
+    let mut response = http_get(url).await?;
+    let total = response.content_length();
+    let mut file = tokio::fs::File::create(dest)
+        .await
+        .map_err(|e| AppError::io("Failed to create download file", e))?;
+
+    let mut downloaded = 0u64;
+    let mut reported = 0u64;
+    progress.emit(
+        "lima-install-progress",
+        "download",
+        format!("Downloading {}", url),
+        (0, total),
+    );
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| AppError::io(&format!("Failed to download {}", url), e))?
+    {
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| AppError::io("Failed to write download file", e))?;
+        downloaded += chunk.len() as u64;
+        if downloaded - reported >= PROGRESS_STEP_BYTES || Some(downloaded) == total {
+            reported = downloaded;
+            let message = match total {
+                Some(total) if total > 0 => format!("Downloaded {}%", downloaded * 100 / total),
+                _ => format!("Downloaded {} MiB", downloaded / (1024 * 1024)),
+            };
+            progress.emit(
+                "lima-install-progress",
+                "download",
+                message,
+                (downloaded, total),
+            );
+        }
+    }
+    file.flush()
+        .await
+        .map_err(|e| AppError::io("Failed to write download file", e))
+}
+
+/// Look up the archive's checksum in the release's `SHA256SUMS`
+async fn fetch_published_checksum(url: &str) -> Result<String, AppError> {
+    let (base, filename) = url.rsplit_once('/').ok_or_else(|| AppError::ParseError {
+        message: format!("Invalid archive URL: {}", url),
+    })?;
+    let sums_url = format!("{}/{}", base, CHECKSUMS_FILENAME);
+    let sums = http_get(&sums_url)
+        .await?
+        .text()
+        .await
+        .map_err(|e| AppError::io(&format!("Failed to download {}", sums_url), e))?;
+    parse_sha256sums(&sums, filename).ok_or_else(|| AppError::ParseError {
+        message: format!("No checksum for {} in {}", filename, sums_url),
+    })
+}
+
+/// Look up a local archive's checksum in a `SHA256SUMS` next to it
+fn local_published_checksum(archive: &Path) -> Result<String, AppError> {
+    let sums_path = archive.with_file_name(CHECKSUMS_FILENAME);
+    let filename = archive
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let sums = std::fs::read_to_string(&sums_path).map_err(|e| {
+        AppError::io(
+            &format!(
+                "A SHA-256 checksum is required; failed to read {}",
+                sums_path.display()
+            ),
+            e,
+        )
+    })?;
+    parse_sha256sums(&sums, &filename).ok_or_else(|| AppError::ParseError {
+        message: format!("No checksum for {} in {}", filename, sums_path.display()),
+    })
+}
+
+/// SHA-256 of a file as lowercase hex
+fn sha256_file(path: &Path) -> Result<String, AppError> {
+    let mut file = std::fs::File::open(path)
+        .map_err(|e| AppError::io(&format!("Failed to open {}", path.display()), e))?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = file
+            .read(&mut buf)
+            .map_err(|e| AppError::io(&format!("Failed to read {}", path.display()), e))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Fail with `ChecksumMismatch` unless `archive` hashes to `expected_sha256`
+fn verify_archive(archive: &Path, expected_sha256: &str) -> Result<(), AppError> {
+    let expected = expected_sha256.trim().to_lowercase();
+    let actual = sha256_file(archive)?;
+    if actual != expected {
+        return Err(AppError::ChecksumMismatch { expected, actual });
+    }
+    Ok(())
+}
+
+/// Extract `archive` into `prefix`, replacing a previous install only once
+/// extraction succeeded and the archive turned out to contain `bin/limactl`.
+fn extract_archive(archive: &Path, prefix: &Path) -> Result<PathBuf, AppError> {
+    let parent = prefix.parent().ok_or_else(|| AppError::Io {
+        message: format!("Invalid install prefix: {}", prefix.display()),
+    })?;
+    std::fs::create_dir_all(parent)
+        .map_err(|e| AppError::io("Failed to create install directory", e))?;
+    let staging = parent.join(format!(".lima-staging-{}", uuid::Uuid::new_v4()));
+
+    let extracted = extract_tar_gz(archive, &staging).and_then(|_| {
+        let limactl = staging.join("bin").join("limactl");
+        if limactl.is_file() {
+            Ok(())
+        } else {
+            Err(AppError::ParseError {
+                message: "Archive does not contain bin/limactl".to_string(),
+            })
+        }
+    });
+    if let Err(e) = extracted {
+        let _ = std::fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    if prefix.exists() {
+        std::fs::remove_dir_all(prefix)
+            .map_err(|e| AppError::io("Failed to remove previous Lima install", e))?;
+    }
+    std::fs::rename(&staging, prefix)
+        .map_err(|e| AppError::io("Failed to move Lima into place", e))?;
+
+    Ok(prefix.join("bin").join("limactl"))
+}
+
+/// Extract a .tar.gz; `tar` refuses entries that would escape `dest`
+fn extract_tar_gz(archive: &Path, dest: &Path) -> Result<(), AppError> {
+    let file = std::fs::File::open(archive)
+        .map_err(|e| AppError::io(&format!("Failed to open {}", archive.display()), e))?;
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    tar.set_preserve_permissions(true);
+    tar.unpack(dest)
+        .map_err(|e| AppError::io("Failed to extract Lima archive", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("0ma-install-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Build a minimal Lima-like release archive and return its path and checksum
+    fn build_archive(dir: &Path, files: &[(&str, &str)]) -> (PathBuf, String) {
+        let path = dir.join("lima-2.0.0-Linux-x86_64.tar.gz");
+        let file = std::fs::File::create(&path).unwrap();
+        let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::fast());
+        let mut builder = tar::Builder::new(encoder);
+        for (name, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o755);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, name, content.as_bytes())
+                .unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+        let sha = sha256_file(&path).unwrap();
+        (path, sha)
+    }
+
+    #[test]
+    fn test_default_lima_archive_url() {
+        assert_eq!(
+            default_lima_archive_url("2.0.0", "macos", "aarch64"),
+            "https://github.com/lima-vm/lima/releases/download/v2.0.0/lima-2.0.0-Darwin-arm64.tar.gz"
+        );
+        assert_eq!(
+            default_lima_archive_url("2.0.0", "linux", "x86_64"),
+            "https://github.com/lima-vm/lima/releases/download/v2.0.0/lima-2.0.0-Linux-x86_64.tar.gz"
+        );
+    }
+
+    #[test]
+    fn test_parse_sha256sums() {
+        let sums =
+            "ABC123  lima-2.0.0-Darwin-arm64.tar.gz\ndef456 *lima-2.0.0-Linux-x86_64.tar.gz\n";
+        assert_eq!(
+            parse_sha256sums(sums, "lima-2.0.0-Darwin-arm64.tar.gz").as_deref(),
+            Some("abc123")
+        );
+        assert_eq!(
+            parse_sha256sums(sums, "lima-2.0.0-Linux-x86_64.tar.gz").as_deref(),
+            Some("def456")
+        );
+        assert_eq!(
+            parse_sha256sums(sums, "lima-2.0.0-Linux-aarch64.tar.gz"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_verify_and_extract_archive_replaces_prefix() {
+        let dir = temp_dir();
+        let prefix = dir.join("app-data").join("lima");
+        let (archive, sha) = build_archive(
+            &dir,
+            &[
+                ("bin/limactl", "#!/bin/sh\necho limactl version 2.0.0\n"),
+                ("share/lima/templates/default.yaml", "vmType: qemu\n"),
+            ],
+        );
+
+        // A wrong checksum leaves nothing behind
+        let err = verify_archive(&archive, &"0".repeat(64)).unwrap_err();
+        assert_eq!(err.kind(), "checksumMismatch");
+        assert!(!prefix.exists());
+
+        std::fs::create_dir_all(prefix.join("bin")).unwrap();
+        std::fs::write(prefix.join("stale"), "old install").unwrap();
+
+        verify_archive(&archive, &sha.to_uppercase()).unwrap();
+        let limactl = extract_archive(&archive, &prefix).unwrap();
+        assert_eq!(limactl, prefix.join("bin/limactl"));
+        assert!(limactl.is_file());
+        assert!(prefix.join("share/lima/templates/default.yaml").is_file());
+        assert!(!prefix.join("stale").exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_extract_archive_requires_limactl() {
+        let dir = temp_dir();
+        let prefix = dir.join("lima");
+        let (archive, sha) = build_archive(&dir, &[("README.md", "not lima")]);
+
+        verify_archive(&archive, &sha).unwrap();
+        let err = extract_archive(&archive, &prefix).unwrap_err();
+        assert_eq!(err.kind(), "parseError");
+        assert!(!prefix.exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
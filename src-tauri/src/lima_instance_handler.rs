@@ -1,3 +1,4 @@
+use crate::error::AppError;
 use crate::lima_config::LimaConfig;
 use crate::lima_instance_service;
 use tauri::AppHandle;
@@ -7,7 +8,7 @@ pub async fn create_lima_instance_cmd(
     app: AppHandle,
     config: LimaConfig,
     instance_name: String,
-) -> Result<String, String> {
+) -> Result<String, AppError> {
     lima_instance_service::create_lima_instance(app, config, instance_name).await
 }
 
@@ -15,7 +16,7 @@ pub async fn create_lima_instance_cmd(
 pub async fn start_lima_instance_cmd(
     app: AppHandle,
     instance_name: String,
-) -> Result<String, String> {
+) -> Result<String, AppError> {
     lima_instance_service::start_lima_instance(app, instance_name).await
 }
 
@@ -23,14 +24,51 @@ pub async fn start_lima_instance_cmd(
 pub async fn stop_lima_instance_cmd(
     app: AppHandle,
     instance_name: String,
-) -> Result<String, String> {
-    lima_instance_service::stop_lima_instance(app, instance_name).await
+    force: Option<bool>,
+) -> Result<String, AppError> {
+    lima_instance_service::stop_lima_instance(app, instance_name, force.unwrap_or(false)).await
 }
 
 #[tauri::command]
 pub async fn delete_lima_instance_cmd(
     app: AppHandle,
     instance_name: String,
-) -> Result<String, String> {
-    lima_instance_service::delete_lima_instance(app, instance_name).await
+    force: Option<bool>,
+) -> Result<String, AppError> {
+    lima_instance_service::delete_lima_instance(app, instance_name, force.unwrap_or(false)).await
+}
+
+#[tauri::command]
+pub async fn restart_lima_instance_cmd(
+    app: AppHandle,
+    instance_name: String,
+    config: Option<LimaConfig>,
+) -> Result<String, AppError> {
+    lima_instance_service::restart_lima_instance(app, instance_name, config).await
+}
+
+#[tauri::command]
+pub async fn recreate_lima_instance_cmd(
+    app: AppHandle,
+    instance_name: String,
+) -> Result<String, AppError> {
+    lima_instance_service::recreate_lima_instance(app, instance_name).await
+}
+
+#[tauri::command]
+pub async fn clone_lima_instance_cmd(
+    app: AppHandle,
+    source_name: String,
+    target_name: String,
+) -> Result<String, AppError> {
+    lima_instance_service::clone_lima_instance(app, source_name, target_name).await
+}
+
+#[tauri::command]
+pub async fn rename_lima_instance_cmd(
+    app: AppHandle,
+    old_name: String,
+    new_name: String,
+) -> Result<(), AppError> {
+    lima_instance_service::rename_lima_instance(app, old_name, new_name).await
 }
@@ -1,11 +1,31 @@
-use crate::find_lima_executable;
+use crate::error::AppError;
+use crate::instance_registry_service;
+use crate::lima_backend::{lima_backend, process_exists, LimaBackend, LimaExitStatus, LimaProcess};
+use crate::lima_compat_service;
 use crate::lima_config::LimaConfig;
-use std::process::Stdio;
+use crate::lima_log::{BootPhase, BootProgressTracker, PhaseStatus};
+use crate::operation_log_service::{self, LogStream, OperationLogEntry};
+use crate::operation_service::{register_operation, OperationHandle, OperationInfo, OperationKind};
+use crate::preflight_service;
+use crate::readiness_service;
+use crate::settings_service;
+use crate::snapshot_service;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tauri::{AppHandle, Emitter, Manager};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
 use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::Command as TokioCommand;
+
+/// Pid file of the Lima host agent, which owns the hypervisor process
+const HOST_AGENT_PID_FILE: &str = "ha.pid";
+
+/// How long the host agent gets to exit after a successful stop/delete
+const HOST_AGENT_EXIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// First Lima release with `limactl clone`; older ones get the instance dir copied
+const LIMACTL_CLONE_VERSION: semver::Version = semver::Version::new(1, 1, 0);
 
 #[derive(Clone, serde::Serialize)]
 struct LimaLogPayload {
@@ -13,9 +33,14 @@ struct LimaLogPayload {
     message: String,
     message_id: String,
     timestamp: String,
+    /// Id of the operation that emitted the event, for `cancel_operation_cmd`
+    operation_id: String,
+    /// The emitting operation, for its persisted log
+    #[serde(skip)]
+    operation: OperationInfo,
 }
 
-fn create_log_payload(instance_name: String, message: String) -> LimaLogPayload {
+fn create_log_payload(operation: &OperationInfo, message: String) -> LimaLogPayload {
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .map(|d| d.as_nanos())
@@ -25,583 +50,2553 @@ fn create_log_payload(instance_name: String, message: String) -> LimaLogPayload
     let message_id = uuid::Uuid::new_v4().to_string();
 
     LimaLogPayload {
-        instance_name,
+        instance_name: operation.instance_name.clone(),
         message,
         message_id,
         timestamp,
+        operation_id: operation.operation_id.clone(),
+        operation: operation.clone(),
     }
 }
 
-pub async fn start_lima_instance(app: AppHandle, instance_name: String) -> Result<String, String> {
-    // Emit start event
-    app.emit(
-        "lima-instance-start",
-        create_log_payload(
-            instance_name.clone(),
-            format!("Starting Lima instance '{}'...", instance_name),
-        ),
-    )
-    .map_err(|e| format!("Failed to emit start event: {}", e))?;
+/// Event payloads built around a `LimaLogPayload`
+trait LogEvent: serde::Serialize + Clone {
+    fn log(&self) -> &LimaLogPayload;
+}
 
-    // Spawn async task to run limactl and stream output
-    let app_handle = app.clone();
-    let instance_name_clone = instance_name.clone();
+impl LogEvent for LimaLogPayload {
+    fn log(&self) -> &LimaLogPayload {
+        self
+    }
+}
 
-    tokio::spawn(async move {
-        let lima_cmd = match find_lima_executable() {
-            Some(cmd) => cmd,
-            None => {
-                let _ = app_handle.emit(
-                    "lima-instance-start-error",
-                    create_log_payload(
-                        instance_name_clone.clone(),
-                        "Lima (limactl) not found. Please ensure lima is installed in /usr/local/bin, /opt/homebrew/bin, or is in your PATH.".to_string(),
-                    ),
-                );
-                return;
-            }
+/// `Emitter::emit` that also appends the event to the operation's persisted log
+trait EmitLogged {
+    fn emit_logged<P: LogEvent>(&self, event: &str, payload: P) -> tauri::Result<()>;
+}
+
+impl<R: Runtime> EmitLogged for AppHandle<R> {
+    fn emit_logged<P: LogEvent>(&self, event: &str, payload: P) -> tauri::Result<()> {
+        let log = payload.log();
+        operation_log_service::append_operation_log(
+            self,
+            &log.operation,
+            &OperationLogEntry {
+                message_id: log.message_id.clone(),
+                timestamp: log.timestamp.clone(),
+                stream: LogStream::of_event(event),
+                event: event.to_string(),
+                message: log.message.clone(),
+            },
+        );
+        self.emit(event, payload)
+    }
+}
+
+/// Payload of `<event>-progress`: a boot phase transition parsed from limactl output
+#[derive(Clone, serde::Serialize)]
+struct BootProgressPayload {
+    #[serde(flatten)]
+    log: LimaLogPayload,
+    #[serde(flatten)]
+    phase: BootPhase,
+    status: PhaseStatus,
+    /// logrus level of the source line, `None` for plain output
+    level: Option<String>,
+}
+
+impl LogEvent for BootProgressPayload {
+    fn log(&self) -> &LimaLogPayload {
+        &self.log
+    }
+}
+
+/// Payload of a start's or restart's `-success`. `ready` is false if the instance
+/// started but its readiness conditions were not met; the message then names them.
+#[derive(Clone, serde::Serialize)]
+struct StartedPayload {
+    #[serde(flatten)]
+    log: LimaLogPayload,
+    ready: bool,
+}
+
+impl LogEvent for StartedPayload {
+    fn log(&self) -> &LimaLogPayload {
+        &self.log
+    }
+}
+
+/// Payload of `<event>-escalated`: the graceful operation timed out and `--force` takes over
+#[derive(Clone, serde::Serialize)]
+struct EscalationPayload {
+    #[serde(flatten)]
+    log: LimaLogPayload,
+    timeout_secs: u64,
+}
+
+impl LogEvent for EscalationPayload {
+    fn log(&self) -> &LimaLogPayload {
+        &self.log
+    }
+}
+
+/// Payload of `-success` for stop and delete
+#[derive(Clone, serde::Serialize)]
+struct ShutdownPayload {
+    #[serde(flatten)]
+    log: LimaLogPayload,
+    /// Whether `--force` was used, directly or after the graceful timeout
+    forced: bool,
+    /// Whether the host agent recorded in `ha.pid` is gone, `None` if there was no pid file
+    host_agent_exited: Option<bool>,
+    /// Warning: the pid from a stale `ha.pid` is still alive, but the process is not a
+    /// Lima host agent (the pid was reused), so it did not fail the operation
+    host_agent_alive: bool,
+}
+
+impl LogEvent for ShutdownPayload {
+    fn log(&self) -> &LimaLogPayload {
+        &self.log
+    }
+}
+
+/// Emit `<operation event><suffix>`, e.g. `lima-instance-start-error`
+fn emit_operation_event<R: Runtime>(
+    app: &AppHandle<R>,
+    operation: &OperationInfo,
+    suffix: &str,
+    message: String,
+) {
+    let _ = app.emit_logged(
+        &format!("{}{}", operation.kind.event(), suffix),
+        create_log_payload(operation, message),
+    );
+}
+
+/// Callback invoked for every stdout/stderr line of a lifecycle operation
+type LineHook = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// Line hook that tracks boot phases and emits each transition as `<event>-progress`
+fn boot_progress_hook<R: Runtime>(app: &AppHandle<R>, operation: &OperationInfo) -> LineHook {
+    let app = app.clone();
+    let operation = operation.clone();
+    let tracker = std::sync::Mutex::new(BootProgressTracker::new());
+    Arc::new(move |line: &str| {
+        let Ok(mut tracker) = tracker.lock() else {
+            return;
         };
+        for progress in tracker.feed(line) {
+            let _ = app.emit_logged(
+                &format!("{}-progress", operation.kind.event()),
+                BootProgressPayload {
+                    log: create_log_payload(&operation, progress.message),
+                    phase: progress.phase,
+                    status: progress.status,
+                    level: progress.level,
+                },
+            );
+        }
+    })
+}
 
-        // Run limactl start command
-        let child = TokioCommand::new(&lima_cmd)
-            .args(["start", "--tty=false", &instance_name_clone])
-            .stdin(Stdio::null())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-            .map_err(|e| format!("Failed to start limactl start process: {}", e));
+/// How a streamed lifecycle process ended
+enum ProcessOutcome {
+    /// The process exited; carries the collected stderr lines for error reporting
+    Exited(LimaExitStatus, Vec<String>),
+    /// The operation was cancelled and its process killed
+    Cancelled,
+    /// The process outlived its deadline and was killed
+    TimedOut,
+}
 
-        let mut child = match child {
-            Ok(c) => c,
-            Err(e) => {
-                let _ = app_handle.emit(
-                    "lima-instance-start-error",
-                    create_log_payload(instance_name_clone.clone(), e.to_string()),
-                );
-                return;
+/// Stream stdout/stderr of a lifecycle operation as `<event>-stdout` / `<event>-stderr`
+/// events and wait for it to exit, or kill it if the operation is cancelled first.
+async fn stream_lima_process<R: Runtime>(
+    app: &AppHandle<R>,
+    operation: &mut OperationHandle,
+    child: Box<dyn LimaProcess>,
+    on_line: Option<LineHook>,
+) -> Result<ProcessOutcome, AppError> {
+    stream_lima_process_until(app, operation, child, on_line, None).await
+}
+
+/// Like `stream_lima_process`, but kills the process once `deadline` has passed
+async fn stream_lima_process_until<R: Runtime>(
+    app: &AppHandle<R>,
+    operation: &mut OperationHandle,
+    mut child: Box<dyn LimaProcess>,
+    on_line: Option<LineHook>,
+    deadline: Option<Duration>,
+) -> Result<ProcessOutcome, AppError> {
+    // Stream stdout
+    let stdout_task = child.take_stdout().map(|stdout| {
+        let app_handle = app.clone();
+        let info = operation.info.clone();
+        let on_line = on_line.clone();
+        tokio::spawn(async move {
+            let mut reader = BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = reader.next_line().await {
+                emit_operation_event(&app_handle, &info, "-stdout", line.clone());
+                if let Some(hook) = &on_line {
+                    hook(&line);
+                }
             }
-        };
+        })
+    });
 
-        // Track whether the ready event has been emitted (shared across stdout/stderr tasks)
-        let ready_emitted = Arc::new(AtomicBool::new(false));
-
-        // Stream stdout
-        if let Some(stdout) = child.stdout.take() {
-            let app_handle_stdout = app_handle.clone();
-            let instance_name_stdout = instance_name_clone.clone();
-            let ready_emitted_stdout = ready_emitted.clone();
-            tokio::spawn(async move {
-                let mut reader = BufReader::new(stdout).lines();
-
-                while let Ok(Some(line)) = reader.next_line().await {
-                    let _ = app_handle_stdout.emit(
-                        "lima-instance-start-stdout",
-                        create_log_payload(instance_name_stdout.clone(), line.clone()),
-                    );
-
-                    if !ready_emitted_stdout.load(Ordering::Relaxed)
-                        && (line.contains("Waiting for the optional requirement")
-                            || (line.contains("optional requirement") && line.contains("msg"))
-                            || line.contains("The optional requirement")
-                                && line.contains("is satisfied"))
-                    {
-                        ready_emitted_stdout.store(true, Ordering::Relaxed);
-                        let _ = app_handle_stdout.emit(
-                            "lima-instance-start-ready",
-                            create_log_payload(
-                                instance_name_stdout.clone(),
-                                format!("Instance '{}' is ready for use (waiting for optional hooks to complete)", instance_name_stdout),
-                            ),
-                        );
-                    }
+    // Stream stderr and collect lines for error reporting
+    let stderr_lines = Arc::new(tokio::sync::Mutex::new(Vec::<String>::new()));
+    let stderr_task = child.take_stderr().map(|stderr| {
+        let app_handle = app.clone();
+        let info = operation.info.clone();
+        let stderr_lines = stderr_lines.clone();
+        let on_line = on_line.clone();
+        tokio::spawn(async move {
+            let mut reader = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = reader.next_line().await {
+                stderr_lines.lock().await.push(line.clone());
+                emit_operation_event(&app_handle, &info, "-stderr", line.clone());
+                if let Some(hook) = &on_line {
+                    hook(&line);
                 }
-            });
+            }
+        })
+    });
+
+    // Wait for process to complete, unless cancelled or timed out first
+    let expired = async {
+        match deadline {
+            Some(deadline) => tokio::time::sleep(deadline).await,
+            None => std::future::pending().await,
+        }
+    };
+    let wait_result = tokio::select! {
+        result = child.wait() => Ok(result),
+        _ = operation.cancelled() => Err(ProcessOutcome::Cancelled),
+        _ = expired => Err(ProcessOutcome::TimedOut),
+    };
+    let wait_result = match wait_result {
+        Ok(result) => result,
+        Err(interrupted) => {
+            // Stop streaming right away; processes outside the group may hold the pipes open
+            for task in [stdout_task, stderr_task].into_iter().flatten() {
+                task.abort();
+            }
+            child.kill().await?;
+            return Ok(interrupted);
         }
+    };
 
-        // Stream stderr and collect lines for error reporting
-        let stderr_lines = std::sync::Arc::new(tokio::sync::Mutex::new(Vec::<String>::new()));
-        let stderr_task = if let Some(stderr) = child.stderr.take() {
-            let app_handle_stderr = app_handle.clone();
-            let instance_name_stderr = instance_name_clone.clone();
-            let stderr_lines_clone = stderr_lines.clone();
-            let ready_emitted_stderr = ready_emitted.clone();
-            Some(tokio::spawn(async move {
-                let mut reader = BufReader::new(stderr).lines();
-
-                while let Ok(Some(line)) = reader.next_line().await {
-                    stderr_lines_clone.lock().await.push(line.clone());
-                    let _ = app_handle_stderr.emit(
-                        "lima-instance-start-stderr",
-                        create_log_payload(instance_name_stderr.clone(), line.clone()),
-                    );
-
-                    if !ready_emitted_stderr.load(Ordering::Relaxed)
-                        && (line.contains("Waiting for the optional requirement")
-                            || (line.contains("optional requirement") && line.contains("msg"))
-                            || line.contains("The optional requirement")
-                                && line.contains("is satisfied"))
-                    {
-                        ready_emitted_stderr.store(true, Ordering::Relaxed);
-                        let _ = app_handle_stderr.emit(
-                            "lima-instance-start-ready",
-                            create_log_payload(
-                                instance_name_stderr.clone(),
-                                format!("Instance '{}' is ready for use (waiting for optional hooks to complete)", instance_name_stderr),
-                            ),
-                        );
-                    }
-                }
-            }))
-        } else {
-            None
-        };
+    // Ensure output is fully read before checking collected lines
+    if let Some(task) = stdout_task {
+        let _ = task.await;
+    }
+    if let Some(task) = stderr_task {
+        let _ = task.await;
+    }
+
+    let status = wait_result?;
+    let collected = std::mem::take(&mut *stderr_lines.lock().await);
+    Ok(ProcessOutcome::Exited(status, collected))
+}
+
+/// Build the error detail for a failed operation: the collected stderr, or the exit code
+fn failure_detail(status: LimaExitStatus, stderr_lines: &[String]) -> String {
+    if stderr_lines.is_empty() {
+        format!("Exit code: {}", status.code.unwrap_or(-1))
+    } else {
+        stderr_lines.join("\n")
+    }
+}
+
+/// Reject an operation early when the installed Lima cannot run `config`,
+/// reporting it as `<event>-error` like any other lifecycle failure.
+/// Failing to determine the version is not fatal; limactl reports its own errors.
+async fn check_lima_compatibility<R: Runtime>(
+    app: &AppHandle<R>,
+    backend: &dyn LimaBackend,
+    operation: &OperationInfo,
+    config: &LimaConfig,
+) -> Result<(), AppError> {
+    match lima_compat_service::ensure_compatible(backend, config).await {
+        Err(e @ AppError::LimaIncompatible { .. }) => {
+            emit_operation_event(app, operation, "-error", e.to_string());
+            Err(e)
+        }
+        Err(e) => {
+            log::warn!("Skipping Lima compatibility check: {}", e);
+            Ok(())
+        }
+        Ok(()) => Ok(()),
+    }
+}
+
+/// Reject an operation early when the host cannot give the instance what `config`
+/// asks for, reported as `<event>-error`. A preflight that fails to run is not fatal.
+async fn check_host_resources<R: Runtime>(
+    app: &AppHandle<R>,
+    operation: &OperationInfo,
+    config: &LimaConfig,
+    creating: bool,
+) -> Result<(), AppError> {
+    match preflight_service::ensure_resources(app, &operation.instance_name, config, creating).await
+    {
+        Err(e @ AppError::InsufficientResources { .. }) => {
+            emit_operation_event(app, operation, "-error", e.to_string());
+            Err(e)
+        }
+        Err(e) => {
+            log::warn!("Skipping host resource preflight: {}", e);
+            Ok(())
+        }
+        Ok(()) => Ok(()),
+    }
+}
+
+/// The instance's lima.yaml, if it exists and parses
+pub fn read_instance_config<R: Runtime>(
+    app: &AppHandle<R>,
+    instance_name: &str,
+) -> Option<LimaConfig> {
+    let path = crate::lima_config_service::get_lima_yaml_path(app, instance_name).ok()?;
+    let yaml = std::fs::read_to_string(path).ok()?;
+    LimaConfig::from_yaml(&yaml).ok()
+}
 
-        // Wait for process to complete
-        let wait_result = child.wait().await;
-        if let Some(task) = stderr_task {
-            let _ = task.await;
-        }
-        match wait_result {
-            Ok(status) => {
-                if status.success() {
-                    // Emit ready event if it was never emitted during startup.
-                    // This happens when the config has no probes (e.g. Docker-only
-                    // template), so Lima never outputs "optional requirement" messages.
-                    if !ready_emitted.load(Ordering::Relaxed) {
-                        let _ = app_handle.emit(
-                            "lima-instance-start-ready",
-                            create_log_payload(
-                                instance_name_clone.clone(),
-                                format!("Instance '{}' is ready for use", instance_name_clone),
-                            ),
+/// Report a finished lifecycle process: `-success` on exit code 0, `-error` with
+/// stderr otherwise, and `-cancelled` if it was cancelled
+fn emit_outcome<R: Runtime>(
+    app: &AppHandle<R>,
+    operation: &OperationInfo,
+    outcome: Result<ProcessOutcome, AppError>,
+    success_message: &str,
+) {
+    match outcome {
+        Ok(ProcessOutcome::Exited(status, _)) if status.success() => {
+            emit_operation_event(app, operation, "-success", success_message.to_string());
+        }
+        Ok(ProcessOutcome::Exited(status, stderr_lines)) => {
+            emit_operation_event(
+                app,
+                operation,
+                "-error",
+                failure_detail(status, &stderr_lines),
+            );
+        }
+        Ok(ProcessOutcome::Cancelled) => {
+            emit_operation_event(app, operation, "-cancelled", "Cancelled".to_string());
+        }
+        Ok(ProcessOutcome::TimedOut) => {
+            let error_msg = format!("limactl {} did not finish in time", operation.kind.name());
+            emit_operation_event(app, operation, "-error", error_msg);
+        }
+        Err(e) => {
+            let error_msg = format!(
+                "Failed to wait for limactl {} process: {}",
+                operation.kind.name(),
+                e
+            );
+            emit_operation_event(app, operation, "-error", error_msg);
+        }
+    }
+}
+
+/// Stream a spawned `limactl start` for `operation`. Once it succeeds, waits up to
+/// `readiness_timeout` for the readiness conditions and emits `-ready` when they are met,
+/// or `-not-ready` naming the ones that are not, whose message is also returned.
+/// Stops the instance again if the operation is cancelled.
+async fn run_start<R: Runtime>(
+    app: &AppHandle<R>,
+    backend: &dyn LimaBackend,
+    operation: &mut OperationHandle,
+    child: Box<dyn LimaProcess>,
+    readiness_timeout: Duration,
+) -> Result<(ProcessOutcome, Option<String>), AppError> {
+    let info = operation.info.clone();
+    let progress = boot_progress_hook(app, &info);
+
+    let outcome = match stream_lima_process(app, operation, child, Some(progress)).await {
+        Ok(ProcessOutcome::Exited(status, stderr)) if status.success() => {
+            tokio::select! {
+                report = readiness_service::wait_until_ready(
+                    app,
+                    &info.instance_name,
+                    readiness_timeout,
+                ) => {
+                    if report.ready {
+                        emit_operation_event(
+                            app,
+                            &info,
+                            "-ready",
+                            format!("Instance '{}' is ready for use", info.instance_name),
                         );
-                    }
-                    let _ = app_handle.emit(
-                        "lima-instance-start-success",
-                        create_log_payload(instance_name_clone, "Started".to_string()),
-                    );
-                } else {
-                    let collected = stderr_lines.lock().await;
-                    let detail = if collected.is_empty() {
-                        format!("Exit code: {}", status.code().unwrap_or(-1))
+                        Ok((ProcessOutcome::Exited(status, stderr), None))
                     } else {
-                        collected.join("\n")
-                    };
-                    let _ = app_handle.emit(
-                        "lima-instance-start-error",
-                        create_log_payload(instance_name_clone, detail),
-                    );
+                        let failed: Vec<String> = report
+                            .conditions
+                            .iter()
+                            .filter(|result| !result.ready)
+                            .map(|result| match &result.message {
+                                Some(message) => format!("{} ({})", result.condition, message),
+                                None => result.condition.to_string(),
+                            })
+                            .collect();
+                        let message = format!(
+                            "Instance '{}' started but is not ready: {}",
+                            info.instance_name,
+                            failed.join(", ")
+                        );
+                        emit_operation_event(app, &info, "-not-ready", message.clone());
+                        Ok((ProcessOutcome::Exited(status, stderr), Some(message)))
+                    }
                 }
+                _ = operation.cancelled() => Ok((ProcessOutcome::Cancelled, None)),
             }
-            Err(e) => {
-                let error_msg = format!("Failed to wait for limactl start process: {}", e);
-                let _ = app_handle.emit(
-                    "lima-instance-start-error",
-                    create_log_payload(instance_name_clone, error_msg),
+        }
+        outcome => outcome.map(|outcome| (outcome, None)),
+    };
+    if let Ok((ProcessOutcome::Cancelled, _)) = outcome {
+        // The host agent runs detached from limactl's process group and
+        // would keep booting the VM, so stop whatever got started
+        if let Ok(mut stop) = backend.stop(&info.instance_name, true) {
+            let _ = stop.wait().await;
+        }
+    }
+    outcome
+}
+
+/// Report a successful start or restart with `-success`, carrying whether the instance
+/// is ready. A `not_ready` message replaces `success_message`.
+fn emit_started<R: Runtime>(
+    app: &AppHandle<R>,
+    operation: &OperationInfo,
+    success_message: &str,
+    not_ready: Option<String>,
+) {
+    let ready = not_ready.is_none();
+    let message = not_ready.unwrap_or_else(|| success_message.to_string());
+    let _ = app.emit_logged(
+        &format!("{}-success", operation.kind.event()),
+        StartedPayload {
+            log: create_log_payload(operation, message),
+            ready,
+        },
+    );
+}
+
+/// Pid recorded by the instance's host agent, if it is (or was) running
+fn read_host_agent_pid<R: Runtime>(app: &AppHandle<R>, instance_name: &str) -> Option<i32> {
+    let instance_dir = crate::yaml_handler::get_instance_dir(app, instance_name).ok()?;
+    let content = std::fs::read_to_string(instance_dir.join(HOST_AGENT_PID_FILE)).ok()?;
+    content.trim().parse().ok()
+}
+
+/// Whether `pid` is a Lima host agent (`limactl hostagent ...`), by its process name
+/// or command line
+fn is_host_agent_process(pid: i32) -> bool {
+    let Ok(pid) = u32::try_from(pid).map(sysinfo::Pid::from_u32) else {
+        return false;
+    };
+    let mut system = sysinfo::System::new();
+    system.refresh_processes_specifics(
+        sysinfo::ProcessesToUpdate::Some(&[pid]),
+        true,
+        sysinfo::ProcessRefreshKind::nothing().with_cmd(sysinfo::UpdateKind::Always),
+    );
+    system.process(pid).is_some_and(|process| {
+        process.name().to_string_lossy().starts_with("limactl")
+            || process.cmd().iter().any(|arg| arg == "hostagent")
+    })
+}
+
+/// Wait up to `HOST_AGENT_EXIT_TIMEOUT` for the host agent to exit. Returns whether
+/// the pid is gone; stops waiting early once the pid no longer is a host agent.
+async fn wait_for_process_exit(pid: i32) -> bool {
+    let started = std::time::Instant::now();
+    while process_exists(pid) {
+        if !is_host_agent_process(pid) || started.elapsed() >= HOST_AGENT_EXIT_TIMEOUT {
+            return false;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    true
+}
+
+/// Run a stop or delete, spawned by `spawn(force)`. Unless `force` is set it first runs
+/// gracefully, and escalates to `--force` with an `<event>-escalated` event once `timeout`
+/// passes. Returns the outcome and whether `--force` was used.
+async fn run_with_escalation<R: Runtime>(
+    app: &AppHandle<R>,
+    operation: &mut OperationHandle,
+    spawn: impl Fn(bool) -> Result<Box<dyn LimaProcess>, AppError>,
+    force: bool,
+    timeout: Duration,
+) -> Result<(ProcessOutcome, bool), AppError> {
+    if !force {
+        let child = spawn(false)?;
+        match stream_lima_process_until(app, operation, child, None, Some(timeout)).await? {
+            ProcessOutcome::TimedOut => {
+                let info = &operation.info;
+                let message = format!(
+                    "limactl {} did not finish within {}s, retrying with --force",
+                    info.kind.name(),
+                    timeout.as_secs()
+                );
+                let _ = app.emit_logged(
+                    &format!("{}-escalated", info.kind.event()),
+                    EscalationPayload {
+                        log: create_log_payload(info, message),
+                        timeout_secs: timeout.as_secs(),
+                    },
                 );
             }
+            outcome => return Ok((outcome, false)),
         }
-    });
+    }
 
-    Ok(instance_name)
+    let child = spawn(true)?;
+    let outcome = stream_lima_process(app, operation, child, None).await?;
+    Ok((outcome, true))
 }
 
-pub async fn stop_lima_instance(app: AppHandle, instance_name: String) -> Result<String, String> {
-    // Emit stop event
-    app.emit(
-        "lima-instance-stop",
+/// Report a finished stop or delete. A successful limactl run only counts as success
+/// once the host agent recorded before the operation (if any) has exited as well.
+async fn emit_shutdown_outcome<R: Runtime>(
+    app: &AppHandle<R>,
+    operation: &OperationInfo,
+    result: Result<(ProcessOutcome, bool), AppError>,
+    host_agent_pid: Option<i32>,
+    success_message: &str,
+) {
+    let forced = match result {
+        Ok((ProcessOutcome::Exited(status, _), forced)) if status.success() => forced,
+        other => {
+            emit_outcome(
+                app,
+                operation,
+                other.map(|(outcome, _)| outcome),
+                success_message,
+            );
+            return;
+        }
+    };
+
+    let host_agent_exited = match host_agent_pid {
+        Some(pid) => Some(wait_for_process_exit(pid).await),
+        None => None,
+    };
+    let host_agent_alive = host_agent_exited == Some(false);
+    if let Some(pid) = host_agent_pid.filter(|_| host_agent_alive) {
+        if is_host_agent_process(pid) {
+            let error_msg = format!(
+                "limactl {} finished, but the host agent (pid {}) is still running",
+                operation.kind.name(),
+                pid
+            );
+            emit_operation_event(app, operation, "-error", error_msg);
+            return;
+        }
+        log::warn!(
+            "Pid {} from the ha.pid of '{}' belongs to another process, ignoring it",
+            pid,
+            operation.instance_name
+        );
+    }
+
+    let message = if forced {
+        format!("{} (forced)", success_message)
+    } else {
+        success_message.to_string()
+    };
+    let _ = app.emit_logged(
+        &format!("{}-success", operation.kind.event()),
+        ShutdownPayload {
+            log: create_log_payload(operation, message),
+            forced,
+            host_agent_exited,
+            host_agent_alive,
+        },
+    );
+}
+
+/// Start an instance. Returns the operation id.
+pub async fn start_lima_instance<R: Runtime>(
+    app: AppHandle<R>,
+    instance_name: String,
+) -> Result<String, AppError> {
+    start_instance_within(
+        app,
+        instance_name,
+        readiness_service::START_READINESS_TIMEOUT,
+    )
+    .await
+}
+
+async fn start_instance_within<R: Runtime>(
+    app: AppHandle<R>,
+    instance_name: String,
+    readiness_timeout: Duration,
+) -> Result<String, AppError> {
+    let mut operation = register_operation(&app, &instance_name, OperationKind::Start)?;
+    let info = operation.info.clone();
+
+    // Emit start event
+    app.emit_logged(
+        "lima-instance-start",
         create_log_payload(
-            instance_name.clone(),
-            format!("Stopping Lima instance '{}'...", instance_name),
+            &info,
+            format!("Starting Lima instance '{}'...", instance_name),
         ),
     )
-    .map_err(|e| format!("Failed to emit stop event: {}", e))?;
+    .map_err(|e| AppError::io("Failed to emit start event", e))?;
+
+    let backend = lima_backend(&app);
+    if let Some(config) = read_instance_config(&app, &instance_name) {
+        check_lima_compatibility(&app, backend.as_ref(), &info, &config).await?;
+        check_host_resources(&app, &info, &config, false).await?;
+    }
 
     // Spawn async task to run limactl and stream output
     let app_handle = app.clone();
-    let instance_name_clone = instance_name.clone();
+    let operation_id = info.operation_id.clone();
 
     tokio::spawn(async move {
-        let lima_cmd = match find_lima_executable() {
-            Some(cmd) => cmd,
-            None => {
-                let _ = app_handle.emit(
-                    "lima-instance-stop-error",
-                    create_log_payload(
-                        instance_name_clone.clone(),
-                        "Lima (limactl) not found. Please ensure lima is installed in /usr/local/bin, /opt/homebrew/bin, or is in your PATH.".to_string(),
-                    ),
-                );
-                return;
-            }
-        };
-
-        // Run limactl stop command
-        let child = TokioCommand::new(&lima_cmd)
-            .args(["stop", "--tty=false", &instance_name_clone])
-            .stdin(Stdio::null())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-            .map_err(|e| format!("Failed to start limactl stop process: {}", e));
-
-        let mut child = match child {
+        let child = match backend.start(&info.instance_name) {
             Ok(c) => c,
             Err(e) => {
-                let _ = app_handle.emit(
-                    "lima-instance-stop-error",
-                    create_log_payload(instance_name_clone.clone(), e.to_string()),
-                );
+                emit_operation_event(&app_handle, &info, "-error", e.to_string());
                 return;
             }
         };
 
-        // Stream stdout
-        if let Some(stdout) = child.stdout.take() {
-            let app_handle_stdout = app_handle.clone();
-            let instance_name_stdout = instance_name_clone.clone();
-            tokio::spawn(async move {
-                let mut reader = BufReader::new(stdout).lines();
-                while let Ok(Some(line)) = reader.next_line().await {
-                    let _ = app_handle_stdout.emit(
-                        "lima-instance-stop-stdout",
-                        create_log_payload(instance_name_stdout.clone(), line),
-                    );
-                }
-            });
-        }
-
-        // Stream stderr and collect lines for error reporting
-        let stderr_lines = std::sync::Arc::new(tokio::sync::Mutex::new(Vec::<String>::new()));
-        let stderr_task = if let Some(stderr) = child.stderr.take() {
-            let app_handle_stderr = app_handle.clone();
-            let instance_name_stderr = instance_name_clone.clone();
-            let stderr_lines_clone = stderr_lines.clone();
-            Some(tokio::spawn(async move {
-                let mut reader = BufReader::new(stderr).lines();
-                while let Ok(Some(line)) = reader.next_line().await {
-                    stderr_lines_clone.lock().await.push(line.clone());
-                    let _ = app_handle_stderr.emit(
-                        "lima-instance-stop-stderr",
-                        create_log_payload(instance_name_stderr.clone(), line),
-                    );
-                }
-            }))
-        } else {
-            None
-        };
-
-        // Wait for process to complete
-        let wait_result = child.wait().await;
-        if let Some(task) = stderr_task {
-            let _ = task.await;
-        }
-        match wait_result {
-            Ok(status) => {
-                if status.success() {
-                    let _ = app_handle.emit(
-                        "lima-instance-stop-success",
-                        create_log_payload(instance_name_clone, "Stopped".to_string()),
-                    );
-                } else {
-                    let collected = stderr_lines.lock().await;
-                    let detail = if collected.is_empty() {
-                        format!("Exit code: {}", status.code().unwrap_or(-1))
-                    } else {
-                        collected.join("\n")
-                    };
-                    let _ = app_handle.emit(
-                        "lima-instance-stop-error",
-                        create_log_payload(instance_name_clone, detail),
-                    );
-                }
-            }
-            Err(e) => {
-                let error_msg = format!("Failed to wait for limactl stop process: {}", e);
-                let _ = app_handle.emit(
-                    "lima-instance-stop-error",
-                    create_log_payload(instance_name_clone, error_msg),
-                );
+        match run_start(
+            &app_handle,
+            backend.as_ref(),
+            &mut operation,
+            child,
+            readiness_timeout,
+        )
+        .await
+        {
+            Ok((ProcessOutcome::Exited(status, _), not_ready)) if status.success() => {
+                emit_started(&app_handle, &info, "Started", not_ready);
             }
+            outcome => emit_outcome(
+                &app_handle,
+                &info,
+                outcome.map(|(outcome, _)| outcome),
+                "Started",
+            ),
         }
     });
 
-    Ok(instance_name)
+    Ok(operation_id)
 }
 
-pub async fn delete_lima_instance(app: AppHandle, instance_name: String) -> Result<String, String> {
-    // Emit delete event
-    app.emit(
-        "lima-instance-delete",
+/// Step of a multi-step operation (restart, recreate), reported with
+/// `lima-instance-<op>-phase` and `-error`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum OperationPhase {
+    Stop,
+    Config,
+    Start,
+    Delete,
+    Create,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct PhasePayload {
+    #[serde(flatten)]
+    log: LimaLogPayload,
+    phase: OperationPhase,
+}
+
+impl LogEvent for PhasePayload {
+    fn log(&self) -> &LimaLogPayload {
+        &self.log
+    }
+}
+
+fn emit_phase_event<R: Runtime>(
+    app: &AppHandle<R>,
+    operation: &OperationInfo,
+    suffix: &str,
+    phase: OperationPhase,
+    message: String,
+) {
+    let _ = app.emit_logged(
+        &format!("{}{}", operation.kind.event(), suffix),
+        PhasePayload {
+            log: create_log_payload(operation, message),
+            phase,
+        },
+    );
+}
+
+/// Report the end of a phase. Returns whether the operation should go on.
+fn phase_succeeded<R: Runtime>(
+    app: &AppHandle<R>,
+    operation: &OperationInfo,
+    phase: OperationPhase,
+    outcome: Result<ProcessOutcome, AppError>,
+) -> bool {
+    let detail = match outcome {
+        Ok(ProcessOutcome::Exited(status, _)) if status.success() => return true,
+        Ok(ProcessOutcome::Exited(status, stderr_lines)) => failure_detail(status, &stderr_lines),
+        Ok(ProcessOutcome::Cancelled) => {
+            emit_phase_event(app, operation, "-cancelled", phase, "Cancelled".to_string());
+            return false;
+        }
+        Ok(ProcessOutcome::TimedOut) => "Timed out".to_string(),
+        Err(e) => e.to_string(),
+    };
+    emit_phase_event(app, operation, "-error", phase, detail);
+    false
+}
+
+/// Restart an instance as one operation: stop it if it is running, optionally
+/// replace its lima.yaml with `config`, then start it again.
+/// Events use the `lima-instance-restart` family; `-phase` marks each step and
+/// `-error` / `-cancelled` carry the phase they happened in. Returns the operation id.
+pub async fn restart_lima_instance<R: Runtime>(
+    app: AppHandle<R>,
+    instance_name: String,
+    config: Option<LimaConfig>,
+) -> Result<String, AppError> {
+    let mut operation = register_operation(&app, &instance_name, OperationKind::Restart)?;
+    let info = operation.info.clone();
+
+    // Emit restart event
+    app.emit_logged(
+        "lima-instance-restart",
         create_log_payload(
-            instance_name.clone(),
-            format!("Deleting Lima instance '{}'...", instance_name),
+            &info,
+            format!("Restarting Lima instance '{}'...", instance_name),
         ),
     )
-    .map_err(|e| format!("Failed to emit delete event: {}", e))?;
+    .map_err(|e| AppError::io("Failed to emit restart event", e))?;
+
+    // Reject an incompatible or oversized config before stopping anything
+    let backend = lima_backend(&app);
+    if let Some(config) = config
+        .clone()
+        .or_else(|| read_instance_config(&app, &instance_name))
+    {
+        check_lima_compatibility(&app, backend.as_ref(), &info, &config).await?;
+        check_host_resources(&app, &info, &config, false).await?;
+    }
+
+    // A stopped instance skips straight to the config and start phases
+    let is_running = instance_registry_service::get_all_lima_instances(backend.as_ref())
+        .await
+        .map(|instances| {
+            instances
+                .iter()
+                .any(|i| i.name == instance_name && i.status == "Running")
+        })
+        .unwrap_or(true);
 
     // Spawn async task to run limactl and stream output
     let app_handle = app.clone();
-    let instance_name_clone = instance_name.clone();
+    let operation_id = info.operation_id.clone();
+    let stop_timeout =
+        Duration::from_secs(settings_service::load_lifecycle_settings(&app).stop_timeout_secs);
 
     tokio::spawn(async move {
-        let lima_cmd = match find_lima_executable() {
-            Some(cmd) => cmd,
-            None => {
-                let _ = app_handle.emit(
-                    "lima-instance-delete-error",
-                    create_log_payload(
-                        instance_name_clone.clone(),
-                        "Lima (limactl) not found. Please ensure lima is installed in /usr/local/bin, /opt/homebrew/bin, or is in your PATH.".to_string(),
-                    ),
-                );
+        if is_running {
+            emit_phase_event(
+                &app_handle,
+                &info,
+                "-phase",
+                OperationPhase::Stop,
+                format!("Stopping Lima instance '{}'...", info.instance_name),
+            );
+            let outcome = run_with_escalation(
+                &app_handle,
+                &mut operation,
+                |force| backend.stop(&info.instance_name, force),
+                false,
+                stop_timeout,
+            )
+            .await
+            .map(|(outcome, _)| outcome);
+            if !phase_succeeded(&app_handle, &info, OperationPhase::Stop, outcome) {
                 return;
             }
-        };
-
-        // Run limactl delete command
-        let child = TokioCommand::new(&lima_cmd)
-            .args(["delete", &instance_name_clone])
-            .stdin(Stdio::null())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-            .map_err(|e| format!("Failed to start limactl delete process: {}", e));
+        }
 
-        let mut child = match child {
-            Ok(c) => c,
-            Err(e) => {
-                let _ = app_handle.emit(
-                    "lima-instance-delete-error",
-                    create_log_payload(instance_name_clone.clone(), e.to_string()),
+        if let Some(config) = config {
+            emit_phase_event(
+                &app_handle,
+                &info,
+                "-phase",
+                OperationPhase::Config,
+                "Writing lima.yaml".to_string(),
+            );
+            if let Err(e) = crate::lima_config_service::write_lima_yaml(
+                &app_handle,
+                &config,
+                &info.instance_name,
+            ) {
+                emit_phase_event(
+                    &app_handle,
+                    &info,
+                    "-error",
+                    OperationPhase::Config,
+                    e.to_string(),
                 );
                 return;
             }
-        };
-
-        // Stream stdout
-        if let Some(stdout) = child.stdout.take() {
-            let app_handle_stdout = app_handle.clone();
-            let instance_name_stdout = instance_name_clone.clone();
-            tokio::spawn(async move {
-                let mut reader = BufReader::new(stdout).lines();
-                while let Ok(Some(line)) = reader.next_line().await {
-                    let _ = app_handle_stdout.emit(
-                        "lima-instance-delete-stdout",
-                        create_log_payload(instance_name_stdout.clone(), line),
-                    );
-                }
-            });
         }
 
-        // Stream stderr and collect lines for error reporting
-        let stderr_lines = std::sync::Arc::new(tokio::sync::Mutex::new(Vec::<String>::new()));
-        let stderr_task = if let Some(stderr) = child.stderr.take() {
-            let app_handle_stderr = app_handle.clone();
-            let instance_name_stderr = instance_name_clone.clone();
-            let stderr_lines_clone = stderr_lines.clone();
-            Some(tokio::spawn(async move {
-                let mut reader = BufReader::new(stderr).lines();
-                while let Ok(Some(line)) = reader.next_line().await {
-                    stderr_lines_clone.lock().await.push(line.clone());
-                    let _ = app_handle_stderr.emit(
-                        "lima-instance-delete-stderr",
-                        create_log_payload(instance_name_stderr.clone(), line),
-                    );
-                }
-            }))
-        } else {
-            None
-        };
-
-        // Wait for process to complete
-        let wait_result = child.wait().await;
-        if let Some(task) = stderr_task {
-            let _ = task.await;
-        }
-        match wait_result {
-            Ok(status) => {
-                if status.success() {
-                    // Clean up shell profile and ~/.kube symlink before emitting success
-                    let _ = crate::lima_config_service::cleanup_env_on_delete(
-                        &app_handle,
-                        &instance_name_clone,
-                    );
-                    let _ = app_handle.emit(
-                        "lima-instance-delete-success",
-                        create_log_payload(instance_name_clone, "Deleted".to_string()),
-                    );
-                } else {
-                    let collected = stderr_lines.lock().await;
-                    let detail = if collected.is_empty() {
-                        format!("Exit code: {}", status.code().unwrap_or(-1))
-                    } else {
-                        collected.join("\n")
-                    };
-                    let _ = app_handle.emit(
-                        "lima-instance-delete-error",
-                        create_log_payload(instance_name_clone, detail),
-                    );
-                }
-            }
-            Err(e) => {
-                let error_msg = format!("Failed to wait for limactl delete process: {}", e);
-                let _ = app_handle.emit(
-                    "lima-instance-delete-error",
-                    create_log_payload(instance_name_clone, error_msg),
-                );
+        emit_phase_event(
+            &app_handle,
+            &info,
+            "-phase",
+            OperationPhase::Start,
+            format!("Starting Lima instance '{}'...", info.instance_name),
+        );
+        let outcome = match backend.start(&info.instance_name) {
+            Ok(child) => {
+                run_start(
+                    &app_handle,
+                    backend.as_ref(),
+                    &mut operation,
+                    child,
+                    readiness_service::START_READINESS_TIMEOUT,
+                )
+                .await
             }
+            Err(e) => Err(e),
+        };
+        let not_ready = match &outcome {
+            Ok((_, not_ready)) => not_ready.clone(),
+            Err(_) => None,
+        };
+        let outcome = outcome.map(|(outcome, _)| outcome);
+        if phase_succeeded(&app_handle, &info, OperationPhase::Start, outcome) {
+            emit_started(&app_handle, &info, "Restarted", not_ready);
         }
     });
 
-    Ok(instance_name)
+    Ok(operation_id)
 }
 
-pub async fn create_lima_instance(
-    app: AppHandle,
-    config: LimaConfig,
+/// Stop a running instance. Returns the operation id.
+/// A graceful stop escalates to `limactl stop --force` after the configured timeout;
+/// `force` skips the graceful attempt.
+pub async fn stop_lima_instance<R: Runtime>(
+    app: AppHandle<R>,
     instance_name: String,
-) -> Result<String, String> {
-    // Create a temporary config file for limactl create
-    let temp_dir = app
-        .path()
-        .temp_dir()
-        .map_err(|e| format!("Failed to get temp directory: {}", e))?;
-    let temp_config_path = temp_dir.join(format!("{}-lima-config.yaml", instance_name));
+    force: bool,
+) -> Result<String, AppError> {
+    let timeout = settings_service::load_lifecycle_settings(&app).stop_timeout_secs;
+    stop_instance_within(app, instance_name, force, Duration::from_secs(timeout)).await
+}
 
-    // Write config to temp file
-    let yaml_content = config
-        .to_yaml_pretty()
-        .map_err(|e| format!("Failed to serialize YAML: {}", e))?;
-    std::fs::write(&temp_config_path, yaml_content)
-        .map_err(|e| format!("Failed to write temporary config: {}", e))?;
+async fn stop_instance_within<R: Runtime>(
+    app: AppHandle<R>,
+    instance_name: String,
+    force: bool,
+    timeout: Duration,
+) -> Result<String, AppError> {
+    let mut operation = register_operation(&app, &instance_name, OperationKind::Stop)?;
+    let info = operation.info.clone();
 
-    // Emit create event
-    app.emit(
-        "lima-instance-create",
+    // Emit stop event
+    app.emit_logged(
+        "lima-instance-stop",
         create_log_payload(
-            instance_name.clone(),
-            format!("Creating Lima instance '{}'...", instance_name),
+            &info,
+            format!("Stopping Lima instance '{}'...", instance_name),
         ),
     )
-    .map_err(|e| format!("Failed to emit create event: {}", e))?;
+    .map_err(|e| AppError::io("Failed to emit stop event", e))?;
 
     // Spawn async task to run limactl and stream output
     let app_handle = app.clone();
-    let instance_name_clone = instance_name.clone();
-    let temp_config_path_clone = temp_config_path.clone();
+    let operation_id = info.operation_id.clone();
+    let backend = lima_backend(&app);
 
     tokio::spawn(async move {
-        let lima_cmd = match find_lima_executable() {
-            Some(cmd) => cmd,
-            None => {
-                let _ = app_handle.emit(
-                    "lima-instance-create-error",
-                    create_log_payload(
-                        instance_name_clone,
-                        "Lima (limactl) not found. Please ensure lima is installed in /usr/local/bin, /opt/homebrew/bin, or is in your PATH.".to_string(),
-                    ),
-                );
-                return;
-            }
-        };
+        let host_agent_pid = read_host_agent_pid(&app_handle, &info.instance_name);
+        let result = run_with_escalation(
+            &app_handle,
+            &mut operation,
+            |force| backend.stop(&info.instance_name, force),
+            force,
+            timeout,
+        )
+        .await;
+        emit_shutdown_outcome(&app_handle, &info, result, host_agent_pid, "Stopped").await;
+    });
 
-        // Run limactl create command with the temporary config file and explicit instance name
-        let child = TokioCommand::new(&lima_cmd)
-            .args([
-                "create",
-                "--tty=false",
-                "--name",
-                &instance_name_clone,
-                &temp_config_path_clone.to_string_lossy(),
-            ])
-            .stdin(Stdio::null())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-            .map_err(|e| format!("Failed to start limactl create process: {}", e));
-
-        let mut child = match child {
-            Ok(c) => c,
-            Err(e) => {
-                let _ = app_handle.emit(
-                    "lima-instance-create-error",
-                    create_log_payload(instance_name_clone.clone(), e.to_string()),
-                );
-                return;
-            }
-        };
+    Ok(operation_id)
+}
 
-        // Stream stdout
-        if let Some(stdout) = child.stdout.take() {
-            let app_handle_stdout = app_handle.clone();
-            let instance_name_stdout = instance_name_clone.clone();
-            tokio::spawn(async move {
-                let mut reader = BufReader::new(stdout).lines();
-                while let Ok(Some(line)) = reader.next_line().await {
-                    let _ = app_handle_stdout.emit(
-                        "lima-instance-create-stdout",
-                        create_log_payload(instance_name_stdout.clone(), line),
-                    );
-                }
-            });
-        }
+/// Delete an instance. Returns the operation id.
+/// Like stopping, a graceful delete escalates to `limactl delete --force` after the
+/// configured timeout, and `force` skips the graceful attempt.
+pub async fn delete_lima_instance<R: Runtime>(
+    app: AppHandle<R>,
+    instance_name: String,
+    force: bool,
+) -> Result<String, AppError> {
+    let timeout = settings_service::load_lifecycle_settings(&app).delete_timeout_secs;
+    delete_instance_within(app, instance_name, force, Duration::from_secs(timeout)).await
+}
 
-        // Stream stderr and collect lines for error reporting
-        let stderr_lines = std::sync::Arc::new(tokio::sync::Mutex::new(Vec::<String>::new()));
-        let stderr_task = if let Some(stderr) = child.stderr.take() {
-            let app_handle_stderr = app_handle.clone();
-            let instance_name_stderr = instance_name_clone.clone();
-            let stderr_lines_clone = stderr_lines.clone();
-            Some(tokio::spawn(async move {
-                let mut reader = BufReader::new(stderr).lines();
-                while let Ok(Some(line)) = reader.next_line().await {
-                    stderr_lines_clone.lock().await.push(line.clone());
-                    let _ = app_handle_stderr.emit(
-                        "lima-instance-create-stderr",
-                        create_log_payload(instance_name_stderr.clone(), line),
-                    );
-                }
-            }))
-        } else {
-            None
-        };
+async fn delete_instance_within<R: Runtime>(
+    app: AppHandle<R>,
+    instance_name: String,
+    force: bool,
+    timeout: Duration,
+) -> Result<String, AppError> {
+    let mut operation = register_operation(&app, &instance_name, OperationKind::Delete)?;
+    let info = operation.info.clone();
 
-        // Wait for process to complete
-        let wait_result = child.wait().await;
-        // Ensure stderr is fully read before checking collected lines
-        if let Some(task) = stderr_task {
-            let _ = task.await;
-        }
-        match wait_result {
-            Ok(status) => {
-                if status.success() {
-                    let _ = app_handle.emit(
-                        "lima-instance-create-success",
-                        create_log_payload(instance_name_clone, "Created".to_string()),
-                    );
-                } else {
-                    let collected = stderr_lines.lock().await;
-                    let detail = if collected.is_empty() {
-                        format!("Exit code: {}", status.code().unwrap_or(-1))
-                    } else {
-                        collected.join("\n")
-                    };
-                    let _ = app_handle.emit(
-                        "lima-instance-create-error",
-                        create_log_payload(instance_name_clone, detail),
-                    );
-                }
-            }
-            Err(e) => {
-                let error_msg = format!("Failed to wait for limactl create process: {}", e);
-                let _ = app_handle.emit(
-                    "lima-instance-create-error",
-                    create_log_payload(instance_name_clone, error_msg),
-                );
-            }
-        }
+    // Emit delete event
+    app.emit_logged(
+        "lima-instance-delete",
+        create_log_payload(
+            &info,
+            format!("Deleting Lima instance '{}'...", instance_name),
+        ),
+    )
+    .map_err(|e| AppError::io("Failed to emit delete event", e))?;
+
+    // Spawn async task to run limactl and stream output
+    let app_handle = app.clone();
+    let operation_id = info.operation_id.clone();
+    let backend = lima_backend(&app);
+
+    tokio::spawn(async move {
+        // Read before limactl removes the instance directory
+        let host_agent_pid = read_host_agent_pid(&app_handle, &info.instance_name);
+        let result = run_with_escalation(
+            &app_handle,
+            &mut operation,
+            |force| backend.delete(&info.instance_name, force),
+            force,
+            timeout,
+        )
+        .await;
+        if matches!(&result, Ok((ProcessOutcome::Exited(status, _), _)) if status.success()) {
+            // Clean up shell profile and ~/.kube symlink before emitting success
+            let _ =
+                crate::lima_config_service::cleanup_env_on_delete(&app_handle, &info.instance_name);
+        }
+        emit_shutdown_outcome(&app_handle, &info, result, host_agent_pid, "Deleted").await;
+    });
+
+    Ok(operation_id)
+}
+
+/// Create an instance from `config`. Returns the operation id.
+pub async fn create_lima_instance<R: Runtime>(
+    app: AppHandle<R>,
+    config: LimaConfig,
+    instance_name: String,
+) -> Result<String, AppError> {
+    let mut operation = register_operation(&app, &instance_name, OperationKind::Create)?;
+    let info = operation.info.clone();
+
+    // Create a temporary config file for limactl create
+    let temp_config_path = write_temp_config(
+        &app,
+        &format!("{}-lima-config.yaml", instance_name),
+        &config,
+    )?;
+
+    // Emit create event
+    app.emit_logged(
+        "lima-instance-create",
+        create_log_payload(
+            &info,
+            format!("Creating Lima instance '{}'...", instance_name),
+        ),
+    )
+    .map_err(|e| AppError::io("Failed to emit create event", e))?;
+
+    let backend = lima_backend(&app);
+    if let Err(e) = check_lima_compatibility(&app, backend.as_ref(), &info, &config).await {
+        let _ = std::fs::remove_file(&temp_config_path);
+        return Err(e);
+    }
+    if let Err(e) = check_host_resources(&app, &info, &config, true).await {
+        let _ = std::fs::remove_file(&temp_config_path);
+        return Err(e);
+    }
+
+    // Spawn async task to run limactl and stream output
+    let app_handle = app.clone();
+    let operation_id = info.operation_id.clone();
+
+    tokio::spawn(async move {
+        // Run limactl create with the temporary config file and explicit instance name
+        let child = match backend.create(&info.instance_name, &temp_config_path) {
+            Ok(c) => c,
+            Err(e) => {
+                let _ = std::fs::remove_file(&temp_config_path);
+                emit_operation_event(&app_handle, &info, "-error", e.to_string());
+                return;
+            }
+        };
+
+        let on_line = boot_progress_hook(&app_handle, &info);
+        let outcome = stream_lima_process(&app_handle, &mut operation, child, Some(on_line)).await;
+        // limactl copies the config into the instance directory, so the temp file
+        // is no longer needed however the operation ended
+        let _ = std::fs::remove_file(&temp_config_path);
+        emit_outcome(&app_handle, &info, outcome, "Created");
+    });
+
+    Ok(operation_id)
+}
+
+/// Write `config` to `file_name` in the temp dir, for `limactl create` to read
+fn write_temp_config<R: Runtime>(
+    app: &AppHandle<R>,
+    file_name: &str,
+    config: &LimaConfig,
+) -> Result<PathBuf, AppError> {
+    let temp_dir = app
+        .path()
+        .temp_dir()
+        .map_err(|e| AppError::io("Failed to get temp directory", e))?;
+    let temp_config_path = temp_dir.join(file_name);
+
+    let yaml_content = config
+        .to_yaml_pretty()
+        .map_err(|e| AppError::parse("Failed to serialize YAML", e))?;
+    std::fs::write(&temp_config_path, yaml_content)
+        .map_err(|e| AppError::io("Failed to write temporary config", e))?;
+    Ok(temp_config_path)
+}
+
+/// Env scripts sourced from the instance dir by the user's shell profiles
+const ENV_FILES: &[&str] = &["env.sh", "env.fish"];
+
+/// Delete an instance and create it again from its current lima.yaml, as one operation.
+/// Unlike a plain delete the env integration survives: the env scripts are put back into
+/// the new instance dir, and shell profile lines and the `~/.kube` symlink are left alone.
+/// Events use the `lima-instance-recreate` family, with `-phase` marking the stop (only
+/// for a running instance), delete and create steps. Returns the operation id.
+pub async fn recreate_lima_instance<R: Runtime>(
+    app: AppHandle<R>,
+    instance_name: String,
+) -> Result<String, AppError> {
+    let mut operation = register_operation(&app, &instance_name, OperationKind::Recreate)?;
+    let info = operation.info.clone();
+
+    let backend = lima_backend(&app);
+    let instances = instance_registry_service::get_all_lima_instances(backend.as_ref()).await?;
+    let is_running = instances
+        .iter()
+        .find(|i| i.name == instance_name)
+        .ok_or_else(|| AppError::InstanceNotFound {
+            instance_name: instance_name.clone(),
+        })?
+        .status
+        == "Running";
+    let config = crate::lima_config_service::read_lima_yaml(&app, &instance_name)?;
+
+    // Emit recreate event
+    app.emit_logged(
+        "lima-instance-recreate",
+        create_log_payload(
+            &info,
+            format!("Recreating Lima instance '{}'...", instance_name),
+        ),
+    )
+    .map_err(|e| AppError::io("Failed to emit recreate event", e))?;
+
+    // Reject an incompatible or oversized config before deleting anything
+    check_lima_compatibility(&app, backend.as_ref(), &info, &config).await?;
+    check_host_resources(&app, &info, &config, true).await?;
+
+    // Doubles as the copy of lima.yaml to recover from should the create fail
+    let config_path = write_temp_config(
+        &app,
+        &format!("{}-recreate-lima.yaml", instance_name),
+        &config,
+    )?;
+    let instance_dir = crate::yaml_handler::get_instance_dir(&app, &instance_name)?;
+    let env_files: Vec<(&str, String, std::fs::Permissions)> = ENV_FILES
+        .iter()
+        .filter_map(|file| {
+            let path = instance_dir.join(file);
+            let content = std::fs::read_to_string(&path).ok()?;
+            Some((*file, content, std::fs::metadata(&path).ok()?.permissions()))
+        })
+        .collect();
+
+    // Spawn async task to run limactl and stream output
+    let app_handle = app.clone();
+    let operation_id = info.operation_id.clone();
+    let timeouts = settings_service::load_lifecycle_settings(&app);
+
+    tokio::spawn(async move {
+        if is_running {
+            emit_phase_event(
+                &app_handle,
+                &info,
+                "-phase",
+                OperationPhase::Stop,
+                format!("Stopping Lima instance '{}'...", info.instance_name),
+            );
+            let outcome = run_with_escalation(
+                &app_handle,
+                &mut operation,
+                |force| backend.stop(&info.instance_name, force),
+                false,
+                Duration::from_secs(timeouts.stop_timeout_secs),
+            )
+            .await
+            .map(|(outcome, _)| outcome);
+            if !phase_succeeded(&app_handle, &info, OperationPhase::Stop, outcome) {
+                let _ = std::fs::remove_file(&config_path);
+                return;
+            }
+        }
+
+        emit_phase_event(
+            &app_handle,
+            &info,
+            "-phase",
+            OperationPhase::Delete,
+            format!("Deleting Lima instance '{}'...", info.instance_name),
+        );
+        let outcome = run_with_escalation(
+            &app_handle,
+            &mut operation,
+            |force| backend.delete(&info.instance_name, force),
+            false,
+            Duration::from_secs(timeouts.delete_timeout_secs),
+        )
+        .await
+        .map(|(outcome, _)| outcome);
+        if !phase_succeeded(&app_handle, &info, OperationPhase::Delete, outcome) {
+            let _ = std::fs::remove_file(&config_path);
+            return;
+        }
+
+        emit_phase_event(
+            &app_handle,
+            &info,
+            "-phase",
+            OperationPhase::Create,
+            format!("Creating Lima instance '{}'...", info.instance_name),
+        );
+        let outcome = match backend.create(&info.instance_name, &config_path) {
+            Ok(child) => {
+                let on_line = boot_progress_hook(&app_handle, &info);
+                stream_lima_process(&app_handle, &mut operation, child, Some(on_line)).await
+            }
+            Err(e) => Err(e),
+        };
+        if !matches!(&outcome, Ok(ProcessOutcome::Exited(status, _)) if status.success()) {
+            // The instance is gone, so keep its config where the user can find it
+            emit_operation_event(
+                &app_handle,
+                &info,
+                "-stderr",
+                format!("lima.yaml was kept at {}", config_path.display()),
+            );
+        }
+        if !phase_succeeded(&app_handle, &info, OperationPhase::Create, outcome) {
+            return;
+        }
+        let _ = std::fs::remove_file(&config_path);
+
+        for (file, content, permissions) in env_files {
+            let path = instance_dir.join(file);
+            let restored = std::fs::write(&path, content)
+                .and_then(|_| std::fs::set_permissions(&path, permissions));
+            if let Err(e) = restored {
+                let e = AppError::io(&format!("Failed to restore {}", file), e);
+                emit_operation_event(&app_handle, &info, "-error", e.to_string());
+                return;
+            }
+        }
+        emit_operation_event(&app_handle, &info, "-success", "Recreated".to_string());
+    });
+
+    Ok(operation_id)
+}
+
+/// Payload of events for an instance made from another one (`lima-instance-clone`,
+/// `lima-instance-rename-success`); `instance_name` is the new instance
+#[derive(Clone, serde::Serialize)]
+struct SourcePayload {
+    #[serde(flatten)]
+    log: LimaLogPayload,
+    source_name: String,
+}
+
+impl LogEvent for SourcePayload {
+    fn log(&self) -> &LimaLogPayload {
+        &self.log
+    }
+}
+
+/// Clone a stopped instance to `target_name`, using `limactl clone` when the installed
+/// Lima has it and copying the instance dir otherwise. The copy gets its own kubeconfig
+/// context, docker.sock path, env scripts and free host ports.
+/// Events use the `lima-instance-clone` family and carry the new instance's name.
+/// Returns the operation id.
+pub async fn clone_lima_instance<R: Runtime>(
+    app: AppHandle<R>,
+    source_name: String,
+    target_name: String,
+) -> Result<String, AppError> {
+    validate_instance_name(&target_name)?;
+    // The source must not start while it is copied, and nothing else may take the new name
+    let source_lock = register_operation(&app, &source_name, OperationKind::Clone)?;
+    let mut operation = register_operation(&app, &target_name, OperationKind::Clone)?;
+    let info = operation.info.clone();
+
+    let backend = lima_backend(&app);
+    let instances = instance_registry_service::get_all_lima_instances(backend.as_ref()).await?;
+    check_stopped_source_and_free_target(&app, &instances, &source_name, &target_name)?;
+    let source_dir = crate::yaml_handler::get_instance_dir(&app, &source_name)?;
+    let target_dir = crate::yaml_handler::get_instance_dir(&app, &target_name)?;
+
+    // Host ports claimed by any instance, running or not
+    let taken_ports: BTreeSet<u16> = instances
+        .iter()
+        .filter_map(|i| read_instance_config(&app, &i.name))
+        .flat_map(|config| config.port_forwards.unwrap_or_default())
+        .filter_map(|forward| forward.host_port)
+        .collect();
+
+    // Emit clone event
+    app.emit_logged(
+        "lima-instance-clone",
+        SourcePayload {
+            log: create_log_payload(
+                &info,
+                format!(
+                    "Cloning Lima instance '{}' to '{}'...",
+                    source_name, target_name
+                ),
+            ),
+            source_name: source_name.clone(),
+        },
+    )
+    .map_err(|e| AppError::io("Failed to emit clone event", e))?;
+
+    let use_limactl = matches!(
+        lima_compat_service::installed_lima_version(backend.as_ref()).await,
+        Ok(Some(version)) if version >= LIMACTL_CLONE_VERSION
+    );
+
+    // Spawn async task to run limactl and stream output
+    let app_handle = app.clone();
+    let operation_id = info.operation_id.clone();
+
+    tokio::spawn(async move {
+        let _source_lock = source_lock;
+        let outcome = if use_limactl {
+            match backend.clone_instance(&source_name, &info.instance_name) {
+                Ok(child) => stream_lima_process(&app_handle, &mut operation, child, None).await,
+                Err(e) => Err(e),
+            }
+        } else {
+            copy_instance(&app_handle, &mut operation, &source_dir, &target_dir).await
+        };
+
+        if !matches!(&outcome, Ok(ProcessOutcome::Exited(status, _)) if status.success()) {
+            // The name was free before, so anything there is a partial copy
+            let _ = std::fs::remove_dir_all(&target_dir);
+            emit_outcome(&app_handle, &info, outcome, "Cloned");
+            return;
+        }
+
+        match retarget_clone(&app_handle, &info, &source_name, &source_dir, &taken_ports) {
+            Ok(()) => emit_operation_event(&app_handle, &info, "-success", "Cloned".to_string()),
+            Err(e) => emit_operation_event(&app_handle, &info, "-error", e.to_string()),
+        }
+    });
+
+    Ok(operation_id)
+}
+
+/// Lima instance names double as directory names: letters, digits, '-', '_' and '.',
+/// starting with a letter or digit
+fn validate_instance_name(instance_name: &str) -> Result<(), AppError> {
+    let mut chars = instance_name.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphanumeric())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::InvalidInstanceName {
+            instance_name: instance_name.to_string(),
+        })
+    }
+}
+
+/// Clone and rename need a stopped source and an unused target name
+fn check_stopped_source_and_free_target<R: Runtime>(
+    app: &AppHandle<R>,
+    instances: &[instance_registry_service::LimaInstance],
+    source_name: &str,
+    target_name: &str,
+) -> Result<(), AppError> {
+    let source = instances
+        .iter()
+        .find(|i| i.name == source_name)
+        .ok_or_else(|| AppError::InstanceNotFound {
+            instance_name: source_name.to_string(),
+        })?;
+    if source.status != "Stopped" {
+        return Err(AppError::InstanceNotStopped {
+            instance_name: source_name.to_string(),
+            status: source.status.clone(),
+        });
+    }
+    let target_dir = crate::yaml_handler::get_instance_dir(app, target_name)?;
+    if instances.iter().any(|i| i.name == target_name) || target_dir.exists() {
+        return Err(AppError::InstanceAlreadyExists {
+            instance_name: target_name.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Whether a config copies the guest's kubeconfig to the host, i.e. runs Kubernetes
+pub fn has_host_kubeconfig(config: &LimaConfig) -> bool {
+    config
+        .copy_to_host
+        .iter()
+        .flatten()
+        .any(|copy| copy.host.ends_with("kubeconfig.yaml"))
+}
+
+/// Steps to undo, run in reverse when a later step of a multi-step change fails
+type UndoStack = Vec<Box<dyn FnOnce() + Send>>;
+
+/// Rename a stopped instance by moving its Lima instance dir. Everything that carries the
+/// name moves along: lima.yaml (kubeconfig context, paths into the dir), env.sh/env.fish
+/// (regenerated through `write_env_sh`), the locks of additional disks, the shell profile
+/// lines and the `~/.kube/<name>` symlink. If any step fails, the completed ones are
+/// rolled back.
+/// Emits `lima-instance-rename-success` with the new name.
+pub async fn rename_lima_instance<R: Runtime>(
+    app: AppHandle<R>,
+    old_name: String,
+    new_name: String,
+) -> Result<(), AppError> {
+    validate_instance_name(&new_name)?;
+    let _old_lock = register_operation(&app, &old_name, OperationKind::Rename)?;
+    let operation = register_operation(&app, &new_name, OperationKind::Rename)?;
+
+    let backend = lima_backend(&app);
+    let instances = instance_registry_service::get_all_lima_instances(backend.as_ref()).await?;
+    check_stopped_source_and_free_target(&app, &instances, &old_name, &new_name)?;
+
+    let mut undo = UndoStack::new();
+    if let Err(e) = move_instance(&app, &old_name, &new_name, &mut undo) {
+        log::warn!(
+            "Renaming '{}' to '{}' failed, rolling back: {}",
+            old_name,
+            new_name,
+            e
+        );
+        for step in undo.into_iter().rev() {
+            step();
+        }
+        return Err(e);
+    }
+
+    let _ = app.emit_logged(
+        "lima-instance-rename-success",
+        SourcePayload {
+            log: create_log_payload(
+                &operation.info,
+                format!("Renamed Lima instance '{}' to '{}'", old_name, new_name),
+            ),
+            source_name: old_name,
+        },
+    );
+    Ok(())
+}
+
+fn move_instance<R: Runtime>(
+    app: &AppHandle<R>,
+    old_name: &str,
+    new_name: &str,
+    undo: &mut UndoStack,
+) -> Result<(), AppError> {
+    use crate::lima_config_service as env;
+
+    let old_dir = crate::yaml_handler::get_instance_dir(app, old_name)?;
+    let new_dir = crate::yaml_handler::get_instance_dir(app, new_name)?;
+    let config = read_instance_config(app, old_name);
+    let had_env = old_dir.join("env.sh").exists();
+
+    std::fs::rename(&old_dir, &new_dir)
+        .map_err(|e| AppError::io("Failed to move instance directory", e))?;
+    {
+        let (old_dir, new_dir) = (old_dir.clone(), new_dir.clone());
+        undo.push(Box::new(move || {
+            let _ = std::fs::rename(&new_dir, &old_dir);
+        }));
+    }
+
+    // Locks of additional disks name the instance dir
+    let lima_home = crate::yaml_handler::get_lima_home(app)?;
+    let locks = crate::disk_service::retarget_disk_locks(&lima_home, &old_dir, &new_dir)?;
+    {
+        let old_dir = old_dir.clone();
+        undo.push(Box::new(move || {
+            crate::disk_service::restore_disk_locks(&locks, &old_dir)
+        }));
+    }
+
+    // Files rewritten inside the moved dir are restored before it moves back
+    let saved: Vec<(PathBuf, String)> = ["lima.yaml", "env.sh", "env.fish"]
+        .iter()
+        .filter_map(|file| {
+            let path = new_dir.join(file);
+            std::fs::read_to_string(&path)
+                .ok()
+                .map(|content| (path, content))
+        })
+        .collect();
+    undo.push(Box::new(move || env::restore_files(&saved)));
+
+    if let Some(mut config) = config.clone() {
+        config.retarget_instance(
+            old_name,
+            new_name,
+            &old_dir.to_string_lossy(),
+            &new_dir.to_string_lossy(),
+        );
+        env::write_lima_yaml(app, &config, new_name)?;
+    }
+
+    if had_env {
+        let k8s = config.as_ref().is_some_and(has_host_kubeconfig);
+        env::write_env_sh(app, new_name, k8s)?;
+    }
+
+    let profiles = env::rename_env_in_shell_profiles(app, old_name, new_name, &old_dir, &new_dir)?;
+    undo.push(Box::new(move || env::restore_files(&profiles)));
+
+    if env::rename_kube_symlink(app, old_name, new_name)?.is_some() {
+        let app = app.clone();
+        let (old_name, new_name) = (old_name.to_string(), new_name.to_string());
+        undo.push(Box::new(move || {
+            let _ = env::rename_kube_symlink(&app, &new_name, &old_name);
+        }));
+    }
+
+    // Still names the old context; the guest copies a fresh one on the next boot
+    let _ = std::fs::remove_file(new_dir.join("kubeconfig.yaml"));
+    Ok(())
+}
+
+/// Files a copy must not inherit: sockets, pid files and logs of the source's host agent
+/// and hypervisor, the kubeconfig fetched from the guest (fetched again on boot), the
+/// env scripts (written again for the copy) and the source's disk-copy snapshots
+fn is_instance_runtime_file(name: &str) -> bool {
+    name.ends_with(".sock")
+        || name.ends_with(".pid")
+        || name.ends_with(".log")
+        || matches!(
+            name,
+            "kubeconfig.yaml" | "env.sh" | "env.fish" | snapshot_service::SNAPSHOT_DIR
+        )
+}
+
+/// Copy a stopped instance dir in a blocking task, reporting each file as `-stdout`.
+/// A cancelled copy stops after the current chunk and its partial target is removed.
+/// A finished copy is reported like a successful limactl process.
+async fn copy_instance<R: Runtime>(
+    app: &AppHandle<R>,
+    operation: &mut OperationHandle,
+    source_dir: &Path,
+    target_dir: &Path,
+) -> Result<ProcessOutcome, AppError> {
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let copy = {
+        let app = app.clone();
+        let info = operation.info.clone();
+        let cancelled = cancelled.clone();
+        let source_dir = source_dir.to_path_buf();
+        let target_dir = target_dir.to_path_buf();
+        tokio::task::spawn_blocking(move || {
+            copy_dir(&source_dir, &target_dir, &cancelled, &|line| {
+                emit_operation_event(&app, &info, "-stdout", line)
+            })
+        })
+    };
+    tokio::pin!(copy);
+
+    tokio::select! {
+        result = &mut copy => {
+            result.map_err(|e| AppError::io("Failed to copy instance", e))??;
+            Ok(ProcessOutcome::Exited(LimaExitStatus { code: Some(0) }, Vec::new()))
+        }
+        _ = operation.cancelled() => {
+            cancelled.store(true, Ordering::Relaxed);
+            let _ = copy.await;
+            let _ = std::fs::remove_dir_all(target_dir);
+            Ok(ProcessOutcome::Cancelled)
+        }
+    }
+}
+
+fn copy_dir(
+    source: &Path,
+    target: &Path,
+    cancelled: &AtomicBool,
+    progress: &dyn Fn(String),
+) -> Result<(), AppError> {
+    std::fs::create_dir(target)
+        .map_err(|e| AppError::io(&format!("Failed to create {}", target.display()), e))?;
+    for entry in std::fs::read_dir(source)
+        .map_err(|e| AppError::io(&format!("Failed to read {}", source.display()), e))?
+    {
+        if cancelled.load(Ordering::Relaxed) {
+            return Err(AppError::io("Copy cancelled", copy_cancelled()));
+        }
+        let entry = entry?;
+        let name = entry.file_name();
+        if is_instance_runtime_file(&name.to_string_lossy()) {
+            continue;
+        }
+        let from = entry.path();
+        let to: PathBuf = target.join(&name);
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            copy_dir(&from, &to, cancelled, progress)?;
+        } else if file_type.is_symlink() {
+            std::os::unix::fs::symlink(std::fs::read_link(&from)?, &to)?;
+        } else if file_type.is_file() {
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            progress(format!(
+                "Copying {} ({:.1} MiB)",
+                name.to_string_lossy(),
+                size as f64 / (1024.0 * 1024.0)
+            ));
+            copy_file(&from, &to, cancelled)
+                .map_err(|e| AppError::io(&format!("Failed to copy {}", from.display()), e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Bytes copied between two cancel checks
+const COPY_CHUNK_SIZE: usize = 8 << 20;
+
+/// Error a copy stops with once cancelled, so a partial copy never passes for a finished one
+fn copy_cancelled() -> std::io::Error {
+    std::io::Error::from(std::io::ErrorKind::Interrupted)
+}
+
+/// Copy a file chunk by chunk, checking for a cancel after each chunk so a disk image
+/// of many GiB does not hold up the cancel. Zero chunks are skipped rather than
+/// written, which keeps sparse disk images sparse. Fails with `Interrupted` if cancelled.
+fn copy_file(from: &Path, to: &Path, cancelled: &AtomicBool) -> std::io::Result<()> {
+    use std::io::{BufRead, Seek, Write};
+
+    let source = std::fs::File::open(from)?;
+    let permissions = source.metadata()?.permissions();
+    let mut reader = std::io::BufReader::with_capacity(COPY_CHUNK_SIZE, source);
+    let mut writer = std::io::BufWriter::with_capacity(COPY_CHUNK_SIZE, std::fs::File::create(to)?);
+    let mut copied = 0;
+    loop {
+        if cancelled.load(Ordering::Relaxed) {
+            return Err(copy_cancelled());
+        }
+        let chunk = reader.fill_buf()?;
+        if chunk.is_empty() {
+            break;
+        }
+        let len = chunk.len();
+        if chunk.iter().all(|&byte| byte == 0) {
+            writer.seek(std::io::SeekFrom::Current(len as i64))?;
+        } else {
+            writer.write_all(chunk)?;
+        }
+        reader.consume(len);
+        copied += len as u64;
+    }
+    let target = writer.into_inner().map_err(|e| e.into_error())?;
+    // A trailing skipped chunk leaves the file short
+    target.set_len(copied)?;
+    target.set_permissions(permissions)
+}
+
+/// Rewrite what a fresh copy still shares with its source: lima.yaml (kubeconfig context,
+/// paths into the source dir, colliding host ports), the stale kubeconfig and env scripts
+fn retarget_clone<R: Runtime>(
+    app: &AppHandle<R>,
+    operation: &OperationInfo,
+    source_name: &str,
+    source_dir: &Path,
+    taken_ports: &BTreeSet<u16>,
+) -> Result<(), AppError> {
+    let target_name = &operation.instance_name;
+    let target_dir = crate::yaml_handler::get_instance_dir(app, target_name)?;
+    let Some(mut config) = read_instance_config(app, target_name) else {
+        return Ok(());
+    };
+
+    config.retarget_instance(
+        source_name,
+        target_name,
+        &source_dir.to_string_lossy(),
+        &target_dir.to_string_lossy(),
+    );
+    let remapped = config.remap_host_ports(|port| {
+        taken_ports.contains(&port) || std::net::TcpListener::bind(("127.0.0.1", port)).is_err()
     });
+    for (old, new) in remapped {
+        emit_operation_event(
+            app,
+            operation,
+            "-stdout",
+            format!("Forwarding host port {} instead of {}", new, old),
+        );
+    }
+    crate::lima_config_service::write_lima_yaml(app, &config, target_name)?;
+
+    // limactl clone copies everything; the kubeconfig still names the source's context
+    let _ = std::fs::remove_file(target_dir.join("kubeconfig.yaml"));
+    if source_dir.join("env.sh").exists() {
+        crate::lima_config_service::write_env_sh(app, target_name, has_host_kubeconfig(&config))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_lima_backend::FakeLimaBackend;
+    use crate::lima_backend::LimaBackendState;
+    use crate::operation_service::{cancel_operation, list_operations, OperationRegistry};
+    use crate::yaml_handler::HomeDirState;
+    use std::sync::Mutex;
+    use tauri::Listener;
+
+    const EVENT_SUFFIXES: &[&str] = &[
+        "",
+        "-stdout",
+        "-stderr",
+        "-ready",
+        "-not-ready",
+        "-success",
+        "-error",
+        "-cancelled",
+        "-phase",
+        "-escalated",
+    ];
+
+    /// Mock app wired to the fake backend, recording every `lima-instance-<op>*` event name.
+    /// Registers the settings store, which stop and delete read their timeouts from, and a
+    /// temp home dir so env cleanup and renames never touch the real shell profiles.
+    fn mock_app_with_fake(
+        backend: impl Into<Arc<FakeLimaBackend>>,
+        ops: &[&str],
+    ) -> (
+        tauri::App<tauri::test::MockRuntime>,
+        Arc<Mutex<Vec<String>>>,
+    ) {
+        let app = tauri::test::mock_builder()
+            .plugin(tauri_plugin_store::Builder::new().build())
+            .build(tauri::test::mock_context(tauri::test::noop_assets()))
+            .unwrap();
+        app.manage(LimaBackendState(backend.into()));
+        app.manage(OperationRegistry::default());
+        let home = std::env::temp_dir().join(format!("0ma-home-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(home.join(".kube")).unwrap();
+        app.manage(HomeDirState(home));
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        for op in ops {
+            for suffix in EVENT_SUFFIXES {
+                let name = format!("lima-instance-{}{}", op, suffix);
+                let events = events.clone();
+                let recorded = name.clone();
+                app.listen_any(name, move |_| {
+                    events.lock().unwrap().push(recorded.clone());
+                });
+            }
+        }
+        (app, events)
+    }
+
+    /// Temp home dir of a `mock_app_with_fake` app
+    fn home_dir(app: &tauri::App<tauri::test::MockRuntime>) -> PathBuf {
+        app.state::<HomeDirState>().0.clone()
+    }
+
+    /// Wait until an event with the given name is recorded (or time out)
+    async fn wait_for(events: &Arc<Mutex<Vec<String>>>, name: &str) {
+        for _ in 0..200 {
+            if events.lock().unwrap().iter().any(|e| e == name) {
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        panic!(
+            "timed out waiting for '{}', got {:?}",
+            name,
+            events.lock().unwrap()
+        );
+    }
+
+    /// Event names with stdout/stderr noise filtered out
+    fn lifecycle_events(events: &Arc<Mutex<Vec<String>>>) -> Vec<String> {
+        events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| !e.ends_with("-stdout") && !e.ends_with("-stderr"))
+            .cloned()
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_start_emits_start_ready_success() {
+        let backend = FakeLimaBackend::new().with_instance("dev", "Stopped");
+        let (app, events) = mock_app_with_fake(backend, &["start"]);
+
+        start_lima_instance(app.handle().clone(), "dev".to_string())
+            .await
+            .unwrap();
+        wait_for(&events, "lima-instance-start-success").await;
+
+        assert_eq!(
+            lifecycle_events(&events),
+            vec![
+                "lima-instance-start",
+                "lima-instance-start-ready",
+                "lima-instance-start-success",
+            ]
+        );
+        assert!(events
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|e| e == "lima-instance-start-stderr"));
+    }
+
+    #[tokio::test]
+    async fn test_start_reports_failed_readiness_conditions() {
+        let backend = FakeLimaBackend::new().with_instance("dev", "Stopped");
+        let dir = backend.lima_home().unwrap().join("dev");
+        std::fs::write(
+            dir.join("lima.yaml"),
+            "probes:\n- description: k0s to be running\n  script: exit 1\n",
+        )
+        .unwrap();
+        let (app, events) = mock_app_with_fake(backend, &["start"]);
+        let payloads = Arc::new(Mutex::new(Vec::<serde_json::Value>::new()));
+        for event in [
+            "lima-instance-start-not-ready",
+            "lima-instance-start-success",
+        ] {
+            let payloads = payloads.clone();
+            app.listen_any(event, move |event| {
+                payloads
+                    .lock()
+                    .unwrap()
+                    .push(serde_json::from_str(event.payload()).unwrap());
+            });
+        }
+
+        start_instance_within(app.handle().clone(), "dev".to_string(), Duration::ZERO)
+            .await
+            .unwrap();
+        wait_for(&events, "lima-instance-start-success").await;
+
+        assert_eq!(
+            lifecycle_events(&events),
+            vec![
+                "lima-instance-start",
+                "lima-instance-start-not-ready",
+                "lima-instance-start-success",
+            ]
+        );
+        let payloads = payloads.lock().unwrap();
+        let not_ready = payloads[0]["message"].as_str().unwrap();
+        assert!(not_ready.contains("probe 'k0s to be running'"));
+        // The start itself worked, but the success says it is not ready
+        assert_eq!(payloads[1]["ready"], false);
+        assert_eq!(payloads[1]["message"], not_ready);
+    }
+
+    #[tokio::test]
+    async fn test_operation_events_are_persisted() {
+        let backend = FakeLimaBackend::new().with_instance("dev", "Stopped");
+        let (app, events) = mock_app_with_fake(backend, &["start"]);
+        let log_dir = std::env::temp_dir().join(format!("0ma-oplogs-{}", uuid::Uuid::new_v4()));
+        app.manage(operation_log_service::OperationLogState::new(
+            log_dir.clone(),
+        ));
+
+        let operation_id = start_lima_instance(app.handle().clone(), "dev".to_string())
+            .await
+            .unwrap();
+        wait_for(&events, "lima-instance-start-success").await;
+
+        let logs = operation_log_service::list_operation_logs(app.handle(), Some("dev")).unwrap();
+        assert_eq!(logs[0].operation_id, operation_id);
+        assert_eq!(logs[0].outcome.as_deref(), Some("success"));
+        let entries =
+            operation_log_service::read_operation_log(app.handle(), "dev", &operation_id, None)
+                .unwrap();
+        for event in events.lock().unwrap().iter() {
+            assert!(
+                entries.iter().any(|e| &e.event == event),
+                "{} missing",
+                event
+            );
+        }
+        assert!(entries.iter().any(|e| e.stream == LogStream::Stderr));
+        let tail =
+            operation_log_service::read_operation_log(app.handle(), "dev", &operation_id, Some(1))
+                .unwrap();
+        assert_eq!(tail[0].event, "lima-instance-start-success");
+        let _ = std::fs::remove_dir_all(log_dir);
+    }
+
+    #[tokio::test]
+    async fn test_start_missing_instance_emits_error() {
+        let (app, events) = mock_app_with_fake(FakeLimaBackend::new(), &["start"]);
+
+        start_lima_instance(app.handle().clone(), "ghost".to_string())
+            .await
+            .unwrap();
+        wait_for(&events, "lima-instance-start-error").await;
+
+        assert_eq!(
+            lifecycle_events(&events),
+            vec!["lima-instance-start", "lima-instance-start-error"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_stop_delete_sequence() {
+        let (app, events) = mock_app_with_fake(
+            FakeLimaBackend::new(),
+            &["create", "start", "stop", "delete"],
+        );
+        let handle = app.handle().clone();
+        let home = home_dir(&app);
+        let lima_home = crate::yaml_handler::get_lima_home(&handle).unwrap();
+        std::fs::write(
+            home.join(".zshrc"),
+            format!(
+                "export EDITOR=vi\n# 0ma environment for instance seq\n[ -f \"{env}\" ] && source \"{env}\"\n",
+                env = lima_home.join("seq/env.sh").display()
+            ),
+        )
+        .unwrap();
+        std::os::unix::fs::symlink(
+            lima_home.join("seq/kubeconfig.yaml"),
+            home.join(".kube/seq"),
+        )
+        .unwrap();
+
+        create_lima_instance(handle.clone(), LimaConfig::default(), "seq".to_string())
+            .await
+            .unwrap();
+        wait_for(&events, "lima-instance-create-success").await;
+        start_lima_instance(handle.clone(), "seq".to_string())
+            .await
+            .unwrap();
+        wait_for(&events, "lima-instance-start-success").await;
+        stop_lima_instance(handle.clone(), "seq".to_string(), false)
+            .await
+            .unwrap();
+        wait_for(&events, "lima-instance-stop-success").await;
+        delete_lima_instance(handle.clone(), "seq".to_string(), false)
+            .await
+            .unwrap();
+        wait_for(&events, "lima-instance-delete-success").await;
+
+        assert_eq!(
+            lifecycle_events(&events),
+            vec![
+                "lima-instance-create",
+                "lima-instance-create-success",
+                "lima-instance-start",
+                "lima-instance-start-ready",
+                "lima-instance-start-success",
+                "lima-instance-stop",
+                "lima-instance-stop-success",
+                "lima-instance-delete",
+                "lima-instance-delete-success",
+            ]
+        );
+        // Env cleanup on delete stays inside the temp home
+        assert_eq!(
+            std::fs::read_to_string(home.join(".zshrc")).unwrap(),
+            "export EDITOR=vi\n"
+        );
+        assert!(!home.join(".kube/seq").is_symlink());
+    }
+
+    #[tokio::test]
+    async fn test_stop_failure_reports_stderr() {
+        let backend = FakeLimaBackend::new().with_instance("dev", "Running");
+        backend.fail_next("stop", "hostagent did not respond");
+        let (app, events) = mock_app_with_fake(backend, &["stop"]);
+
+        let messages = Arc::new(Mutex::new(Vec::<String>::new()));
+        {
+            let messages = messages.clone();
+            app.listen_any("lima-instance-stop-error", move |event| {
+                messages.lock().unwrap().push(event.payload().to_string());
+            });
+        }
+
+        stop_lima_instance(app.handle().clone(), "dev".to_string(), false)
+            .await
+            .unwrap();
+        wait_for(&events, "lima-instance-stop-error").await;
+
+        assert!(messages.lock().unwrap()[0].contains("hostagent did not respond"));
+    }
+
+    #[tokio::test]
+    async fn test_create_rejects_incompatible_lima_version() {
+        let backend = Arc::new(FakeLimaBackend::new());
+        let app = tauri::test::mock_app();
+        app.manage(LimaBackendState(backend.clone()));
+        let events = Arc::new(Mutex::new(Vec::new()));
+        for name in ["lima-instance-create", "lima-instance-create-error"] {
+            let events = events.clone();
+            app.listen_any(name, move |_| events.lock().unwrap().push(name.to_string()));
+        }
+
+        let config = LimaConfig {
+            minimum_lima_version: Some("3.0.0".to_string()),
+            ..Default::default()
+        };
+        let err = create_lima_instance(app.handle().clone(), config, "future".to_string())
+            .await
+            .unwrap_err();
+
+        match err {
+            AppError::LimaIncompatible {
+                installed_version,
+                issues,
+                ..
+            } => {
+                assert_eq!(installed_version, "2.0.0");
+                assert_eq!(issues[0].field, "minimumLimaVersion");
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+        wait_for(&events, "lima-instance-create-error").await;
+        assert_eq!(backend.status("future"), None);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_start_kills_process_and_stops_instance() {
+        let backend = Arc::new(FakeLimaBackend::new().with_instance("dev", "Stopped"));
+        backend.hang_next("start");
+        let (app, events) = mock_app_with_fake(backend.clone(), &["start"]);
+
+        let operation_id = start_lima_instance(app.handle().clone(), "dev".to_string())
+            .await
+            .unwrap();
+        assert_eq!(list_operations(app.handle())[0].operation_id, operation_id);
+
+        cancel_operation(app.handle(), &operation_id).unwrap();
+        wait_for(&events, "lima-instance-start-cancelled").await;
+
+        assert_eq!(
+            lifecycle_events(&events),
+            vec!["lima-instance-start", "lima-instance-start-cancelled"]
+        );
+        assert_eq!(backend.status("dev").as_deref(), Some("Stopped"));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_create_removes_temp_config() {
+        let backend = FakeLimaBackend::new();
+        backend.hang_next("create");
+        let (app, events) = mock_app_with_fake(backend, &["create"]);
+        let temp_config_path = app
+            .path()
+            .temp_dir()
+            .unwrap()
+            .join("cancelled-lima-config.yaml");
+
+        let operation_id = create_lima_instance(
+            app.handle().clone(),
+            LimaConfig::default(),
+            "cancelled".to_string(),
+        )
+        .await
+        .unwrap();
+        assert!(temp_config_path.exists());
+
+        cancel_operation(app.handle(), &operation_id).unwrap();
+        wait_for(&events, "lima-instance-create-cancelled").await;
+
+        assert!(!temp_config_path.exists());
+        assert!(!lifecycle_events(&events).contains(&"lima-instance-create-success".to_string()));
+    }
 
-    Ok(instance_name)
+    #[tokio::test]
+    async fn test_conflicting_operation_is_rejected() {
+        let backend = FakeLimaBackend::new().with_instance("dev", "Stopped");
+        backend.hang_next("start");
+        let (app, events) = mock_app_with_fake(backend, &["start", "stop"]);
+        let handle = app.handle().clone();
+
+        let operation_id = start_lima_instance(handle.clone(), "dev".to_string())
+            .await
+            .unwrap();
+        let err = stop_lima_instance(handle.clone(), "dev".to_string(), false)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), "operationInProgress");
+        assert!(!events
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|e| e.starts_with("lima-instance-stop")));
+
+        // Once the start is gone the instance can be stopped
+        cancel_operation(&handle, &operation_id).unwrap();
+        wait_for(&events, "lima-instance-start-cancelled").await;
+        for _ in 0..200 {
+            if list_operations(&handle).is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        stop_lima_instance(handle, "dev".to_string(), false)
+            .await
+            .unwrap();
+        wait_for(&events, "lima-instance-stop-success").await;
+    }
+
+    #[tokio::test]
+    async fn test_start_emits_boot_progress() {
+        let backend = FakeLimaBackend::new().with_instance("dev", "Stopped");
+        let (app, events) = mock_app_with_fake(backend, &["start"]);
+        let progress = Arc::new(Mutex::new(Vec::<serde_json::Value>::new()));
+        {
+            let progress = progress.clone();
+            app.listen_any("lima-instance-start-progress", move |event| {
+                progress
+                    .lock()
+                    .unwrap()
+                    .push(serde_json::from_str(event.payload()).unwrap());
+            });
+        }
+
+        start_lima_instance(app.handle().clone(), "dev".to_string())
+            .await
+            .unwrap();
+        wait_for(&events, "lima-instance-start-success").await;
+
+        let progress = progress.lock().unwrap();
+        let phases: Vec<(&str, &str)> = progress
+            .iter()
+            .map(|p| (p["phase"].as_str().unwrap(), p["status"].as_str().unwrap()))
+            .collect();
+        assert_eq!(phases, vec![("vm_boot", "started"), ("ready", "completed")]);
+        assert_eq!(progress[0]["instance_name"], "dev");
+        assert_eq!(progress[0]["level"], "info");
+    }
+
+    #[tokio::test]
+    async fn test_restart_rewrites_config_between_stop_and_start() {
+        let backend = Arc::new(FakeLimaBackend::new().with_instance("dev", "Running"));
+        let lima_home = crate::lima_backend::LimaBackend::lima_home(backend.as_ref()).unwrap();
+        let (app, events) = mock_app_with_fake(backend.clone(), &["restart"]);
+
+        let config = LimaConfig {
+            cpus: Some(6),
+            ..Default::default()
+        };
+        restart_lima_instance(app.handle().clone(), "dev".to_string(), Some(config))
+            .await
+            .unwrap();
+        wait_for(&events, "lima-instance-restart-success").await;
+
+        assert_eq!(
+            lifecycle_events(&events),
+            vec![
+                "lima-instance-restart",
+                "lima-instance-restart-phase",
+                "lima-instance-restart-phase",
+                "lima-instance-restart-phase",
+                "lima-instance-restart-ready",
+                "lima-instance-restart-success",
+            ]
+        );
+        let yaml = std::fs::read_to_string(lima_home.join("dev").join("lima.yaml")).unwrap();
+        assert_eq!(LimaConfig::from_yaml(&yaml).unwrap().cpus, Some(6));
+        assert_eq!(backend.status("dev").as_deref(), Some("Running"));
+    }
+
+    #[tokio::test]
+    async fn test_restart_reports_failed_phase() {
+        let backend = FakeLimaBackend::new().with_instance("dev", "Running");
+        backend.fail_next("start", "boot failed");
+        let (app, events) = mock_app_with_fake(backend, &["restart"]);
+        let payloads = Arc::new(Mutex::new(Vec::<serde_json::Value>::new()));
+        {
+            let payloads = payloads.clone();
+            app.listen_any("lima-instance-restart-error", move |event| {
+                payloads
+                    .lock()
+                    .unwrap()
+                    .push(serde_json::from_str(event.payload()).unwrap());
+            });
+        }
+
+        restart_lima_instance(app.handle().clone(), "dev".to_string(), None)
+            .await
+            .unwrap();
+        wait_for(&events, "lima-instance-restart-error").await;
+
+        let payloads = payloads.lock().unwrap();
+        assert_eq!(payloads[0]["phase"], "start");
+        assert!(payloads[0]["message"]
+            .as_str()
+            .unwrap()
+            .contains("boot failed"));
+    }
+
+    #[tokio::test]
+    async fn test_recreate_keeps_env_scripts() {
+        let backend = Arc::new(FakeLimaBackend::new().with_instance("dev", "Running"));
+        let dir = crate::lima_backend::LimaBackend::lima_home(backend.as_ref())
+            .unwrap()
+            .join("dev");
+        std::fs::write(dir.join("lima.yaml"), "cpus: 3\n").unwrap();
+        std::fs::write(dir.join("env.sh"), "export DOCKER_HOST=unix:///x\n").unwrap();
+        std::fs::write(dir.join("diffdisk"), "broken").unwrap();
+        let (app, events) = mock_app_with_fake(backend.clone(), &["recreate"]);
+
+        recreate_lima_instance(app.handle().clone(), "dev".to_string())
+            .await
+            .unwrap();
+        wait_for(&events, "lima-instance-recreate-success").await;
+
+        assert_eq!(
+            lifecycle_events(&events),
+            vec![
+                "lima-instance-recreate",
+                "lima-instance-recreate-phase",
+                "lima-instance-recreate-phase",
+                "lima-instance-recreate-phase",
+                "lima-instance-recreate-success",
+            ]
+        );
+        assert_eq!(backend.status("dev").as_deref(), Some("Stopped"));
+        assert!(!dir.join("diffdisk").exists());
+        let yaml = std::fs::read_to_string(dir.join("lima.yaml")).unwrap();
+        assert_eq!(LimaConfig::from_yaml(&yaml).unwrap().cpus, Some(3));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("env.sh")).unwrap(),
+            "export DOCKER_HOST=unix:///x\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_recreate_keeps_config_when_create_fails() {
+        let backend = FakeLimaBackend::new().with_instance("dev", "Stopped");
+        let dir = backend.lima_home().unwrap().join("dev");
+        std::fs::write(dir.join("lima.yaml"), "cpus: 3\n").unwrap();
+        backend.fail_next("create", "image download failed");
+        let (app, events) = mock_app_with_fake(backend, &["recreate"]);
+        let messages = Arc::new(Mutex::new(Vec::<String>::new()));
+        {
+            let messages = messages.clone();
+            app.listen_any("lima-instance-recreate-stderr", move |event| {
+                let payload: serde_json::Value = serde_json::from_str(event.payload()).unwrap();
+                messages
+                    .lock()
+                    .unwrap()
+                    .push(payload["message"].as_str().unwrap().to_string());
+            });
+        }
+
+        recreate_lima_instance(app.handle().clone(), "dev".to_string())
+            .await
+            .unwrap();
+        wait_for(&events, "lima-instance-recreate-error").await;
+
+        let kept = messages
+            .lock()
+            .unwrap()
+            .iter()
+            .find_map(|m| m.strip_prefix("lima.yaml was kept at ").map(PathBuf::from))
+            .expect("kept config path");
+        let yaml = std::fs::read_to_string(&kept).unwrap();
+        assert_eq!(LimaConfig::from_yaml(&yaml).unwrap().cpus, Some(3));
+        let _ = std::fs::remove_file(kept);
+    }
+
+    #[tokio::test]
+    async fn test_stop_escalates_to_force_after_timeout() {
+        let backend = FakeLimaBackend::new().with_instance("dev", "Running");
+        backend.hang_next("stop");
+        let (app, events) = mock_app_with_fake(backend, &["stop"]);
+        let payloads = Arc::new(Mutex::new(Vec::<serde_json::Value>::new()));
+        {
+            let payloads = payloads.clone();
+            app.listen_any("lima-instance-stop-success", move |event| {
+                payloads
+                    .lock()
+                    .unwrap()
+                    .push(serde_json::from_str(event.payload()).unwrap());
+            });
+        }
+
+        stop_instance_within(
+            app.handle().clone(),
+            "dev".to_string(),
+            false,
+            Duration::from_millis(50),
+        )
+        .await
+        .unwrap();
+        wait_for(&events, "lima-instance-stop-success").await;
+
+        assert_eq!(
+            lifecycle_events(&events),
+            vec![
+                "lima-instance-stop",
+                "lima-instance-stop-escalated",
+                "lima-instance-stop-success",
+            ]
+        );
+        let payloads = payloads.lock().unwrap();
+        assert_eq!(payloads[0]["forced"], true);
+        assert_eq!(payloads[0]["host_agent_exited"], serde_json::Value::Null);
+    }
+
+    #[tokio::test]
+    async fn test_stale_host_agent_pid_of_another_process_is_a_warning() {
+        let backend = Arc::new(FakeLimaBackend::new().with_instance("dev", "Running"));
+        let lima_home = crate::lima_backend::LimaBackend::lima_home(backend.as_ref()).unwrap();
+        // A live pid that is not a host agent, as after a reboot reused it
+        std::fs::write(
+            lima_home.join("dev").join(HOST_AGENT_PID_FILE),
+            std::process::id().to_string(),
+        )
+        .unwrap();
+        let (app, events) = mock_app_with_fake(backend, &["stop"]);
+        let payloads = Arc::new(Mutex::new(Vec::<serde_json::Value>::new()));
+        {
+            let payloads = payloads.clone();
+            app.listen_any("lima-instance-stop-success", move |event| {
+                payloads
+                    .lock()
+                    .unwrap()
+                    .push(serde_json::from_str(event.payload()).unwrap());
+            });
+        }
+
+        stop_lima_instance(app.handle().clone(), "dev".to_string(), false)
+            .await
+            .unwrap();
+        wait_for(&events, "lima-instance-stop-success").await;
+
+        assert!(!is_host_agent_process(std::process::id() as i32));
+        let payloads = payloads.lock().unwrap();
+        assert_eq!(payloads[0]["host_agent_exited"], false);
+        assert_eq!(payloads[0]["host_agent_alive"], true);
+    }
+
+    #[tokio::test]
+    async fn test_forced_delete_checks_host_agent_pid() {
+        let backend = Arc::new(FakeLimaBackend::new().with_instance("dev", "Running"));
+        let lima_home = crate::lima_backend::LimaBackend::lima_home(backend.as_ref()).unwrap();
+        // A pid that has certainly exited
+        let mut exited = std::process::Command::new("true").spawn().unwrap();
+        let pid = exited.id();
+        exited.wait().unwrap();
+        std::fs::write(
+            lima_home.join("dev").join(HOST_AGENT_PID_FILE),
+            pid.to_string(),
+        )
+        .unwrap();
+
+        let (app, events) = mock_app_with_fake(backend.clone(), &["delete"]);
+        let kube_link = home_dir(&app).join(".kube/dev");
+        std::os::unix::fs::symlink(lima_home.join("dev/kubeconfig.yaml"), &kube_link).unwrap();
+        let payloads = Arc::new(Mutex::new(Vec::<serde_json::Value>::new()));
+        {
+            let payloads = payloads.clone();
+            app.listen_any("lima-instance-delete-success", move |event| {
+                payloads
+                    .lock()
+                    .unwrap()
+                    .push(serde_json::from_str(event.payload()).unwrap());
+            });
+        }
+
+        delete_lima_instance(app.handle().clone(), "dev".to_string(), true)
+            .await
+            .unwrap();
+        wait_for(&events, "lima-instance-delete-success").await;
+
+        assert!(!lifecycle_events(&events).contains(&"lima-instance-delete-escalated".to_string()));
+        let payloads = payloads.lock().unwrap();
+        assert_eq!(payloads[0]["forced"], true);
+        assert_eq!(payloads[0]["host_agent_exited"], true);
+        assert_eq!(backend.status("dev"), None);
+        // Cleaned up in the temp home, not the user's
+        assert!(!kube_link.is_symlink());
+    }
+
+    /// Stopped k0s-like source instance with a 6443 forward, a provision script and env.sh
+    fn backend_with_k0s_source(version: &str) -> Arc<FakeLimaBackend> {
+        let backend = Arc::new(
+            FakeLimaBackend::new()
+                .with_version(version)
+                .with_instance("k0s", "Stopped"),
+        );
+        let source_dir = backend.lima_home().unwrap().join("k0s");
+        let yaml = r#"
+provision:
+- mode: system
+  script: 'sed -i "s/current-context: [Dd]efault/current-context: k0s/g" f'
+portForwards:
+- guestPort: 6443
+  hostPort: 6443
+copyToHost:
+- guest: /var/lib/k0s/pki/external-admin.conf
+  host: "{{.Dir}}/kubeconfig.yaml"
+"#;
+        std::fs::write(source_dir.join("lima.yaml"), yaml).unwrap();
+        std::fs::write(source_dir.join("env.sh"), "# k0s").unwrap();
+        std::fs::write(source_dir.join("kubeconfig.yaml"), "current-context: k0s").unwrap();
+        std::fs::write(source_dir.join("ha.pid"), "1").unwrap();
+        std::fs::write(source_dir.join("diffdisk"), "disk").unwrap();
+        backend
+    }
+
+    #[tokio::test]
+    async fn test_clone_retargets_copy() {
+        let backend = backend_with_k0s_source("2.0.0");
+        let lima_home = backend.lima_home().unwrap();
+        let (app, events) = mock_app_with_fake(backend.clone(), &["clone"]);
+
+        clone_lima_instance(
+            app.handle().clone(),
+            "k0s".to_string(),
+            "k0s-copy".to_string(),
+        )
+        .await
+        .unwrap();
+        wait_for(&events, "lima-instance-clone-success").await;
+
+        assert_eq!(backend.status("k0s-copy").as_deref(), Some("Stopped"));
+        let target_dir = lima_home.join("k0s-copy");
+        let config =
+            LimaConfig::from_yaml(&std::fs::read_to_string(target_dir.join("lima.yaml")).unwrap())
+                .unwrap();
+        assert_ne!(config.port_forwards.unwrap()[0].host_port, Some(6443));
+        assert!(config.provision.unwrap()[0]
+            .script
+            .contains("current-context: k0s-copy/g"));
+        assert!(!target_dir.join("kubeconfig.yaml").exists());
+        let env_sh = std::fs::read_to_string(target_dir.join("env.sh")).unwrap();
+        assert!(env_sh.contains("k0s-copy/docker.sock"));
+    }
+
+    #[tokio::test]
+    async fn test_clone_copies_dir_without_limactl_clone() {
+        let backend = backend_with_k0s_source("1.0.0");
+        let lima_home = backend.lima_home().unwrap();
+        let (app, events) = mock_app_with_fake(backend.clone(), &["clone"]);
+
+        clone_lima_instance(
+            app.handle().clone(),
+            "k0s".to_string(),
+            "k0s-copy".to_string(),
+        )
+        .await
+        .unwrap();
+        wait_for(&events, "lima-instance-clone-success").await;
+
+        let target_dir = lima_home.join("k0s-copy");
+        assert!(target_dir.join("diffdisk").exists());
+        assert!(!target_dir.join("ha.pid").exists());
+        // Not created through the backend
+        assert_eq!(backend.status("k0s-copy"), None);
+    }
+
+    #[test]
+    fn test_copy_file_keeps_zero_chunks_and_stops_on_cancel() {
+        let dir = std::env::temp_dir().join(format!("0ma-copy-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut content = vec![1u8; COPY_CHUNK_SIZE];
+        content.extend(vec![0u8; COPY_CHUNK_SIZE + 3]);
+        content.extend(b"tail");
+        content.extend([0u8; 5]);
+        std::fs::write(dir.join("diffdisk"), &content).unwrap();
+
+        let not_cancelled = AtomicBool::new(false);
+        copy_file(&dir.join("diffdisk"), &dir.join("copy"), &not_cancelled).unwrap();
+        assert_eq!(std::fs::read(dir.join("copy")).unwrap(), content);
+
+        let cancelled = AtomicBool::new(true);
+        let err = copy_file(&dir.join("diffdisk"), &dir.join("cancelled"), &cancelled).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::Interrupted);
+        assert_eq!(std::fs::metadata(dir.join("cancelled")).unwrap().len(), 0);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_clone_requires_stopped_source() {
+        let backend = FakeLimaBackend::new().with_instance("dev", "Running");
+        let (app, events) = mock_app_with_fake(backend, &["clone"]);
+
+        let err = clone_lima_instance(app.handle().clone(), "dev".to_string(), "dev2".to_string())
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), "instanceNotStopped");
+        assert!(events.lock().unwrap().is_empty());
+        // Both locks are released again
+        assert!(list_operations(app.handle()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rename_moves_instance_and_env() {
+        let backend = backend_with_k0s_source("2.0.0");
+        let lima_home = backend.lima_home().unwrap();
+        let (app, events) = mock_app_with_fake(backend, &["rename"]);
+        let home = home_dir(&app);
+        let zshrc = home.join(".zshrc");
+        std::fs::write(
+            &zshrc,
+            format!(
+                "export EDITOR=vi\n# 0ma environment for instance k0s\n[ -f \"{env}\" ] && source \"{env}\"\n",
+                env = lima_home.join("k0s/env.sh").display()
+            ),
+        )
+        .unwrap();
+        std::os::unix::fs::symlink(
+            lima_home.join("k0s/kubeconfig.yaml"),
+            home.join(".kube/k0s"),
+        )
+        .unwrap();
+        let disk_lock = lima_home.join("_disks/data/in_use_by");
+        std::fs::create_dir_all(disk_lock.parent().unwrap()).unwrap();
+        std::os::unix::fs::symlink(lima_home.join("k0s"), &disk_lock).unwrap();
+
+        rename_lima_instance(
+            app.handle().clone(),
+            "k0s".to_string(),
+            "cluster".to_string(),
+        )
+        .await
+        .unwrap();
+
+        let profile = std::fs::read_to_string(&zshrc).unwrap();
+        assert!(profile.starts_with("export EDITOR=vi\n# 0ma environment for instance cluster\n"));
+        assert!(profile.contains(&lima_home.join("cluster/env.sh").display().to_string()));
+        assert!(!home.join(".kube/k0s").exists());
+        assert_eq!(
+            std::fs::read_link(home.join(".kube/cluster")).unwrap(),
+            lima_home.join("cluster/kubeconfig.yaml")
+        );
+        assert_eq!(
+            std::fs::read_link(&disk_lock).unwrap(),
+            lima_home.join("cluster")
+        );
+        assert!(!lima_home.join("k0s").exists());
+        let new_dir = lima_home.join("cluster");
+        assert!(new_dir.join("diffdisk").exists());
+        let yaml = std::fs::read_to_string(new_dir.join("lima.yaml")).unwrap();
+        assert!(yaml.contains("current-context: cluster/g"));
+        let env_sh = std::fs::read_to_string(new_dir.join("env.sh")).unwrap();
+        assert!(env_sh.contains("cluster/kubeconfig.yaml"));
+        assert!(!new_dir.join("kubeconfig.yaml").exists());
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec!["lima-instance-rename-success".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rename_rolls_back_on_failure() {
+        let backend = backend_with_k0s_source("2.0.0");
+        let lima_home = backend.lima_home().unwrap();
+        let old_dir = lima_home.join("k0s");
+        let original_yaml = std::fs::read_to_string(old_dir.join("lima.yaml")).unwrap();
+        // env.sh cannot be regenerated over a directory
+        std::fs::remove_file(old_dir.join("env.sh")).unwrap();
+        std::fs::create_dir(old_dir.join("env.sh")).unwrap();
+        let (app, events) = mock_app_with_fake(backend, &["rename"]);
+
+        let err = rename_lima_instance(
+            app.handle().clone(),
+            "k0s".to_string(),
+            "cluster".to_string(),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(err.kind(), "io");
+        assert!(!lima_home.join("cluster").exists());
+        assert_eq!(
+            std::fs::read_to_string(old_dir.join("lima.yaml")).unwrap(),
+            original_yaml
+        );
+        assert!(events.lock().unwrap().is_empty());
+        assert!(list_operations(app.handle()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rename_rolls_back_shell_profiles() {
+        let backend = backend_with_k0s_source("2.0.0");
+        let lima_home = backend.lima_home().unwrap();
+        let (app, _events) = mock_app_with_fake(backend, &["rename"]);
+        let home = home_dir(&app);
+        let bashrc = home.join(".bashrc");
+        let profile = format!(
+            "# 0ma environment for instance k0s\n[ -f \"{env}\" ] && source \"{env}\"\n",
+            env = lima_home.join("k0s/env.sh").display()
+        );
+        std::fs::write(&bashrc, &profile).unwrap();
+        std::os::unix::fs::symlink(
+            lima_home.join("k0s/kubeconfig.yaml"),
+            home.join(".kube/k0s"),
+        )
+        .unwrap();
+        let disk_lock = lima_home.join("_disks/data/in_use_by");
+        std::fs::create_dir_all(disk_lock.parent().unwrap()).unwrap();
+        std::os::unix::fs::symlink(lima_home.join("k0s"), &disk_lock).unwrap();
+        // The new kubeconfig link cannot be created, after the profiles were rewritten
+        std::fs::write(home.join(".kube/cluster"), "").unwrap();
+
+        let err = rename_lima_instance(
+            app.handle().clone(),
+            "k0s".to_string(),
+            "cluster".to_string(),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(err.kind(), "io");
+        assert_eq!(std::fs::read_to_string(&bashrc).unwrap(), profile);
+        assert!(home.join(".kube/k0s").is_symlink());
+        assert_eq!(
+            std::fs::read_link(&disk_lock).unwrap(),
+            lima_home.join("k0s")
+        );
+        assert!(lima_home.join("k0s/env.sh").exists());
+        assert!(!lima_home.join("cluster").exists());
+    }
 }
@@ -0,0 +1,491 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// One logrus line as printed by limactl, e.g.
+/// `time="2025-01-01T00:00:00Z" level=info msg="SSH Local Port: 60022"`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogRecord {
+    pub time: Option<String>,
+    pub level: String,
+    pub msg: String,
+    /// Any other `key=value` pairs, e.g. `location` of a download
+    pub fields: BTreeMap<String, String>,
+}
+
+/// Parse a logrus text line. Returns `None` for lines without `level` and `msg`
+/// (progress bars, plain output of provision scripts, ...).
+pub fn parse_log_line(line: &str) -> Option<LogRecord> {
+    let mut fields = BTreeMap::new();
+    let mut rest = line.trim();
+    while !rest.is_empty() {
+        let (key, after_key) = rest.split_once('=')?;
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return None;
+        }
+        let (value, after_value) = parse_value(after_key)?;
+        fields.insert(key.to_string(), value);
+        rest = after_value.trim_start();
+    }
+
+    let level = fields.remove("level")?;
+    let msg = fields.remove("msg")?;
+    Some(LogRecord {
+        time: fields.remove("time"),
+        level,
+        msg,
+        fields,
+    })
+}
+
+/// Parse a bare or double-quoted logfmt value, returning it and the remaining input
+fn parse_value(input: &str) -> Option<(String, &str)> {
+    let Some(quoted) = input.strip_prefix('"') else {
+        let end = input.find(char::is_whitespace).unwrap_or(input.len());
+        return Some((input[..end].to_string(), &input[end..]));
+    };
+
+    let mut value = String::new();
+    let mut chars = quoted.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Some((value, &quoted[i + 1..])),
+            '\\' => match chars.next()?.1 {
+                'n' => value.push('\n'),
+                't' => value.push('\t'),
+                other => value.push(other),
+            },
+            c => value.push(c),
+        }
+    }
+    // Unterminated quote
+    None
+}
+
+/// A named step of creating or booting an instance
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "phase", rename_all = "snake_case")]
+pub enum BootPhase {
+    /// Downloading the VM image, with percent when limactl reports it
+    ImageDownload { percent: Option<f64> },
+    /// The VM driver is booting the guest
+    VmBoot,
+    /// Waiting for SSH to the guest
+    Ssh,
+    /// A provision script from the config; `index` is its position in `provision`
+    Provision { mode: String, index: u32 },
+    /// A readiness probe from the config (an optional requirement)
+    Probe {
+        index: u32,
+        total: u32,
+        description: String,
+    },
+    /// Any other requirement limactl waits for, e.g. the guest agent
+    Requirement {
+        kind: String,
+        index: u32,
+        total: u32,
+        description: String,
+    },
+    /// A port or socket forwarded from the guest to the host
+    PortForward { guest: String, host: String },
+    /// limactl reported the instance as ready
+    Ready,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PhaseStatus {
+    Started,
+    Progress,
+    Completed,
+    Failed,
+}
+
+/// A phase transition derived from limactl output
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BootProgress {
+    #[serde(flatten)]
+    pub phase: BootPhase,
+    pub status: PhaseStatus,
+    /// logrus level of the line, `None` for plain output
+    pub level: Option<String>,
+    pub message: String,
+}
+
+/// Turns the stdout/stderr lines of `limactl create/start` into phase transitions.
+/// Stateful, since limactl refers back to requirements by number only.
+#[derive(Debug, Default)]
+pub struct BootProgressTracker {
+    /// (requirement kind, index) -> description, e.g. ("essential", 1) -> "ssh"
+    requirements: HashMap<(String, u32), String>,
+    downloading: bool,
+    booting: bool,
+    /// The most recently started phase, reported as failed on errors
+    current: Option<BootPhase>,
+}
+
+impl BootProgressTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed one output line; returns the phase transitions it implies
+    pub fn feed(&mut self, line: &str) -> Vec<BootProgress> {
+        let mut events = Vec::new();
+        let record = parse_log_line(line);
+        let level = record.as_ref().map(|r| r.level.clone());
+        let message = record
+            .as_ref()
+            .map(|r| r.msg.clone())
+            .unwrap_or_else(|| line.to_string());
+        let msg = message.strip_prefix("[hostagent] ").unwrap_or(&message);
+
+        let mut push = |phase: BootPhase, status: PhaseStatus| {
+            events.push(BootProgress {
+                phase,
+                status,
+                level: level.clone(),
+                message: message.clone(),
+            });
+        };
+
+        if matches!(level.as_deref(), Some("error" | "fatal" | "panic")) {
+            if let Some(phase) = self.current.take() {
+                push(phase, PhaseStatus::Failed);
+            }
+            return events;
+        }
+
+        if msg.starts_with("Attempting to download the image")
+            || msg.starts_with("Downloading the image")
+        {
+            self.downloading = true;
+            self.start(BootPhase::ImageDownload { percent: None }, &mut push);
+        } else if self.downloading
+            && (msg.starts_with("Using cache") || msg.starts_with("Downloaded the image"))
+        {
+            self.downloading = false;
+            push(
+                BootPhase::ImageDownload {
+                    percent: Some(100.0),
+                },
+                PhaseStatus::Completed,
+            );
+        } else if msg.starts_with("Starting the instance")
+            || (msg.starts_with("Starting ") && msg.contains("(hint"))
+        {
+            if std::mem::take(&mut self.downloading) {
+                push(
+                    BootPhase::ImageDownload {
+                        percent: Some(100.0),
+                    },
+                    PhaseStatus::Completed,
+                );
+            }
+            if !self.booting {
+                self.booting = true;
+                self.start(BootPhase::VmBoot, &mut push);
+            }
+        } else if let Some((kind, index, total, description)) = parse_waiting_requirement(msg) {
+            self.requirements
+                .insert((kind.clone(), index), description.clone());
+            let phase = requirement_phase(&kind, index, total, &description);
+            self.start(phase, &mut push);
+        } else if let Some((kind, index, total)) = parse_satisfied_requirement(msg) {
+            let description = self
+                .requirements
+                .get(&(kind.clone(), index))
+                .cloned()
+                .unwrap_or_default();
+            let phase = requirement_phase(&kind, index, total, &description);
+            if phase == BootPhase::Ssh && std::mem::take(&mut self.booting) {
+                // SSH answering means the guest has booted
+                push(BootPhase::VmBoot, PhaseStatus::Completed);
+            }
+            if self.current.as_ref() == Some(&phase) {
+                self.current = None;
+            }
+            push(phase, PhaseStatus::Completed);
+        } else if let Some((guest, host)) = parse_forward(msg) {
+            push(
+                BootPhase::PortForward { guest, host },
+                PhaseStatus::Completed,
+            );
+        } else if msg.starts_with("READY.") {
+            self.current = None;
+            push(BootPhase::Ready, PhaseStatus::Completed);
+        } else if let Some((mode, index)) = parse_provision_script(msg) {
+            self.start(BootPhase::Provision { mode, index }, &mut push);
+        } else if record.is_none() && self.downloading {
+            if let Some(percent) = parse_percent(line) {
+                push(
+                    BootPhase::ImageDownload {
+                        percent: Some(percent),
+                    },
+                    PhaseStatus::Progress,
+                );
+            }
+        }
+
+        events
+    }
+
+    fn start(&mut self, phase: BootPhase, push: &mut impl FnMut(BootPhase, PhaseStatus)) {
+        self.current = Some(phase.clone());
+        push(phase, PhaseStatus::Started);
+    }
+}
+
+fn requirement_phase(kind: &str, index: u32, total: u32, description: &str) -> BootPhase {
+    if description == "ssh" {
+        BootPhase::Ssh
+    } else if kind == "optional" && description.starts_with("user probe") {
+        BootPhase::Probe {
+            index,
+            total,
+            description: description.to_string(),
+        }
+    } else {
+        BootPhase::Requirement {
+            kind: kind.to_string(),
+            index,
+            total,
+            description: description.to_string(),
+        }
+    }
+}
+
+/// `Waiting for the essential requirement 1 of 4: "ssh"` -> ("essential", 1, 4, "ssh")
+fn parse_waiting_requirement(msg: &str) -> Option<(String, u32, u32, String)> {
+    let rest = msg.strip_prefix("Waiting for the ")?;
+    let (kind, rest) = rest.split_once(" requirement ")?;
+    let (counts, description) = rest.split_once(": ")?;
+    let (index, total) = parse_counts(counts)?;
+    Some((
+        kind.to_string(),
+        index,
+        total,
+        description.trim_matches('"').to_string(),
+    ))
+}
+
+/// `The essential requirement 1 of 4 is satisfied` -> ("essential", 1, 4)
+fn parse_satisfied_requirement(msg: &str) -> Option<(String, u32, u32)> {
+    let rest = msg.strip_prefix("The ")?;
+    let (kind, rest) = rest.split_once(" requirement ")?;
+    let counts = rest.strip_suffix(" is satisfied")?;
+    let (index, total) = parse_counts(counts)?;
+    Some((kind.to_string(), index, total))
+}
+
+/// "1 of 4" -> (1, 4)
+fn parse_counts(counts: &str) -> Option<(u32, u32)> {
+    let (index, total) = counts.split_once(" of ")?;
+    Some((index.trim().parse().ok()?, total.trim().parse().ok()?))
+}
+
+/// `Forwarding TCP from 127.0.0.1:6443 to 127.0.0.1:6443` or
+/// `Forwarding "/run/docker.sock" (guest) to "/Users/me/.lima/docker/sock/docker.sock" (host)`
+fn parse_forward(msg: &str) -> Option<(String, String)> {
+    let rest = msg.strip_prefix("Forwarding ")?;
+    if let Some(rest) = rest
+        .strip_prefix("TCP from ")
+        .or_else(|| rest.strip_prefix("UDP from "))
+    {
+        let (guest, host) = rest.split_once(" to ")?;
+        return Some((guest.trim().to_string(), host.trim().to_string()));
+    }
+    let parts: Vec<&str> = rest.split('"').collect();
+    match parts.as_slice() {
+        [_, guest, _, host, ..] => Some((guest.to_string(), host.to_string())),
+        _ => None,
+    }
+}
+
+/// Lima runs provision scripts from `<cidata>/provision.<mode>/<index>`, e.g.
+/// `LIMA| Executing /mnt/lima-cidata/provision.system/00000001`
+fn parse_provision_script(line: &str) -> Option<(String, u32)> {
+    let start = line.find("provision.")?;
+    let rest = &line[start + "provision.".len()..];
+    let (mode, rest) = rest.split_once('/')?;
+    let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+    if mode.is_empty() || !mode.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return None;
+    }
+    Some((mode.to_string(), digits.parse().ok()?))
+}
+
+/// First `NN%` or `NN.NN%` in a progress bar line
+fn parse_percent(line: &str) -> Option<f64> {
+    line.match_indices('%').find_map(|(i, _)| {
+        let number: String = line[..i]
+            .chars()
+            .rev()
+            .take_while(|c| c.is_ascii_digit() || *c == '.')
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .collect();
+        number
+            .parse::<f64>()
+            .ok()
+            .filter(|p| (0.0..=100.0).contains(p))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(level: &str, msg: &str) -> String {
+        format!(
+            "time=\"2025-01-01T00:00:00Z\" level={} msg=\"{}\"",
+            level,
+            msg.replace('"', "\\\"")
+        )
+    }
+
+    #[test]
+    fn test_parse_log_line() {
+        let record = parse_log_line(
+            r#"time="2025-01-01T00:00:00Z" level=info msg="Attempting to download the image" arch=aarch64 digest= location="https://example.com/img \"x\".qcow2""#,
+        )
+        .unwrap();
+        assert_eq!(record.time.as_deref(), Some("2025-01-01T00:00:00Z"));
+        assert_eq!(record.level, "info");
+        assert_eq!(record.msg, "Attempting to download the image");
+        assert_eq!(record.fields["arch"], "aarch64");
+        assert_eq!(record.fields["digest"], "");
+        assert_eq!(
+            record.fields["location"],
+            "https://example.com/img \"x\".qcow2"
+        );
+
+        assert_eq!(parse_log_line("1.23 GiB / 2.00 GiB [===>---] 61.50%"), None);
+        assert_eq!(parse_log_line("LIMA| Executing provision.system/0"), None);
+        assert_eq!(parse_log_line(r#"level=info msg="unterminated"#), None);
+    }
+
+    #[test]
+    fn test_tracker_maps_boot_to_phases() {
+        let mut tracker = BootProgressTracker::new();
+        let lines = [
+            log("info", "Attempting to download the image"),
+            "512.00 MiB / 1.00 GiB [=====>-----] 50.00% 20.00 MiB/s".to_string(),
+            log(
+                "info",
+                "Starting the instance \"dev\" with VM driver \"vz\"",
+            ),
+            log(
+                "info",
+                "[hostagent] Waiting for the essential requirement 1 of 2: \"ssh\"",
+            ),
+            log(
+                "info",
+                "[hostagent] The essential requirement 1 of 2 is satisfied",
+            ),
+            log(
+                "info",
+                "[hostagent] Forwarding TCP from 127.0.0.1:6443 to 127.0.0.1:6443",
+            ),
+            "LIMA| Executing /mnt/lima-cidata/provision.system/00000001".to_string(),
+            log(
+                "info",
+                "[hostagent] Waiting for the optional requirement 1 of 1: \"user probe 1/1\"",
+            ),
+            log(
+                "info",
+                "[hostagent] The optional requirement 1 of 1 is satisfied",
+            ),
+            log("info", "READY. Run `limactl shell dev` to open the shell."),
+        ];
+        let events: Vec<(BootPhase, PhaseStatus)> = lines
+            .iter()
+            .flat_map(|line| tracker.feed(line))
+            .map(|e| (e.phase, e.status))
+            .collect();
+
+        let probe = BootPhase::Probe {
+            index: 1,
+            total: 1,
+            description: "user probe 1/1".to_string(),
+        };
+        assert_eq!(
+            events,
+            vec![
+                (
+                    BootPhase::ImageDownload { percent: None },
+                    PhaseStatus::Started
+                ),
+                (
+                    BootPhase::ImageDownload {
+                        percent: Some(50.0)
+                    },
+                    PhaseStatus::Progress
+                ),
+                (
+                    BootPhase::ImageDownload {
+                        percent: Some(100.0)
+                    },
+                    PhaseStatus::Completed
+                ),
+                (BootPhase::VmBoot, PhaseStatus::Started),
+                (BootPhase::Ssh, PhaseStatus::Started),
+                (BootPhase::VmBoot, PhaseStatus::Completed),
+                (BootPhase::Ssh, PhaseStatus::Completed),
+                (
+                    BootPhase::PortForward {
+                        guest: "127.0.0.1:6443".to_string(),
+                        host: "127.0.0.1:6443".to_string(),
+                    },
+                    PhaseStatus::Completed
+                ),
+                (
+                    BootPhase::Provision {
+                        mode: "system".to_string(),
+                        index: 1,
+                    },
+                    PhaseStatus::Started
+                ),
+                (probe.clone(), PhaseStatus::Started),
+                (probe, PhaseStatus::Completed),
+                (BootPhase::Ready, PhaseStatus::Completed),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tracker_reports_failure_of_current_phase() {
+        let mut tracker = BootProgressTracker::new();
+        tracker.feed(&log(
+            "info",
+            "[hostagent] Waiting for the essential requirement 2 of 2: \"user session is ready for ssh\"",
+        ));
+        let events = tracker.feed(&log(
+            "fatal",
+            "did not receive an event with the \"running\" status",
+        ));
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].status, PhaseStatus::Failed);
+        assert_eq!(events[0].level.as_deref(), Some("fatal"));
+        assert!(matches!(
+            &events[0].phase,
+            BootPhase::Requirement { kind, index: 2, .. } if kind == "essential"
+        ));
+    }
+
+    #[test]
+    fn test_parse_forward_socket() {
+        assert_eq!(
+            parse_forward(
+                r#"Forwarding "/run/docker.sock" (guest) to "/Users/me/.lima/docker/sock/docker.sock" (host)"#
+            ),
+            Some((
+                "/run/docker.sock".to_string(),
+                "/Users/me/.lima/docker/sock/docker.sock".to_string()
+            ))
+        );
+        assert_eq!(parse_forward("Not forwarding TCP 0.0.0.0:22"), None);
+    }
+}
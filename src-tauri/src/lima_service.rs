@@ -1,74 +1,152 @@
+use crate::error::AppError;
+use crate::lima_backend::LimaBackend;
+use crate::lima_info_service::{self, HostLimaInfo};
+use crate::settings_service::{self, LimaPathSettings};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::{Arc, RwLock};
+use tauri::{AppHandle, Manager, Runtime};
 use tokio::process::Command as AsyncCommand;
 
 /// System capabilities relevant to VM type selection
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemCapabilities {
+    /// Host operating system (e.g., "macos", "linux")
+    pub os: String,
     /// CPU architecture (e.g., "aarch64", "x86_64")
     pub arch: String,
-    /// macOS version string (e.g., "14.5")
+    /// macOS version string (e.g., "14.5"), empty on other hosts
     #[serde(rename = "macosVersion")]
     pub macos_version: String,
+    /// OS version for display (macOS product version or Linux distribution name)
+    #[serde(rename = "osVersion")]
+    pub os_version: String,
     /// Whether the krunkit binary is available on the host
     #[serde(rename = "krunkitAvailable")]
     pub krunkit_available: bool,
     /// Whether Lima has the krunkit driver installed
     #[serde(rename = "krunkitDriverAvailable")]
     pub krunkit_driver_available: bool,
+    /// Whether /dev/kvm exists and the current user can open it (Linux only)
+    #[serde(rename = "kvmAvailable")]
+    pub kvm_available: bool,
+    /// Whether the qemu-system binary for the host architecture is installed
+    #[serde(rename = "qemuAvailable")]
+    pub qemu_available: bool,
+    /// VM type used by the default templates on this host
+    #[serde(rename = "defaultVmType")]
+    pub default_vm_type: String,
+    /// VM types that can be selected on this host
+    #[serde(rename = "supportedVmTypes")]
+    pub supported_vm_types: Vec<String>,
 }
 
-pub fn get_system_capabilities() -> SystemCapabilities {
+/// Detect host capabilities. `host_info` is the parsed `limactl info`, if Lima is installed.
+pub fn get_system_capabilities(host_info: Option<&HostLimaInfo>) -> SystemCapabilities {
+    let os = std::env::consts::OS.to_string();
     let arch = std::env::consts::ARCH.to_string();
+    let is_macos = os == "macos";
 
-    let macos_version = Command::new("sw_vers")
-        .arg("-productVersion")
-        .output()
-        .ok()
-        .and_then(|o| {
-            if o.status.success() {
-                String::from_utf8(o.stdout)
-                    .ok()
-                    .map(|s| s.trim().to_string())
-            } else {
-                None
-            }
-        })
-        .unwrap_or_default();
+    let macos_version = if is_macos {
+        Command::new("sw_vers")
+            .arg("-productVersion")
+            .output()
+            .ok()
+            .and_then(|o| {
+                if o.status.success() {
+                    String::from_utf8(o.stdout)
+                        .ok()
+                        .map(|s| s.trim().to_string())
+                } else {
+                    None
+                }
+            })
+            .unwrap_or_default()
+    } else {
+        String::new()
+    };
 
-    let krunkit_available = Command::new("which")
-        .arg("krunkit")
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false);
+    let os_version = if is_macos {
+        macos_version.clone()
+    } else {
+        std::fs::read_to_string("/etc/os-release")
+            .ok()
+            .and_then(|content| parse_os_release_name(&content))
+            .unwrap_or_default()
+    };
+
+    let krunkit_available = command_exists("krunkit");
 
     // Check if Lima has the krunkit driver via `limactl info`
-    let krunkit_driver_available = find_lima_executable()
-        .and_then(|lima_cmd| {
-            Command::new(&lima_cmd)
-                .arg("info")
-                .output()
-                .ok()
-                .and_then(|o| {
-                    if o.status.success() {
-                        String::from_utf8(o.stdout).ok()
-                    } else {
-                        None
-                    }
-                })
-        })
-        .map(|info| info.contains("krunkit"))
-        .unwrap_or(false);
+    let krunkit_driver_available = host_info.is_some_and(|info| info.supports_vm_type("krunkit"));
+
+    let kvm_available = os == "linux" && kvm_accessible(Path::new("/dev/kvm"));
+    let qemu_available = command_exists(&qemu_binary_name(&arch));
+
+    // vz and krunkit are macOS-only drivers
+    let supported_vm_types = if is_macos {
+        vec!["vz".to_string(), "qemu".to_string(), "krunkit".to_string()]
+    } else {
+        vec!["qemu".to_string()]
+    };
 
     SystemCapabilities {
+        os,
         arch,
         macos_version,
+        os_version,
         krunkit_available,
         krunkit_driver_available,
+        kvm_available,
+        qemu_available,
+        default_vm_type: crate::lima_config::host_default_vm_type().to_string(),
+        supported_vm_types,
     }
 }
 
+/// Whether `name` resolves to an executable on PATH
+fn command_exists(name: &str) -> bool {
+    Command::new("which")
+        .arg(name)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// qemu system emulator for a Rust architecture name, e.g. "qemu-system-aarch64"
+fn qemu_binary_name(arch: &str) -> String {
+    format!("qemu-system-{}", arch)
+}
+
+/// Whether the KVM device can be opened read-write by the current user.
+/// Existence alone is not enough: users outside the `kvm` group get EACCES.
+fn kvm_accessible(device: &Path) -> bool {
+    std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(device)
+        .is_ok()
+}
+
+/// Distribution name from /etc/os-release (PRETTY_NAME, falling back to NAME VERSION_ID)
+fn parse_os_release_name(content: &str) -> Option<String> {
+    let value = |key: &str| {
+        content.lines().find_map(|line| {
+            line.strip_prefix(key)?
+                .strip_prefix('=')
+                .map(|v| v.trim().trim_matches('"').to_string())
+        })
+    };
+    value("PRETTY_NAME").or_else(|| {
+        let name = value("NAME")?;
+        Some(match value("VERSION_ID") {
+            Some(version) => format!("{} {}", name, version),
+            None => name,
+        })
+    })
+}
+
 const COMMON_BREW_PATHS: &[&str] = &["/opt/homebrew/bin/brew", "/usr/local/bin/brew", "brew"];
 
 /// Find the Homebrew executable in common installation paths
@@ -94,8 +172,13 @@ pub const COMMON_LIMA_EXEC_PATHS: &[&str] = &[
     "limactl",
 ];
 
-/// Find the lima executable in common installation paths
+/// Find the lima executable in the app-managed prefix or common installation paths
 pub fn find_lima_executable() -> Option<String> {
+    // Installed by the app from a release archive
+    if let Some(limactl) = crate::lima_install_service::managed_limactl() {
+        return Some(limactl.to_string_lossy().to_string());
+    }
+
     for path in COMMON_LIMA_EXEC_PATHS {
         if *path == "limactl" {
             // Try using PATH
@@ -110,56 +193,160 @@ pub fn find_lima_executable() -> Option<String> {
     None
 }
 
-pub fn get_lima_version() -> Result<String, String> {
-    // Find limactl executable
-    let lima_cmd = find_lima_executable()
-        .ok_or_else(|| "Lima (limactl) not found. Please ensure lima is installed in /usr/local/bin, /opt/homebrew/bin, or is in your PATH.".to_string())?;
+/// Lima locations resolved from user settings, falling back to auto-detection.
+/// Resolved once and cached in `LimaPathsState` until settings change.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LimaPaths {
+    /// limactl executable, `None` if Lima is not installed
+    pub limactl: Option<String>,
+    /// LIMA_HOME, `None` means Lima's default (~/.lima)
+    pub lima_home: Option<PathBuf>,
+    /// brew executable, `None` if Homebrew is not installed
+    pub brew: Option<String>,
+}
 
-    // Try to execute 'limactl --version' to get the version string
-    match Command::new(&lima_cmd).arg("--version").output() {
-        Ok(output) => {
-            if output.status.success() {
-                // Convert stdout to string and trim whitespace
-                String::from_utf8(output.stdout)
-                    .map(|s| {
-                        let trimmed = s.trim();
-                        // Extract version number from "limactl version X.Y.Z"
-                        trimmed
-                            .split_whitespace()
-                            .last()
-                            .unwrap_or(trimmed)
-                            .to_string()
-                    })
-                    .map_err(|e| format!("Failed to parse limactl version output: {}", e))
-            } else {
-                // If command failed, return stderr as error
-                let error_msg = String::from_utf8_lossy(&output.stderr).trim().to_string();
-                Err(if error_msg.is_empty() {
-                    "limactl --version command failed".to_string()
-                } else {
-                    error_msg
-                })
-            }
+/// Managed state holding the cached `LimaPaths`, shared with `LimactlBackend`
+#[derive(Clone, Default)]
+pub struct LimaPathsState(pub Arc<RwLock<LimaPaths>>);
+
+/// Resolve Lima locations. Overrides win; otherwise limactl and brew are searched
+/// in common paths, and LIMA_HOME comes from the environment or `limactl info`.
+pub fn resolve_lima_paths(settings: &LimaPathSettings) -> LimaPaths {
+    let limactl = settings.limactl_path.clone().or_else(find_lima_executable);
+    let brew = settings.brew_path.clone().or_else(find_brew_executable);
+
+    let lima_home = settings
+        .lima_home
+        .as_ref()
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var("LIMA_HOME")
+                .ok()
+                .filter(|v| !v.is_empty())
+                .map(PathBuf::from)
+        })
+        .or_else(|| query_lima_home(limactl.as_deref()?));
+
+    LimaPaths {
+        limactl,
+        lima_home,
+        brew,
+    }
+}
+
+/// Ask limactl where its home is (`limactl info` -> limaHome)
+fn query_lima_home(limactl: &str) -> Option<PathBuf> {
+    let output = Command::new(limactl).arg("info").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let info = lima_info_service::parse_lima_info(&String::from_utf8_lossy(&output.stdout)).ok()?;
+    Some(info.lima_home)
+}
+
+/// Cached Lima locations, resolving them on first use
+pub fn lima_paths<R: Runtime>(app: &AppHandle<R>) -> LimaPaths {
+    match app.try_state::<LimaPathsState>() {
+        Some(state) => state.0.read().map(|p| p.clone()).unwrap_or_default(),
+        None => resolve_lima_paths(&settings_service::load_lima_path_settings(app)),
+    }
+}
+
+/// Re-resolve Lima locations from the stored settings and update the cache
+pub fn refresh_lima_paths<R: Runtime>(app: &AppHandle<R>) -> LimaPaths {
+    let paths = resolve_lima_paths(&settings_service::load_lima_path_settings(app));
+    if let Some(state) = app.try_state::<LimaPathsState>() {
+        if let Ok(mut cached) = state.0.write() {
+            *cached = paths.clone();
         }
-        Err(e) => Err(format!("Failed to execute lima command: {}", e)),
+    }
+    // A different limactl or LIMA_HOME reports different info
+    lima_info_service::invalidate_host_lima_info(app);
+    paths
+}
+
+pub async fn get_lima_version(backend: &dyn LimaBackend) -> Result<String, AppError> {
+    // Try to execute 'limactl --version' to get the version string
+    let output = backend.version().await?;
+
+    if output.success() {
+        // Extract version number from "limactl version X.Y.Z"
+        let trimmed = output.stdout.trim();
+        Ok(trimmed
+            .split_whitespace()
+            .last()
+            .unwrap_or(trimmed)
+            .to_string())
+    } else {
+        Err(AppError::command_failed(
+            "limactl --version",
+            output.code,
+            &output.stderr,
+        ))
     }
 }
 
-pub async fn install_lima() -> Result<String, String> {
-    let brew_path = find_brew_executable().ok_or_else(|| {
-        "Homebrew is not installed. Please install Homebrew first: https://brew.sh".to_string()
+pub async fn install_lima(brew_path: Option<String>) -> Result<String, AppError> {
+    let brew_path = brew_path.ok_or_else(|| AppError::Io {
+        message: "Homebrew is not installed. Please install Homebrew first: https://brew.sh"
+            .to_string(),
     })?;
 
     let output = AsyncCommand::new(&brew_path)
         .args(["install", "lima"])
         .output()
         .await
-        .map_err(|e| format!("Failed to run brew install lima: {}", e))?;
+        .map_err(|e| AppError::io("Failed to run brew install lima", e))?;
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-        return Err(format!("Failed to install Lima: {}", stderr));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(AppError::command_failed(
+            "brew install lima",
+            output.status.code(),
+            &stderr,
+        ));
     }
 
     Ok("Lima installed successfully".to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_os_release_name() {
+        let ubuntu = "NAME=\"Ubuntu\"\nVERSION_ID=\"24.04\"\nPRETTY_NAME=\"Ubuntu 24.04.1 LTS\"\n";
+        assert_eq!(
+            parse_os_release_name(ubuntu).as_deref(),
+            Some("Ubuntu 24.04.1 LTS")
+        );
+
+        let minimal = "NAME=Fedora Linux\nVERSION_ID=41\n";
+        assert_eq!(
+            parse_os_release_name(minimal).as_deref(),
+            Some("Fedora Linux 41")
+        );
+
+        assert_eq!(parse_os_release_name(""), None);
+    }
+
+    #[test]
+    fn test_kvm_accessible_requires_openable_device() {
+        assert!(!kvm_accessible(Path::new("/nonexistent/kvm")));
+    }
+
+    #[test]
+    fn test_system_capabilities_for_host() {
+        let caps = get_system_capabilities(None);
+        assert_eq!(caps.os, std::env::consts::OS);
+        assert!(!caps.krunkit_driver_available);
+        assert!(caps.supported_vm_types.contains(&caps.default_vm_type));
+        if cfg!(target_os = "linux") {
+            assert_eq!(caps.default_vm_type, "qemu");
+            assert_eq!(caps.supported_vm_types, vec!["qemu"]);
+            assert!(caps.macos_version.is_empty());
+        }
+    }
+}
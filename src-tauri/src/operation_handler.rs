@@ -0,0 +1,21 @@
+use crate::error::AppError;
+use crate::operation_service::{self, OperationInfo};
+use tauri::AppHandle;
+
+#[tauri::command]
+pub fn cancel_operation_cmd(
+    app: AppHandle,
+    operation_id: String,
+) -> Result<OperationInfo, AppError> {
+    operation_service::cancel_operation(&app, &operation_id)
+}
+
+#[tauri::command]
+pub fn list_operations_cmd(app: AppHandle) -> Vec<OperationInfo> {
+    operation_service::list_operations(&app)
+}
+
+#[tauri::command]
+pub fn get_instance_operation_cmd(app: AppHandle, instance_name: String) -> Option<OperationInfo> {
+    operation_service::current_operation(&app, &instance_name)
+}
@@ -0,0 +1,33 @@
+use crate::error::AppError;
+use crate::operation_log_service::{self, OperationLogEntry, OperationLogSummary};
+use tauri::AppHandle;
+
+/// List persisted operation logs of one instance (or all instances), newest first
+#[tauri::command]
+pub async fn list_operation_logs_cmd(
+    app: AppHandle,
+    instance_name: Option<String>,
+) -> Result<Vec<OperationLogSummary>, AppError> {
+    operation_log_service::list_operation_logs(&app, instance_name.as_deref())
+}
+
+/// Get every entry of an operation's log
+#[tauri::command]
+pub async fn read_operation_log_cmd(
+    app: AppHandle,
+    instance_name: String,
+    operation_id: String,
+) -> Result<Vec<OperationLogEntry>, AppError> {
+    operation_log_service::read_operation_log(&app, &instance_name, &operation_id, None)
+}
+
+/// Get the last `lines` entries of an operation's log
+#[tauri::command]
+pub async fn tail_operation_log_cmd(
+    app: AppHandle,
+    instance_name: String,
+    operation_id: String,
+    lines: usize,
+) -> Result<Vec<OperationLogEntry>, AppError> {
+    operation_log_service::read_operation_log(&app, &instance_name, &operation_id, Some(lines))
+}
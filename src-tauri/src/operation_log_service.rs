@@ -0,0 +1,380 @@
+use crate::error::AppError;
+use crate::operation_service::OperationInfo;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Operation logs kept per instance; the oldest are deleted when a new operation starts
+const MAX_LOGS_PER_INSTANCE: usize = 20;
+
+/// Size after which an operation's log only records lifecycle events, not output lines
+const MAX_LOG_BYTES: u64 = 4 * 1024 * 1024;
+
+const LOG_EXTENSION: &str = "ndjson";
+
+/// Which output a log entry came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+    /// Lifecycle events: start of the operation, phases, progress, success, errors
+    Event,
+}
+
+impl LogStream {
+    /// Stream of an event by its suffix, e.g. `lima-instance-start-stderr`
+    pub fn of_event(event: &str) -> Self {
+        if event.ends_with("-stdout") {
+            LogStream::Stdout
+        } else if event.ends_with("-stderr") {
+            LogStream::Stderr
+        } else {
+            LogStream::Event
+        }
+    }
+}
+
+/// One NDJSON line of an operation log, with the same ids as the emitted event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationLogEntry {
+    pub message_id: String,
+    /// Nanoseconds since the epoch
+    pub timestamp: String,
+    pub stream: LogStream,
+    /// Event the entry was emitted as, e.g. "lima-instance-start-error"
+    pub event: String,
+    pub message: String,
+}
+
+/// A persisted operation log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationLogSummary {
+    pub operation_id: String,
+    pub instance_name: String,
+    /// Operation kind, e.g. "start"
+    pub kind: String,
+    /// Nanoseconds since the epoch
+    pub started_at: String,
+    /// "success", "error" or "cancelled"; `None` while running or if the app quit mid-operation
+    pub outcome: Option<String>,
+    pub size_bytes: u64,
+}
+
+/// Managed state: directory holding `<instance>/<started_at>-<kind>-<operation_id>.ndjson`
+pub struct OperationLogState {
+    dir: PathBuf,
+    /// Serializes appends and rotation
+    lock: Mutex<()>,
+}
+
+impl OperationLogState {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            lock: Mutex::new(()),
+        }
+    }
+
+    fn instance_dir(&self, instance_name: &str) -> Result<PathBuf, AppError> {
+        if instance_name.is_empty() || instance_name.starts_with('.') || instance_name.contains('/')
+        {
+            return Err(AppError::InvalidInstanceName {
+                instance_name: instance_name.to_string(),
+            });
+        }
+        Ok(self.dir.join(instance_name))
+    }
+
+    /// Append `entry` to the operation's log, creating it (and rotating older logs) on
+    /// the first entry. Past `MAX_LOG_BYTES` only `LogStream::Event` entries are kept.
+    pub fn append(
+        &self,
+        operation: &OperationInfo,
+        entry: &OperationLogEntry,
+    ) -> Result<(), AppError> {
+        let _guard = self
+            .lock
+            .lock()
+            .map_err(|e| AppError::io("Failed to lock operation logs", e))?;
+        let dir = self.instance_dir(&operation.instance_name)?;
+        let path = dir.join(format!(
+            "{}-{}-{}.{}",
+            operation.started_at,
+            operation.kind.name(),
+            operation.operation_id,
+            LOG_EXTENSION
+        ));
+
+        let is_new = match std::fs::metadata(&path) {
+            Ok(metadata) => {
+                if metadata.len() >= MAX_LOG_BYTES && entry.stream != LogStream::Event {
+                    return Ok(());
+                }
+                false
+            }
+            Err(_) => {
+                std::fs::create_dir_all(&dir)
+                    .map_err(|e| AppError::io("Failed to create operation log dir", e))?;
+                true
+            }
+        };
+
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|e| AppError::io("Failed to write operation log", e))?;
+
+        if is_new {
+            for (old, _) in log_files(&dir)?
+                .into_iter()
+                .rev()
+                .skip(MAX_LOGS_PER_INSTANCE)
+            {
+                let _ = std::fs::remove_file(old);
+            }
+        }
+        Ok(())
+    }
+
+    /// Logs of one instance, or of all instances, newest first
+    pub fn list(&self, instance_name: Option<&str>) -> Result<Vec<OperationLogSummary>, AppError> {
+        let instance_names = match instance_name {
+            Some(name) => vec![name.to_string()],
+            None => match std::fs::read_dir(&self.dir) {
+                Ok(entries) => entries
+                    .filter_map(|e| e.ok())
+                    .filter(|e| e.path().is_dir())
+                    .map(|e| e.file_name().to_string_lossy().to_string())
+                    .collect(),
+                Err(_) => Vec::new(),
+            },
+        };
+
+        let mut summaries = Vec::new();
+        for instance_name in instance_names {
+            for (path, name) in log_files(&self.instance_dir(&instance_name)?)? {
+                summaries.push(OperationLogSummary {
+                    operation_id: name.operation_id,
+                    instance_name: instance_name.clone(),
+                    kind: name.kind,
+                    started_at: name.started_at.to_string(),
+                    outcome: read_entries(&path)?.last().and_then(outcome_of),
+                    size_bytes: std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+                });
+            }
+        }
+        summaries.sort_by_key(|s| std::cmp::Reverse(s.started_at.parse::<u128>().unwrap_or(0)));
+        Ok(summaries)
+    }
+
+    /// All entries of an operation's log
+    pub fn read(
+        &self,
+        instance_name: &str,
+        operation_id: &str,
+    ) -> Result<Vec<OperationLogEntry>, AppError> {
+        let path = log_files(&self.instance_dir(instance_name)?)?
+            .into_iter()
+            .find(|(_, name)| name.operation_id == operation_id)
+            .map(|(path, _)| path)
+            .ok_or_else(|| AppError::OperationNotFound {
+                operation_id: operation_id.to_string(),
+            })?;
+        read_entries(&path)
+    }
+}
+
+/// Parts of a log file name
+struct LogFileName {
+    started_at: u128,
+    kind: String,
+    operation_id: String,
+}
+
+/// "<started_at>-<kind>-<operation_id>.ndjson"; operation ids are UUIDs and contain '-'
+fn parse_log_file_name(file_name: &str) -> Option<LogFileName> {
+    let stem = file_name.strip_suffix(&format!(".{}", LOG_EXTENSION))?;
+    let (started_at, rest) = stem.split_once('-')?;
+    let (kind, operation_id) = rest.split_once('-')?;
+    Some(LogFileName {
+        started_at: started_at.parse().ok()?,
+        kind: kind.to_string(),
+        operation_id: operation_id.to_string(),
+    })
+}
+
+/// Log files in `dir`, oldest first; a missing dir has none
+fn log_files(dir: &Path) -> Result<Vec<(PathBuf, LogFileName)>, AppError> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(AppError::io("Failed to read operation logs", e)),
+    };
+    let mut files: Vec<(PathBuf, LogFileName)> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let name = parse_log_file_name(&e.file_name().to_string_lossy())?;
+            Some((e.path(), name))
+        })
+        .collect();
+    files.sort_by_key(|(_, name)| name.started_at);
+    Ok(files)
+}
+
+/// Entries of a log file. A line cut short by a crash is skipped.
+fn read_entries(path: &Path) -> Result<Vec<OperationLogEntry>, AppError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| AppError::io("Failed to read operation log", e))?;
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+fn outcome_of(entry: &OperationLogEntry) -> Option<String> {
+    ["success", "error", "cancelled"]
+        .into_iter()
+        .find(|outcome| entry.event.ends_with(&format!("-{}", outcome)))
+        .map(str::to_string)
+}
+
+/// Append an entry to the operation's log. Without `OperationLogState` (e.g. in tests)
+/// nothing is persisted; failures are logged rather than failing the operation.
+pub fn append_operation_log<R: Runtime>(
+    app: &AppHandle<R>,
+    operation: &OperationInfo,
+    entry: &OperationLogEntry,
+) {
+    if let Some(state) = app.try_state::<OperationLogState>() {
+        if let Err(e) = state.append(operation, entry) {
+            log::warn!("Failed to persist operation log: {}", e);
+        }
+    }
+}
+
+/// Persisted operation logs, newest first
+pub fn list_operation_logs<R: Runtime>(
+    app: &AppHandle<R>,
+    instance_name: Option<&str>,
+) -> Result<Vec<OperationLogSummary>, AppError> {
+    match app.try_state::<OperationLogState>() {
+        Some(state) => state.list(instance_name),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// The last `lines` entries of an operation's log, or all of them
+pub fn read_operation_log<R: Runtime>(
+    app: &AppHandle<R>,
+    instance_name: &str,
+    operation_id: &str,
+    lines: Option<usize>,
+) -> Result<Vec<OperationLogEntry>, AppError> {
+    let mut entries = app
+        .try_state::<OperationLogState>()
+        .ok_or_else(|| AppError::OperationNotFound {
+            operation_id: operation_id.to_string(),
+        })?
+        .read(instance_name, operation_id)?;
+    if let Some(lines) = lines {
+        entries.drain(..entries.len().saturating_sub(lines));
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operation_service::OperationKind;
+
+    fn operation(instance_name: &str, started_at: u128, kind: OperationKind) -> OperationInfo {
+        OperationInfo {
+            operation_id: uuid::Uuid::new_v4().to_string(),
+            instance_name: instance_name.to_string(),
+            kind,
+            started_at: started_at.to_string(),
+        }
+    }
+
+    fn entry(event: &str, message: &str) -> OperationLogEntry {
+        OperationLogEntry {
+            message_id: uuid::Uuid::new_v4().to_string(),
+            timestamp: "1".to_string(),
+            stream: LogStream::of_event(event),
+            event: event.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_append_list_and_read() {
+        let dir = std::env::temp_dir().join(format!("0ma-oplogs-{}", uuid::Uuid::new_v4()));
+        let state = OperationLogState::new(dir.clone());
+        let start = operation("dev", 100, OperationKind::Start);
+        let stop = operation("dev", 200, OperationKind::Stop);
+
+        state
+            .append(&start, &entry("lima-instance-start", "Starting"))
+            .unwrap();
+        state
+            .append(&start, &entry("lima-instance-start-stderr", "boot failed"))
+            .unwrap();
+        state
+            .append(&start, &entry("lima-instance-start-error", "boot failed"))
+            .unwrap();
+        state
+            .append(&stop, &entry("lima-instance-stop", "Stopping"))
+            .unwrap();
+
+        let summaries = state.list(Some("dev")).unwrap();
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].operation_id, stop.operation_id);
+        assert_eq!(summaries[0].outcome, None);
+        assert_eq!(summaries[1].kind, "start");
+        assert_eq!(summaries[1].outcome.as_deref(), Some("error"));
+        assert_eq!(state.list(None).unwrap(), summaries);
+
+        let entries = state.read("dev", &start.operation_id).unwrap();
+        let streams: Vec<LogStream> = entries.iter().map(|e| e.stream).collect();
+        assert_eq!(
+            streams,
+            [LogStream::Event, LogStream::Stderr, LogStream::Event]
+        );
+        assert_eq!(
+            state.read("dev", "missing").unwrap_err().kind(),
+            "operationNotFound"
+        );
+        assert!(state.list(Some("../etc")).is_err());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_rotation_keeps_newest_logs() {
+        let dir = std::env::temp_dir().join(format!("0ma-oplogs-{}", uuid::Uuid::new_v4()));
+        let state = OperationLogState::new(dir.clone());
+        let operations: Vec<OperationInfo> = (0..MAX_LOGS_PER_INSTANCE as u128 + 3)
+            .map(|i| operation("dev", 1000 + i, OperationKind::Start))
+            .collect();
+        for op in &operations {
+            state
+                .append(op, &entry("lima-instance-start", "Starting"))
+                .unwrap();
+        }
+
+        let summaries = state.list(Some("dev")).unwrap();
+        assert_eq!(summaries.len(), MAX_LOGS_PER_INSTANCE);
+        assert_eq!(
+            summaries.last().unwrap().operation_id,
+            operations[3].operation_id
+        );
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,468 @@
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, EventId, Listener, Manager, Runtime};
+use tokio::sync::{mpsc, oneshot};
+
+/// Lifecycle operation that runs a long-lived limactl process
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OperationKind {
+    Create,
+    Start,
+    Stop,
+    Delete,
+    Restart,
+    Clone,
+    Rename,
+    Snapshot,
+    Recreate,
+}
+
+impl OperationKind {
+    /// Short name, e.g. "start"; the limactl subcommand for single-step operations
+    pub fn name(self) -> &'static str {
+        match self {
+            OperationKind::Create => "create",
+            OperationKind::Start => "start",
+            OperationKind::Stop => "stop",
+            OperationKind::Delete => "delete",
+            OperationKind::Restart => "restart",
+            OperationKind::Clone => "clone",
+            OperationKind::Rename => "rename",
+            OperationKind::Snapshot => "snapshot",
+            OperationKind::Recreate => "recreate",
+        }
+    }
+
+    /// Present participle for status labels, e.g. "Starting"
+    pub fn progress_label(self) -> &'static str {
+        match self {
+            OperationKind::Create => "Creating",
+            OperationKind::Start => "Starting",
+            OperationKind::Stop => "Stopping",
+            OperationKind::Delete => "Deleting",
+            OperationKind::Restart => "Restarting",
+            OperationKind::Clone => "Cloning",
+            OperationKind::Rename => "Renaming",
+            OperationKind::Snapshot => "Snapshotting",
+            OperationKind::Recreate => "Recreating",
+        }
+    }
+
+    /// Prefix of the events emitted for this operation, e.g. "lima-instance-start"
+    pub fn event(self) -> &'static str {
+        match self {
+            OperationKind::Create => "lima-instance-create",
+            OperationKind::Start => "lima-instance-start",
+            OperationKind::Stop => "lima-instance-stop",
+            OperationKind::Delete => "lima-instance-delete",
+            OperationKind::Restart => "lima-instance-restart",
+            OperationKind::Clone => "lima-instance-clone",
+            OperationKind::Rename => "lima-instance-rename",
+            OperationKind::Snapshot => "lima-instance-snapshot",
+            OperationKind::Recreate => "lima-instance-recreate",
+        }
+    }
+}
+
+/// A running operation as reported to the frontend
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationInfo {
+    pub operation_id: String,
+    pub instance_name: String,
+    pub kind: OperationKind,
+    /// Nanoseconds since the epoch, same format as event timestamps
+    pub started_at: String,
+}
+
+/// Payload of `instance-operation-changed`; `operation` is `None` once the instance is idle
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceOperationChanged {
+    pub instance_name: String,
+    pub operation: Option<OperationInfo>,
+}
+
+struct TrackedOperation {
+    info: OperationInfo,
+    /// Taken when the operation is cancelled
+    cancel: Option<oneshot::Sender<()>>,
+}
+
+/// Called with the instance name and its current operation whenever an operation
+/// starts or finishes
+type ChangeListener = Arc<dyn Fn(&str, Option<&OperationInfo>) + Send + Sync>;
+
+/// Managed registry of in-flight lifecycle operations, keyed by operation id.
+/// Also the per-instance lock: an instance runs at most one operation at a time.
+#[derive(Clone, Default)]
+pub struct OperationRegistry {
+    operations: Arc<Mutex<HashMap<String, TrackedOperation>>>,
+    on_change: Option<ChangeListener>,
+}
+
+impl OperationRegistry {
+    /// Registry that calls `on_change` whenever an instance's current operation changes
+    pub fn with_listener(
+        on_change: impl Fn(&str, Option<&OperationInfo>) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            operations: Arc::default(),
+            on_change: Some(Arc::new(on_change)),
+        }
+    }
+
+    /// Track a new operation. It stays registered until the returned handle is dropped.
+    /// Fails with `AppError::OperationInProgress` if the instance is already busy.
+    pub fn register(
+        &self,
+        instance_name: &str,
+        kind: OperationKind,
+    ) -> Result<OperationHandle, AppError> {
+        let (sender, receiver) = oneshot::channel();
+        let info = new_operation_info(instance_name, kind);
+        {
+            let mut operations = self
+                .operations
+                .lock()
+                .map_err(|e| AppError::io("Failed to lock operation registry", e))?;
+            if let Some(running) = operations
+                .values()
+                .find(|op| op.info.instance_name == instance_name)
+            {
+                return Err(AppError::OperationInProgress {
+                    instance_name: instance_name.to_string(),
+                    operation_id: running.info.operation_id.clone(),
+                    running_operation: running.info.kind.name().to_string(),
+                });
+            }
+            operations.insert(
+                info.operation_id.clone(),
+                TrackedOperation {
+                    info: info.clone(),
+                    cancel: Some(sender),
+                },
+            );
+        }
+        self.notify(instance_name, Some(&info));
+        Ok(OperationHandle {
+            info,
+            cancelled: Some(receiver),
+            registry: Some(self.clone()),
+        })
+    }
+
+    /// The operation currently running on an instance, if any
+    pub fn current(&self, instance_name: &str) -> Option<OperationInfo> {
+        self.operations.lock().ok().and_then(|ops| {
+            ops.values()
+                .find(|op| op.info.instance_name == instance_name)
+                .map(|op| op.info.clone())
+        })
+    }
+
+    /// All in-flight operations, oldest first
+    pub fn list(&self) -> Vec<OperationInfo> {
+        let mut operations: Vec<OperationInfo> = self
+            .operations
+            .lock()
+            .map(|ops| ops.values().map(|op| op.info.clone()).collect())
+            .unwrap_or_default();
+        operations.sort_by(|a, b| {
+            let key = |info: &OperationInfo| info.started_at.parse::<u128>().unwrap_or(0);
+            key(a).cmp(&key(b))
+        });
+        operations
+    }
+
+    /// Ask an operation to stop. Cancelling an operation twice is not an error.
+    pub fn cancel(&self, operation_id: &str) -> Result<OperationInfo, AppError> {
+        let mut operations = self
+            .operations
+            .lock()
+            .map_err(|e| AppError::io("Failed to lock operation registry", e))?;
+        let operation =
+            operations
+                .get_mut(operation_id)
+                .ok_or_else(|| AppError::OperationNotFound {
+                    operation_id: operation_id.to_string(),
+                })?;
+        if let Some(cancel) = operation.cancel.take() {
+            let _ = cancel.send(());
+        }
+        Ok(operation.info.clone())
+    }
+
+    fn remove(&self, info: &OperationInfo) {
+        if let Ok(mut operations) = self.operations.lock() {
+            operations.remove(&info.operation_id);
+        }
+        self.notify(&info.instance_name, None);
+    }
+
+    fn notify(&self, instance_name: &str, current: Option<&OperationInfo>) {
+        if let Some(on_change) = &self.on_change {
+            on_change(instance_name, current);
+        }
+    }
+}
+
+/// Owned by the task running an operation; unregisters the operation on drop
+pub struct OperationHandle {
+    pub info: OperationInfo,
+    cancelled: Option<oneshot::Receiver<()>>,
+    registry: Option<OperationRegistry>,
+}
+
+impl OperationHandle {
+    /// Resolves once the operation is cancelled; never resolves otherwise
+    pub async fn cancelled(&mut self) {
+        if let Some(receiver) = self.cancelled.as_mut() {
+            let result = receiver.await;
+            self.cancelled = None;
+            if result.is_ok() {
+                return;
+            }
+        }
+        std::future::pending::<()>().await
+    }
+}
+
+impl Drop for OperationHandle {
+    fn drop(&mut self) {
+        if let Some(registry) = &self.registry {
+            registry.remove(&self.info);
+        }
+    }
+}
+
+fn new_operation_info(instance_name: &str, kind: OperationKind) -> OperationInfo {
+    let started_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    OperationInfo {
+        operation_id: uuid::Uuid::new_v4().to_string(),
+        instance_name: instance_name.to_string(),
+        kind,
+        started_at: started_at.to_string(),
+    }
+}
+
+/// Track an operation in the managed registry, locking the instance until it finishes.
+/// Without a registry (e.g. in tests) the operation runs untracked, unlocked and cannot be cancelled.
+pub fn register_operation<R: Runtime>(
+    app: &AppHandle<R>,
+    instance_name: &str,
+    kind: OperationKind,
+) -> Result<OperationHandle, AppError> {
+    match app.try_state::<OperationRegistry>() {
+        Some(registry) => registry.register(instance_name, kind),
+        None => Ok(OperationHandle {
+            info: new_operation_info(instance_name, kind),
+            cancelled: None,
+            registry: None,
+        }),
+    }
+}
+
+/// The operation currently running on an instance, if any
+pub fn current_operation<R: Runtime>(
+    app: &AppHandle<R>,
+    instance_name: &str,
+) -> Option<OperationInfo> {
+    app.try_state::<OperationRegistry>()
+        .and_then(|registry| registry.current(instance_name))
+}
+
+/// Cancel an in-flight operation by id
+pub fn cancel_operation<R: Runtime>(
+    app: &AppHandle<R>,
+    operation_id: &str,
+) -> Result<OperationInfo, AppError> {
+    app.try_state::<OperationRegistry>()
+        .ok_or_else(|| AppError::OperationNotFound {
+            operation_id: operation_id.to_string(),
+        })?
+        .cancel(operation_id)
+}
+
+/// In-flight operations, oldest first
+pub fn list_operations<R: Runtime>(app: &AppHandle<R>) -> Vec<OperationInfo> {
+    app.try_state::<OperationRegistry>()
+        .map(|registry| registry.list())
+        .unwrap_or_default()
+}
+
+/// How an operation ended, from its `-success`, `-error` or `-cancelled` event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OperationOutcome {
+    Success,
+    /// A start that worked but whose readiness conditions were not met, with the
+    /// success event's message naming them
+    NotReady(String),
+    /// The error event's message
+    Error(String),
+    Cancelled,
+}
+
+/// Collects the outcome events of one kind of operation. Create it before starting the
+/// operations to wait for, so one that ends right away is not missed.
+pub struct OutcomeWatcher<R: Runtime> {
+    app: AppHandle<R>,
+    listeners: Vec<EventId>,
+    received: mpsc::UnboundedReceiver<(String, OperationOutcome)>,
+    /// Outcomes of other operations that arrived while waiting
+    pending: HashMap<String, OperationOutcome>,
+}
+
+impl<R: Runtime> OutcomeWatcher<R> {
+    pub fn new(app: &AppHandle<R>, kind: OperationKind) -> Self {
+        #[derive(Deserialize)]
+        struct OutcomePayload {
+            operation_id: String,
+            #[serde(default)]
+            message: String,
+            /// Only set by starts
+            #[serde(default)]
+            ready: Option<bool>,
+        }
+
+        let (sender, received) = mpsc::unbounded_channel();
+        let listeners = ["-success", "-error", "-cancelled"]
+            .into_iter()
+            .map(|suffix| {
+                let sender = sender.clone();
+                app.listen_any(format!("{}{}", kind.event(), suffix), move |event| {
+                    let Ok(payload) = serde_json::from_str::<OutcomePayload>(event.payload())
+                    else {
+                        return;
+                    };
+                    let outcome = match suffix {
+                        "-success" if payload.ready == Some(false) => {
+                            OperationOutcome::NotReady(payload.message)
+                        }
+                        "-success" => OperationOutcome::Success,
+                        "-error" => OperationOutcome::Error(payload.message),
+                        _ => OperationOutcome::Cancelled,
+                    };
+                    let _ = sender.send((payload.operation_id, outcome));
+                })
+            })
+            .collect();
+        Self {
+            app: app.clone(),
+            listeners,
+            received,
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Wait until the operation with this id has ended
+    pub async fn wait(&mut self, operation_id: &str) -> OperationOutcome {
+        if let Some(outcome) = self.pending.remove(operation_id) {
+            return outcome;
+        }
+        while let Some((id, outcome)) = self.received.recv().await {
+            if id == operation_id {
+                return outcome;
+            }
+            self.pending.insert(id, outcome);
+        }
+        // Only reached if the listeners were removed, which happens on drop
+        OperationOutcome::Error("Stopped waiting for the operation".to_string())
+    }
+}
+
+impl<R: Runtime> Drop for OutcomeWatcher<R> {
+    fn drop(&mut self) {
+        for listener in self.listeners.drain(..) {
+            self.app.unlisten(listener);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_register_cancel_and_drop() {
+        let registry = OperationRegistry::default();
+        let mut handle = registry.register("dev", OperationKind::Start).unwrap();
+        let id = handle.info.operation_id.clone();
+        assert_eq!(registry.list(), vec![handle.info.clone()]);
+
+        let info = registry.cancel(&id).unwrap();
+        assert_eq!(info.kind, OperationKind::Start);
+        // A second cancel is a no-op
+        assert!(registry.cancel(&id).is_ok());
+        tokio::time::timeout(std::time::Duration::from_secs(1), handle.cancelled())
+            .await
+            .expect("cancel signal");
+
+        drop(handle);
+        assert!(registry.list().is_empty());
+        assert_eq!(
+            registry.cancel(&id).unwrap_err().kind(),
+            "operationNotFound"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_untracked_handle_is_never_cancelled() {
+        let app = tauri::test::mock_app();
+        let mut handle = register_operation(app.handle(), "dev", OperationKind::Stop).unwrap();
+        assert!(list_operations(app.handle()).is_empty());
+        let result =
+            tokio::time::timeout(std::time::Duration::from_millis(20), handle.cancelled()).await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_register_rejects_conflicting_operation() {
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let registry = {
+            let changes = changes.clone();
+            OperationRegistry::with_listener(move |name, current| {
+                changes
+                    .lock()
+                    .unwrap()
+                    .push((name.to_string(), current.map(|op| op.kind)));
+            })
+        };
+
+        let start = registry.register("dev", OperationKind::Start).unwrap();
+        match registry.register("dev", OperationKind::Delete) {
+            Err(AppError::OperationInProgress {
+                operation_id,
+                running_operation,
+                ..
+            }) => {
+                assert_eq!(operation_id, start.info.operation_id);
+                assert_eq!(running_operation, "start");
+            }
+            other => panic!("expected OperationInProgress, got {:?}", other.err()),
+        }
+        // Other instances are not affected
+        let other = registry.register("other", OperationKind::Stop).unwrap();
+        assert_eq!(registry.current("dev"), Some(start.info.clone()));
+
+        drop(start);
+        assert_eq!(registry.current("dev"), None);
+        assert!(registry.register("dev", OperationKind::Delete).is_ok());
+        drop(other);
+
+        assert_eq!(
+            changes.lock().unwrap()[..3],
+            [
+                ("dev".to_string(), Some(OperationKind::Start)),
+                ("other".to_string(), Some(OperationKind::Stop)),
+                ("dev".to_string(), None),
+            ]
+        );
+    }
+}
@@ -0,0 +1,26 @@
+use crate::error::AppError;
+use crate::lima_config::LimaConfig;
+use crate::lima_instance_service::read_instance_config;
+use crate::preflight_service::{self, PreflightReport};
+use tauri::AppHandle;
+
+/// Check the host's CPUs, memory and free disk against what an instance asks for and
+/// what the running instances use. Pass `config` for an instance about to be created;
+/// without it the existing instance's lima.yaml is checked for a start.
+#[tauri::command]
+pub async fn preflight_lima_instance_cmd(
+    app: AppHandle,
+    instance_name: String,
+    config: Option<LimaConfig>,
+) -> Result<PreflightReport, AppError> {
+    let creating = config.is_some();
+    let config = match config {
+        Some(config) => config,
+        None => read_instance_config(&app, &instance_name).ok_or_else(|| {
+            AppError::InstanceNotFound {
+                instance_name: instance_name.clone(),
+            }
+        })?,
+    };
+    preflight_service::preflight(&app, &instance_name, &config, creating).await
+}
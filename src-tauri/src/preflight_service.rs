@@ -0,0 +1,453 @@
+use crate::error::AppError;
+use crate::instance_registry_service;
+use crate::lima_backend::lima_backend;
+use crate::lima_config::{host_default_vm_type, LimaConfig};
+use crate::lima_instance_service::read_instance_config;
+use crate::yaml_handler::get_lima_home;
+use serde::Serialize;
+use std::path::Path;
+use sysinfo::{CpuRefreshKind, Disks, System};
+use tauri::{AppHandle, Runtime};
+
+/// Lima's default CPUs for a config without `cpus`, capped at the host's count
+const LIMA_DEFAULT_CPUS: u32 = 4;
+
+/// Lima's default memory for a config without `memory`, capped at half the host's
+const LIMA_DEFAULT_MEMORY: u64 = 4 << 30;
+
+/// Lima's default disk size for a config without `disk`
+const LIMA_DEFAULT_DISK: u64 = 100 << 30;
+
+/// Free space in LIMA_HOME below which a VM is likely to fail writing its disk
+const MIN_FREE_DISK: u64 = 1 << 30;
+
+const GIB: f64 = (1u64 << 30) as f64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Resource {
+    Cpus,
+    Memory,
+    Disk,
+}
+
+/// A resource the instance asks for more of than the host can comfortably give
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceIssue {
+    pub resource: Resource,
+    /// CPUs, or bytes for memory and disk
+    pub requested: u64,
+    /// What is left on the host for this instance, in the same unit
+    pub available: u64,
+    pub message: String,
+}
+
+/// CPUs, memory and disk size of a config, with Lima's defaults filled in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceResources {
+    pub cpus: u32,
+    pub memory: u64,
+    pub disk: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostResources {
+    pub cpus: u32,
+    pub memory: u64,
+    /// Size of the filesystem holding LIMA_HOME, `None` if it could not be found
+    pub disk_total: Option<u64>,
+    /// Free space in LIMA_HOME
+    pub disk_free: Option<u64>,
+}
+
+/// CPUs and memory given to the running instances
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AllocatedResources {
+    pub cpus: u32,
+    pub memory: u64,
+    pub instances: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightReport {
+    pub requested: InstanceResources,
+    pub host: HostResources,
+    pub allocated: AllocatedResources,
+    /// Overcommit the host, but may well work
+    pub warnings: Vec<ResourceIssue>,
+    /// Keep the instance from being created or started
+    pub errors: Vec<ResourceIssue>,
+}
+
+/// Parse a Lima size like "4GiB", "512MiB" or "100G". Lima reads decimal and binary
+/// suffixes alike as powers of 1024.
+pub fn parse_byte_size(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let unit = unit.trim().to_ascii_lowercase();
+    let unit = unit.strip_suffix('b').unwrap_or(&unit);
+    let unit = unit.strip_suffix('i').unwrap_or(unit);
+    let shift = match unit {
+        "" => 0,
+        "k" => 10,
+        "m" => 20,
+        "g" => 30,
+        "t" => 40,
+        "p" => 50,
+        _ => return None,
+    };
+    Some((number * (1u64 << shift) as f64) as u64)
+}
+
+fn gib(bytes: u64) -> String {
+    format!("{:.1} GiB", bytes as f64 / GIB)
+}
+
+/// Resources `config` asks for; unset or unreadable fields get Lima's defaults
+pub fn instance_resources(config: &LimaConfig, host: &HostResources) -> InstanceResources {
+    InstanceResources {
+        cpus: config
+            .cpus
+            .unwrap_or_else(|| LIMA_DEFAULT_CPUS.min(host.cpus.max(1))),
+        memory: config
+            .memory
+            .as_deref()
+            .and_then(parse_byte_size)
+            .unwrap_or_else(|| LIMA_DEFAULT_MEMORY.min(host.memory / 2)),
+        disk: config
+            .disk
+            .as_deref()
+            .and_then(parse_byte_size)
+            .unwrap_or(LIMA_DEFAULT_DISK),
+    }
+}
+
+/// Compare what an instance of `vm_type` asks for with the host and the running instances.
+/// More vCPUs than the host has is an error for vz, which refuses to start such a VM,
+/// and only a warning otherwise, as QEMU runs them (slowly).
+/// Disks are sparse, so a size beyond the free space warns, but next to no free space
+/// at all is an error.
+fn evaluate(
+    requested: InstanceResources,
+    vm_type: &str,
+    host: HostResources,
+    allocated: AllocatedResources,
+    creating: bool,
+) -> PreflightReport {
+    let mut warnings = Vec::new();
+    let mut errors = Vec::new();
+
+    let free_cpus = host.cpus.saturating_sub(allocated.cpus);
+    if requested.cpus > host.cpus && vm_type == "vz" {
+        errors.push(ResourceIssue {
+            resource: Resource::Cpus,
+            requested: requested.cpus.into(),
+            available: free_cpus.into(),
+            message: format!(
+                "{} CPUs requested but the host has {}; the VZ driver refuses to start this",
+                requested.cpus, host.cpus
+            ),
+        });
+    } else if requested.cpus > host.cpus {
+        warnings.push(ResourceIssue {
+            resource: Resource::Cpus,
+            requested: requested.cpus.into(),
+            available: free_cpus.into(),
+            message: format!(
+                "{} CPUs requested but the host has {}; they will share its cores and run slowly",
+                requested.cpus, host.cpus
+            ),
+        });
+    } else if requested.cpus > free_cpus {
+        warnings.push(ResourceIssue {
+            resource: Resource::Cpus,
+            requested: requested.cpus.into(),
+            available: free_cpus.into(),
+            message: format!(
+                "{} CPUs requested while running instances already use {} of the host's {}",
+                requested.cpus, allocated.cpus, host.cpus
+            ),
+        });
+    }
+
+    let free_memory = host.memory.saturating_sub(allocated.memory);
+    if requested.memory > host.memory {
+        errors.push(ResourceIssue {
+            resource: Resource::Memory,
+            requested: requested.memory,
+            available: free_memory,
+            message: format!(
+                "{} of memory requested but the host only has {}",
+                gib(requested.memory),
+                gib(host.memory)
+            ),
+        });
+    } else if requested.memory > free_memory {
+        warnings.push(ResourceIssue {
+            resource: Resource::Memory,
+            requested: requested.memory,
+            available: free_memory,
+            message: format!(
+                "{} of memory requested while running instances already use {} of the host's {}",
+                gib(requested.memory),
+                gib(allocated.memory),
+                gib(host.memory)
+            ),
+        });
+    }
+
+    if let Some(free) = host.disk_free {
+        if free < MIN_FREE_DISK {
+            errors.push(ResourceIssue {
+                resource: Resource::Disk,
+                requested: if creating {
+                    requested.disk
+                } else {
+                    MIN_FREE_DISK
+                },
+                available: free,
+                message: format!("Only {} free in the Lima home directory", gib(free)),
+            });
+        } else if creating && requested.disk > free {
+            warnings.push(ResourceIssue {
+                resource: Resource::Disk,
+                requested: requested.disk,
+                available: free,
+                message: format!(
+                    "The {} disk can grow beyond the {} free in the Lima home directory",
+                    gib(requested.disk),
+                    gib(free)
+                ),
+            });
+        }
+    }
+
+    PreflightReport {
+        requested,
+        host,
+        allocated,
+        warnings,
+        errors,
+    }
+}
+
+/// Size and free space of the filesystem `path` is on
+fn disk_space(path: &Path) -> Option<(u64, u64)> {
+    // LIMA_HOME may not exist before the first instance
+    let path = path.ancestors().find_map(|dir| dir.canonicalize().ok())?;
+    let disks = Disks::new_with_refreshed_list();
+    disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| (disk.total_space(), disk.available_space()))
+}
+
+fn host_resources(lima_home: Option<&Path>) -> HostResources {
+    let mut system = System::new();
+    system.refresh_memory();
+    system.refresh_cpu_list(CpuRefreshKind::nothing());
+    let disk = lima_home.and_then(disk_space);
+    HostResources {
+        cpus: system.cpus().len() as u32,
+        memory: system.total_memory(),
+        disk_total: disk.map(|(total, _)| total),
+        disk_free: disk.map(|(_, free)| free),
+    }
+}
+
+/// Check whether the host can run `instance_name` with `config` next to the running
+/// instances. `creating` adds the check of the new disk against the free space.
+pub async fn preflight<R: Runtime>(
+    app: &AppHandle<R>,
+    instance_name: &str,
+    config: &LimaConfig,
+    creating: bool,
+) -> Result<PreflightReport, AppError> {
+    let host = host_resources(get_lima_home(app).ok().as_deref());
+    let instances =
+        instance_registry_service::get_all_lima_instances(lima_backend(app).as_ref()).await?;
+
+    let mut allocated = AllocatedResources::default();
+    for instance in instances
+        .iter()
+        .filter(|i| i.status == "Running" && i.name != instance_name)
+    {
+        let config = read_instance_config(app, &instance.name).unwrap_or_default();
+        let resources = instance_resources(&config, &host);
+        allocated.cpus += resources.cpus;
+        allocated.memory += resources.memory;
+        allocated.instances.push(instance.name.clone());
+    }
+
+    let requested = instance_resources(config, &host);
+    // Lima picks the same default VM type as the default templates
+    let vm_type = config.vm_type.as_deref().unwrap_or(host_default_vm_type());
+    Ok(evaluate(requested, vm_type, host, allocated, creating))
+}
+
+/// Fail with `AppError::InsufficientResources` if the preflight finds blocking errors.
+/// Warnings are only logged; the UI shows them from `preflight` before asking.
+pub async fn ensure_resources<R: Runtime>(
+    app: &AppHandle<R>,
+    instance_name: &str,
+    config: &LimaConfig,
+    creating: bool,
+) -> Result<(), AppError> {
+    let report = preflight(app, instance_name, config, creating).await?;
+    for warning in &report.warnings {
+        log::warn!("Preflight for '{}': {}", instance_name, warning.message);
+    }
+    if report.errors.is_empty() {
+        return Ok(());
+    }
+    Err(AppError::InsufficientResources {
+        issues: report.errors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(cpus: u32, memory_gib: u64, disk_free_gib: u64) -> HostResources {
+        HostResources {
+            cpus,
+            memory: memory_gib << 30,
+            disk_total: Some(500 << 30),
+            disk_free: Some(disk_free_gib << 30),
+        }
+    }
+
+    fn resources(cpus: u32, memory_gib: u64, disk_gib: u64) -> InstanceResources {
+        InstanceResources {
+            cpus,
+            memory: memory_gib << 30,
+            disk: disk_gib << 30,
+        }
+    }
+
+    fn allocated(cpus: u32, memory_gib: u64) -> AllocatedResources {
+        AllocatedResources {
+            cpus,
+            memory: memory_gib << 30,
+            instances: vec!["k0s".to_string()],
+        }
+    }
+
+    fn resources_of(issues: &[ResourceIssue]) -> Vec<Resource> {
+        issues.iter().map(|issue| issue.resource).collect()
+    }
+
+    #[test]
+    fn test_parse_byte_size() {
+        assert_eq!(parse_byte_size("4GiB"), Some(4 << 30));
+        assert_eq!(parse_byte_size("100G"), Some(100 << 30));
+        assert_eq!(parse_byte_size("512 MB"), Some(512 << 20));
+        assert_eq!(parse_byte_size("1.5GiB"), Some(3 << 29));
+        assert_eq!(parse_byte_size("1024"), Some(1024));
+        assert_eq!(parse_byte_size("lots"), None);
+        assert_eq!(parse_byte_size("4XiB"), None);
+    }
+
+    #[test]
+    fn test_unset_fields_get_lima_defaults() {
+        let small_host = host(2, 6, 100);
+        assert_eq!(
+            instance_resources(&LimaConfig::default(), &small_host),
+            resources(2, 3, 100)
+        );
+        let config = LimaConfig {
+            cpus: Some(6),
+            memory: Some("8GiB".to_string()),
+            disk: Some("not a size".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            instance_resources(&config, &host(16, 64, 100)),
+            resources(6, 8, 100)
+        );
+    }
+
+    #[test]
+    fn test_second_half_host_instance_overcommits() {
+        // Two instances from the default templates each take half the host
+        let report = evaluate(
+            resources(5, 16, 40),
+            "qemu",
+            host(8, 32, 200),
+            allocated(4, 16),
+            true,
+        );
+        assert!(report.errors.is_empty());
+        assert_eq!(resources_of(&report.warnings), [Resource::Cpus]);
+
+        let report = evaluate(
+            resources(4, 20, 40),
+            "qemu",
+            host(8, 32, 200),
+            allocated(4, 16),
+            false,
+        );
+        assert!(report.errors.is_empty());
+        assert_eq!(resources_of(&report.warnings), [Resource::Memory]);
+        assert_eq!(report.warnings[0].available, 16 << 30);
+    }
+
+    #[test]
+    fn test_blocking_errors() {
+        let report = evaluate(
+            resources(16, 64, 40),
+            "qemu",
+            host(8, 32, 0),
+            AllocatedResources::default(),
+            false,
+        );
+        assert_eq!(resources_of(&report.warnings), [Resource::Cpus]);
+        assert_eq!(
+            resources_of(&report.errors),
+            [Resource::Memory, Resource::Disk]
+        );
+
+        // VZ does not start a VM with more vCPUs than the host has
+        let report = evaluate(
+            resources(16, 4, 40),
+            "vz",
+            host(8, 32, 200),
+            AllocatedResources::default(),
+            false,
+        );
+        assert!(report.warnings.is_empty());
+        assert_eq!(resources_of(&report.errors), [Resource::Cpus]);
+    }
+
+    #[test]
+    fn test_disk_beyond_free_space_warns_on_create_only() {
+        let report = evaluate(
+            resources(2, 4, 100),
+            "qemu",
+            host(8, 32, 50),
+            AllocatedResources::default(),
+            true,
+        );
+        assert_eq!(resources_of(&report.warnings), [Resource::Disk]);
+        let report = evaluate(
+            resources(2, 4, 100),
+            "qemu",
+            host(8, 32, 50),
+            AllocatedResources::default(),
+            false,
+        );
+        assert!(report.warnings.is_empty());
+    }
+}
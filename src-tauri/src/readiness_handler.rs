@@ -0,0 +1,15 @@
+use crate::readiness_service::{self, ReadinessReport};
+use std::time::Duration;
+use tauri::AppHandle;
+
+/// Check the readiness conditions of an instance, retrying unmet ones for up to
+/// `timeout_secs` (default: check once)
+#[tauri::command]
+pub async fn check_instance_readiness_cmd(
+    app: AppHandle,
+    instance_name: String,
+    timeout_secs: Option<u64>,
+) -> ReadinessReport {
+    let timeout = Duration::from_secs(timeout_secs.unwrap_or(0));
+    readiness_service::wait_until_ready(&app, &instance_name, timeout).await
+}
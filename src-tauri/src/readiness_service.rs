@@ -0,0 +1,409 @@
+use crate::http_client;
+use crate::lima_backend::{lima_backend, LimaBackend};
+use crate::lima_config::LimaConfig;
+use crate::lima_config_service;
+use crate::lima_instance_service::has_host_kubeconfig;
+use crate::yaml_handler::{get_home_dir, get_instance_dir};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Runtime};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::time::Instant;
+
+/// How long a start waits for its conditions after `limactl start` returns. Docker
+/// and Kubernetes can take a while longer than the probes Lima itself waits for.
+pub const START_READINESS_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Pause between attempts of a condition that is not met yet
+const RETRY_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Longest a single attempt may take, e.g. an SSH connection to a booting guest
+const ATTEMPT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Guest port of the Kubernetes API server
+const KUBERNETES_API_PORT: u16 = 6443;
+
+/// Something that has to work before an instance counts as ready
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ReadinessCondition {
+    /// `limactl shell` gets through
+    Ssh,
+    /// A `probes` script of lima.yaml exits successfully
+    Probe {
+        description: String,
+        #[serde(skip)]
+        script: String,
+        #[serde(skip)]
+        hint: Option<String>,
+    },
+    /// The docker socket forwarded to the host answers `/_ping`
+    DockerSocket { path: PathBuf },
+    /// The Kubernetes API answers on its forwarded host port
+    KubernetesApi { port: u16 },
+}
+
+impl std::fmt::Display for ReadinessCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReadinessCondition::Ssh => write!(f, "SSH"),
+            ReadinessCondition::Probe { description, .. } => write!(f, "probe '{}'", description),
+            ReadinessCondition::DockerSocket { path } => {
+                write!(f, "docker socket {}", path.display())
+            }
+            ReadinessCondition::KubernetesApi { port } => {
+                write!(f, "Kubernetes API on port {}", port)
+            }
+        }
+    }
+}
+
+/// Outcome of one condition
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConditionResult {
+    pub condition: ReadinessCondition,
+    pub ready: bool,
+    /// Why the last attempt failed
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadinessReport {
+    pub instance_name: String,
+    pub ready: bool,
+    pub conditions: Vec<ConditionResult>,
+}
+
+/// Payload of `instance-readiness`, emitted once per condition
+#[derive(Clone, Serialize)]
+struct ReadinessPayload<'a> {
+    instance_name: &'a str,
+    #[serde(flatten)]
+    result: &'a ConditionResult,
+}
+
+/// The conditions an instance's config calls for. SSH always comes first, as the
+/// probes run over it.
+fn readiness_conditions(
+    config: Option<&LimaConfig>,
+    instance_dir: &Path,
+    home: Option<&Path>,
+) -> Vec<ReadinessCondition> {
+    let mut conditions = vec![ReadinessCondition::Ssh];
+    let Some(config) = config else {
+        return conditions;
+    };
+
+    for (i, probe) in config.probes.iter().flatten().enumerate() {
+        let description = if probe.description.trim().is_empty() {
+            format!("probe {}", i + 1)
+        } else {
+            probe.description.clone()
+        };
+        conditions.push(ReadinessCondition::Probe {
+            description,
+            script: probe.script.clone(),
+            hint: probe.hint.clone(),
+        });
+    }
+
+    let port_forwards: Vec<_> = config
+        .port_forwards
+        .iter()
+        .flatten()
+        .filter(|forward| forward.ignore != Some(true))
+        .collect();
+    for forward in &port_forwards {
+        if let Some(socket) = forward
+            .host_socket
+            .as_deref()
+            .filter(|socket| socket.ends_with("docker.sock"))
+        {
+            let socket = expand_host_path(socket, instance_dir, home);
+            conditions.push(ReadinessCondition::DockerSocket { path: socket });
+        }
+    }
+
+    // Lima forwards guest ports to the same host port unless a rule says otherwise
+    let api_port = port_forwards
+        .iter()
+        .find(|forward| forward.guest_port == Some(KUBERNETES_API_PORT))
+        .map(|forward| forward.host_port.unwrap_or(KUBERNETES_API_PORT));
+    if let Some(port) =
+        api_port.or_else(|| has_host_kubeconfig(config).then_some(KUBERNETES_API_PORT))
+    {
+        conditions.push(ReadinessCondition::KubernetesApi { port });
+    }
+    conditions
+}
+
+/// Resolve the template variables Lima allows in host paths; relative paths are
+/// relative to the instance dir
+fn expand_host_path(path: &str, instance_dir: &Path, home: Option<&Path>) -> PathBuf {
+    let mut expanded = path.replace("{{.Dir}}", &instance_dir.to_string_lossy());
+    if let Some(name) = instance_dir.file_name() {
+        expanded = expanded.replace("{{.Name}}", &name.to_string_lossy());
+    }
+    if let Some(home) = home {
+        expanded = expanded.replace("{{.Home}}", &home.to_string_lossy());
+    }
+    instance_dir.join(expanded)
+}
+
+/// Command running a probe script with the interpreter from its shebang, like Lima does
+fn probe_command(script: &str) -> Vec<&str> {
+    let mut command: Vec<&str> = script
+        .lines()
+        .next()
+        .and_then(|line| line.strip_prefix("#!"))
+        .map(|interpreter| interpreter.split_whitespace().collect())
+        .filter(|interpreter: &Vec<&str>| !interpreter.is_empty())
+        .unwrap_or_else(|| vec!["/bin/sh"]);
+    command.extend(["-c", script]);
+    command
+}
+
+async fn check_condition(
+    backend: &dyn LimaBackend,
+    instance_name: &str,
+    condition: &ReadinessCondition,
+) -> Result<(), String> {
+    match condition {
+        ReadinessCondition::Ssh => {
+            let output = backend
+                .shell(instance_name, &["true"])
+                .await
+                .map_err(|e| e.to_string())?;
+            if output.success() {
+                Ok(())
+            } else {
+                Err(output.stderr.trim().to_string())
+            }
+        }
+        ReadinessCondition::Probe { script, hint, .. } => {
+            let output = backend
+                .shell(instance_name, &probe_command(script))
+                .await
+                .map_err(|e| e.to_string())?;
+            if output.success() {
+                Ok(())
+            } else {
+                Err(hint
+                    .clone()
+                    .unwrap_or_else(|| output.stderr.trim().to_string()))
+            }
+        }
+        ReadinessCondition::DockerSocket { path } => ping_docker(path).await,
+        ReadinessCondition::KubernetesApi { port } => check_kubernetes_api(*port).await,
+    }
+}
+
+/// `GET /_ping` on the docker socket, which answers "OK" once the daemon is up
+async fn ping_docker(socket: &Path) -> Result<(), String> {
+    let mut stream = tokio::net::UnixStream::connect(socket)
+        .await
+        .map_err(|e| format!("{}: {}", socket.display(), e))?;
+    stream
+        .write_all(b"GET /_ping HTTP/1.0\r\nHost: docker\r\n\r\n")
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut response = String::new();
+    stream
+        .read_to_string(&mut response)
+        .await
+        .map_err(|e| e.to_string())?;
+    match response.lines().next() {
+        Some(status) if status.split_whitespace().nth(1) == Some("200") => Ok(()),
+        status => Err(format!(
+            "Docker answered {}",
+            status.unwrap_or("nothing").trim()
+        )),
+    }
+}
+
+/// Any HTTP answer from `/readyz` counts: without credentials the API server may well
+/// say 401, but it is up. Its certificate is the cluster's own, so it is not verified.
+async fn check_kubernetes_api(port: u16) -> Result<(), String> {
+    http_client::local_guest_client(ATTEMPT_TIMEOUT)
+        .map_err(|e| e.to_string())?
+        .get(format!("https://127.0.0.1:{}/readyz", port))
+        .send()
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Check each condition until it is met or `timeout` has passed, emitting an
+/// `instance-readiness` event per condition. A zero timeout checks everything once,
+/// e.g. for an instance that has been running for a while.
+pub async fn wait_until_ready<R: Runtime>(
+    app: &AppHandle<R>,
+    instance_name: &str,
+    timeout: Duration,
+) -> ReadinessReport {
+    let config = lima_config_service::read_lima_yaml(app, instance_name).ok();
+    let instance_dir = get_instance_dir(app, instance_name).unwrap_or_default();
+    let home = get_home_dir(app).ok();
+    let conditions = readiness_conditions(config.as_ref(), &instance_dir, home.as_deref());
+
+    let backend = lima_backend(app);
+    let deadline = Instant::now() + timeout;
+    let mut results = Vec::new();
+    for condition in conditions {
+        let result = loop {
+            let attempt = tokio::time::timeout(
+                ATTEMPT_TIMEOUT,
+                check_condition(backend.as_ref(), instance_name, &condition),
+            )
+            .await
+            .unwrap_or_else(|_| Err("Timed out".to_string()));
+            if attempt.is_ok() || Instant::now() + RETRY_INTERVAL > deadline {
+                break attempt;
+            }
+            tokio::time::sleep(RETRY_INTERVAL).await;
+        };
+
+        let result = ConditionResult {
+            condition,
+            ready: result.is_ok(),
+            message: result.err(),
+        };
+        let _ = app.emit(
+            "instance-readiness",
+            ReadinessPayload {
+                instance_name,
+                result: &result,
+            },
+        );
+        results.push(result);
+    }
+
+    ReadinessReport {
+        instance_name: instance_name.to_string(),
+        ready: results.iter().all(|result| result.ready),
+        conditions: results,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_lima_backend::FakeLimaBackend;
+    use crate::lima_backend::LimaBackendState;
+    use crate::lima_config::{PortForward, Probe};
+    use std::sync::{Arc, Mutex};
+    use tauri::{Listener, Manager};
+
+    fn socket_forward(host_socket: &str) -> PortForward {
+        serde_json::from_value(serde_json::json!({
+            "guestSocket": "/var/run/docker.sock",
+            "hostSocket": host_socket,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_conditions_follow_the_config() {
+        let instance_dir = Path::new("/Users/me/.lima/dev");
+        assert_eq!(
+            readiness_conditions(None, instance_dir, None),
+            [ReadinessCondition::Ssh]
+        );
+
+        let mut api_forward = socket_forward("unused");
+        api_forward.host_socket = None;
+        api_forward.guest_socket = None;
+        api_forward.guest_port = Some(6443);
+        api_forward.host_port = Some(16443);
+        let config = LimaConfig {
+            probes: Some(vec![Probe {
+                description: String::new(),
+                script: "#!/bin/bash\ntest -S /run/k0s/status.sock".to_string(),
+                hint: None,
+            }]),
+            port_forwards: Some(vec![
+                socket_forward("{{.Dir}}/docker.sock"),
+                socket_forward("sock/docker.sock"),
+                api_forward,
+            ]),
+            ..Default::default()
+        };
+        let conditions = readiness_conditions(Some(&config), instance_dir, None);
+        assert_eq!(conditions.len(), 5);
+        assert!(
+            matches!(&conditions[1], ReadinessCondition::Probe { description, .. } if description == "probe 1")
+        );
+        assert_eq!(
+            conditions[2..],
+            [
+                ReadinessCondition::DockerSocket {
+                    path: PathBuf::from("/Users/me/.lima/dev/docker.sock")
+                },
+                ReadinessCondition::DockerSocket {
+                    path: PathBuf::from("/Users/me/.lima/dev/sock/docker.sock")
+                },
+                ReadinessCondition::KubernetesApi { port: 16443 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_probe_command_uses_the_shebang() {
+        assert_eq!(
+            probe_command("#!/usr/bin/env bash\nexit 0"),
+            ["/usr/bin/env", "bash", "-c", "#!/usr/bin/env bash\nexit 0"]
+        );
+        assert_eq!(probe_command("exit 0"), ["/bin/sh", "-c", "exit 0"]);
+    }
+
+    #[tokio::test]
+    async fn test_each_condition_is_reported() {
+        let backend = Arc::new(FakeLimaBackend::new().with_instance("dev", "Running"));
+        backend.set_shell_response("/bin/sh -c #!/bin/sh\ntest -f /ready\n", "");
+        std::fs::write(
+            backend.lima_home().unwrap().join("dev").join("lima.yaml"),
+            r#"probes:
+- description: ready file
+  script: |
+    #!/bin/sh
+    test -f /ready
+- description: never
+  script: exit 1
+  hint: Check the provision logs
+"#,
+        )
+        .unwrap();
+        let app = tauri::test::mock_app();
+        app.manage(LimaBackendState(backend));
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        {
+            let events = events.clone();
+            app.listen_any("instance-readiness", move |event| {
+                let payload: serde_json::Value = serde_json::from_str(event.payload()).unwrap();
+                events.lock().unwrap().push((
+                    payload["condition"]["kind"].as_str().unwrap().to_string(),
+                    payload["ready"].as_bool().unwrap(),
+                ));
+            });
+        }
+
+        let report = wait_until_ready(app.handle(), "dev", Duration::ZERO).await;
+        assert!(!report.ready);
+        assert_eq!(
+            report.conditions[2].message.as_deref(),
+            Some("Check the provision logs")
+        );
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                ("ssh".to_string(), true),
+                ("probe".to_string(), true),
+                ("probe".to_string(), false),
+            ]
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use crate::error::AppError;
+use crate::lima_service::{self, LimaPaths};
+use crate::settings_service::{
+    self, IdlePolicy, LaunchPolicy, LifecycleSettings, LimaPathSettings,
+};
+use std::collections::BTreeMap;
+use tauri::AppHandle;
+
+/// Get the user's Lima path overrides (unset fields are auto-detected)
+#[tauri::command]
+pub async fn get_lima_path_settings_cmd(app: AppHandle) -> LimaPathSettings {
+    settings_service::load_lima_path_settings(&app)
+}
+
+/// Validate and save Lima path overrides, returning the newly resolved paths
+#[tauri::command]
+pub async fn set_lima_path_settings_cmd(
+    app: AppHandle,
+    settings: LimaPathSettings,
+) -> Result<LimaPaths, AppError> {
+    settings_service::save_lima_path_settings(&app, settings).await?;
+    Ok(lima_service::refresh_lima_paths(&app))
+}
+
+/// Get the Lima paths currently in use
+#[tauri::command]
+pub async fn get_lima_paths_cmd(app: AppHandle) -> LimaPaths {
+    lima_service::lima_paths(&app)
+}
+
+/// Get the graceful stop/delete timeouts
+#[tauri::command]
+pub async fn get_lifecycle_settings_cmd(app: AppHandle) -> LifecycleSettings {
+    settings_service::load_lifecycle_settings(&app)
+}
+
+/// Validate and save the graceful stop/delete timeouts
+#[tauri::command]
+pub async fn set_lifecycle_settings_cmd(
+    app: AppHandle,
+    settings: LifecycleSettings,
+) -> Result<LifecycleSettings, AppError> {
+    settings_service::save_lifecycle_settings(&app, settings)
+}
+
+/// Get the launch policies keyed by instance name; instances without one are left alone
+#[tauri::command]
+pub async fn get_launch_policies_cmd(app: AppHandle) -> BTreeMap<String, LaunchPolicy> {
+    settings_service::load_launch_policies(&app)
+}
+
+/// Set whether an instance starts when the app launches and stops when it quits
+#[tauri::command]
+pub async fn set_launch_policy_cmd(
+    app: AppHandle,
+    instance_name: String,
+    policy: LaunchPolicy,
+) -> Result<BTreeMap<String, LaunchPolicy>, AppError> {
+    settings_service::save_launch_policy(&app, &instance_name, policy)
+}
+
+/// Get the idle auto-stop policies keyed by instance name
+#[tauri::command]
+pub async fn get_idle_policies_cmd(app: AppHandle) -> BTreeMap<String, IdlePolicy> {
+    settings_service::load_idle_policies(&app)
+}
+
+/// Set when an idle instance gets stopped, or turn auto-stop off with `null`
+#[tauri::command]
+pub async fn set_idle_policy_cmd(
+    app: AppHandle,
+    instance_name: String,
+    policy: Option<IdlePolicy>,
+) -> Result<BTreeMap<String, IdlePolicy>, AppError> {
+    settings_service::save_idle_policy(&app, &instance_name, policy)
+}
@@ -0,0 +1,526 @@
+use crate::error::AppError;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+
+/// Store file shared with the frontend (`defaultGlobalStoreFileName`)
+pub const SETTINGS_STORE_FILE: &str = "app.json";
+
+const LIMA_PATHS_KEY: &str = "limaPaths";
+const LIFECYCLE_KEY: &str = "lifecycle";
+const LAUNCH_POLICIES_KEY: &str = "launchPolicies";
+const IDLE_POLICIES_KEY: &str = "idlePolicies";
+
+/// How long a configured limactl gets to answer `--version`
+const LIMACTL_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Read a setting from the store. Missing, unreadable or invalid values give the default.
+fn load_setting<R: Runtime, T: DeserializeOwned + Default>(app: &AppHandle<R>, key: &str) -> T {
+    let store = match app.store(SETTINGS_STORE_FILE) {
+        Ok(store) => store,
+        Err(e) => {
+            log::warn!("Failed to open settings store: {}", e);
+            return T::default();
+        }
+    };
+
+    store
+        .get(key)
+        .and_then(|value| match serde_json::from_value(value) {
+            Ok(settings) => Some(settings),
+            Err(e) => {
+                log::warn!("Ignoring invalid {} setting: {}", key, e);
+                None
+            }
+        })
+        .unwrap_or_default()
+}
+
+/// Write a setting to the store and save it to disk
+fn save_setting<R: Runtime>(
+    app: &AppHandle<R>,
+    key: &str,
+    value: impl Serialize,
+) -> Result<(), AppError> {
+    let store = app
+        .store(SETTINGS_STORE_FILE)
+        .map_err(|e| AppError::io("Failed to open settings store", e))?;
+    store.set(key, serde_json::to_value(value)?);
+    store
+        .save()
+        .map_err(|e| AppError::io("Failed to save settings", e))
+}
+
+/// User overrides for where Lima lives. `None` means auto-detect.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LimaPathSettings {
+    /// Absolute path to the limactl executable
+    #[serde(default)]
+    pub limactl_path: Option<String>,
+    /// LIMA_HOME directory passed to every limactl process
+    #[serde(default)]
+    pub lima_home: Option<String>,
+    /// Absolute path to the brew executable used by `install_lima`
+    #[serde(default)]
+    pub brew_path: Option<String>,
+}
+
+impl LimaPathSettings {
+    /// Trim values, drop empty ones and expand a leading `~/`
+    pub fn normalized(self, home: Option<&Path>) -> Self {
+        let normalize = |value: Option<String>| {
+            let value = value?.trim().to_string();
+            if value.is_empty() {
+                return None;
+            }
+            match (value.strip_prefix("~/"), home) {
+                (Some(rest), Some(home)) => Some(home.join(rest).to_string_lossy().to_string()),
+                _ => Some(value),
+            }
+        };
+        Self {
+            limactl_path: normalize(self.limactl_path),
+            lima_home: normalize(self.lima_home),
+            brew_path: normalize(self.brew_path),
+        }
+    }
+}
+
+/// Load the Lima path overrides from the settings store.
+/// Missing or unreadable settings fall back to auto-detection.
+pub fn load_lima_path_settings<R: Runtime>(app: &AppHandle<R>) -> LimaPathSettings {
+    load_setting(app, LIMA_PATHS_KEY)
+}
+
+/// Validate and persist the Lima path overrides. Returns the normalized settings.
+pub async fn save_lima_path_settings<R: Runtime>(
+    app: &AppHandle<R>,
+    settings: LimaPathSettings,
+) -> Result<LimaPathSettings, AppError> {
+    let home = tauri::Manager::path(app).home_dir().ok();
+    let settings = settings.normalized(home.as_deref());
+    validate_lima_path_settings(&settings).await?;
+    save_setting(app, LIMA_PATHS_KEY, &settings)?;
+    Ok(settings)
+}
+
+/// How long graceful lifecycle operations may take before escalating to `--force`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LifecycleSettings {
+    /// Seconds `limactl stop` gets before `limactl stop --force`
+    #[serde(default = "default_stop_timeout_secs")]
+    pub stop_timeout_secs: u64,
+    /// Seconds `limactl delete` gets before `limactl delete --force`
+    #[serde(default = "default_delete_timeout_secs")]
+    pub delete_timeout_secs: u64,
+}
+
+fn default_stop_timeout_secs() -> u64 {
+    120
+}
+
+fn default_delete_timeout_secs() -> u64 {
+    60
+}
+
+impl Default for LifecycleSettings {
+    fn default() -> Self {
+        Self {
+            stop_timeout_secs: default_stop_timeout_secs(),
+            delete_timeout_secs: default_delete_timeout_secs(),
+        }
+    }
+}
+
+/// Load the lifecycle timeouts, falling back to the defaults
+pub fn load_lifecycle_settings<R: Runtime>(app: &AppHandle<R>) -> LifecycleSettings {
+    load_setting(app, LIFECYCLE_KEY)
+}
+
+/// Validate and persist the lifecycle timeouts
+pub fn save_lifecycle_settings<R: Runtime>(
+    app: &AppHandle<R>,
+    settings: LifecycleSettings,
+) -> Result<LifecycleSettings, AppError> {
+    validate_lifecycle_settings(&settings)?;
+    save_setting(app, LIFECYCLE_KEY, settings)?;
+    Ok(settings)
+}
+
+/// What the app does with an instance when it launches and quits.
+/// Both off (the default) leaves the instance alone.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchPolicy {
+    /// Start the instance when the app launches
+    #[serde(default)]
+    pub start_on_launch: bool,
+    /// Stop the instance when the app is quit from the tray
+    #[serde(default)]
+    pub stop_on_quit: bool,
+}
+
+/// Launch policies keyed by instance name; instances without one are left alone
+pub fn load_launch_policies<R: Runtime>(app: &AppHandle<R>) -> BTreeMap<String, LaunchPolicy> {
+    load_setting(app, LAUNCH_POLICIES_KEY)
+}
+
+/// Set the launch policy of an instance. Returns all policies.
+pub fn save_launch_policy<R: Runtime>(
+    app: &AppHandle<R>,
+    instance_name: &str,
+    policy: LaunchPolicy,
+) -> Result<BTreeMap<String, LaunchPolicy>, AppError> {
+    let mut policies = load_launch_policies(app);
+    set_launch_policy(&mut policies, instance_name, policy);
+    save_setting(app, LAUNCH_POLICIES_KEY, &policies)?;
+    Ok(policies)
+}
+
+/// Move a renamed instance's launch policy to its new name
+pub fn rename_launch_policy<R: Runtime>(
+    app: &AppHandle<R>,
+    old_name: &str,
+    new_name: &str,
+) -> Result<(), AppError> {
+    let mut policies = load_launch_policies(app);
+    let Some(policy) = policies.remove(old_name) else {
+        return Ok(());
+    };
+    set_launch_policy(&mut policies, new_name, policy);
+    save_setting(app, LAUNCH_POLICIES_KEY, &policies)
+}
+
+/// Insert or update a policy; the default one is dropped so the store only keeps real choices
+fn set_launch_policy(
+    policies: &mut BTreeMap<String, LaunchPolicy>,
+    instance_name: &str,
+    policy: LaunchPolicy,
+) {
+    if policy == LaunchPolicy::default() {
+        policies.remove(instance_name);
+    } else {
+        policies.insert(instance_name.to_string(), policy);
+    }
+}
+
+/// Opt-in auto-stop for an instance nobody uses: no terminal sessions into it, no
+/// running containers and a low load average for `idle_minutes`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdlePolicy {
+    /// Minutes the instance must stay idle before it is stopped
+    #[serde(default = "default_idle_minutes")]
+    pub idle_minutes: u32,
+    /// Highest guest 1-minute load average that still counts as idle
+    #[serde(default = "default_max_load")]
+    pub max_load: f64,
+}
+
+fn default_idle_minutes() -> u32 {
+    30
+}
+
+fn default_max_load() -> f64 {
+    0.2
+}
+
+impl Default for IdlePolicy {
+    fn default() -> Self {
+        Self {
+            idle_minutes: default_idle_minutes(),
+            max_load: default_max_load(),
+        }
+    }
+}
+
+/// Idle policies keyed by instance name; instances without one are never auto-stopped
+pub fn load_idle_policies<R: Runtime>(app: &AppHandle<R>) -> BTreeMap<String, IdlePolicy> {
+    load_setting(app, IDLE_POLICIES_KEY)
+}
+
+/// Set the idle policy of an instance, or turn auto-stop off with `None`. Returns all policies.
+pub fn save_idle_policy<R: Runtime>(
+    app: &AppHandle<R>,
+    instance_name: &str,
+    policy: Option<IdlePolicy>,
+) -> Result<BTreeMap<String, IdlePolicy>, AppError> {
+    let mut policies = load_idle_policies(app);
+    match policy {
+        Some(policy) => {
+            validate_idle_policy(&policy)?;
+            policies.insert(instance_name.to_string(), policy);
+        }
+        None => {
+            policies.remove(instance_name);
+        }
+    }
+    save_setting(app, IDLE_POLICIES_KEY, &policies)?;
+    Ok(policies)
+}
+
+/// Move a renamed instance's idle policy to its new name
+pub fn rename_idle_policy<R: Runtime>(
+    app: &AppHandle<R>,
+    old_name: &str,
+    new_name: &str,
+) -> Result<(), AppError> {
+    let mut policies = load_idle_policies(app);
+    let Some(policy) = policies.remove(old_name) else {
+        return Ok(());
+    };
+    policies.insert(new_name.to_string(), policy);
+    save_setting(app, IDLE_POLICIES_KEY, &policies)
+}
+
+pub fn validate_idle_policy(policy: &IdlePolicy) -> Result<(), AppError> {
+    if policy.idle_minutes == 0 {
+        return Err(invalid("idleMinutes", "must be at least 1 minute"));
+    }
+    if !policy.max_load.is_finite() || policy.max_load < 0.0 {
+        return Err(invalid("maxLoad", "must be a non-negative number"));
+    }
+    Ok(())
+}
+
+/// Timeouts must leave the guest some time to shut down
+pub fn validate_lifecycle_settings(settings: &LifecycleSettings) -> Result<(), AppError> {
+    if settings.stop_timeout_secs == 0 {
+        return Err(invalid("stopTimeoutSecs", "must be at least 1 second"));
+    }
+    if settings.delete_timeout_secs == 0 {
+        return Err(invalid("deleteTimeoutSecs", "must be at least 1 second"));
+    }
+    Ok(())
+}
+
+/// Check that configured executables exist and run, and that LIMA_HOME is usable
+pub async fn validate_lima_path_settings(settings: &LimaPathSettings) -> Result<(), AppError> {
+    if let Some(limactl) = &settings.limactl_path {
+        validate_executable("limactlPath", limactl)?;
+        // kill_on_drop so a limactl that hangs is not left behind after the timeout
+        let output = tokio::process::Command::new(limactl)
+            .arg("--version")
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output();
+        let output = tokio::time::timeout(LIMACTL_CHECK_TIMEOUT, output)
+            .await
+            .map_err(|_| {
+                invalid(
+                    "limactlPath",
+                    format!(
+                        "{} --version did not finish within {} seconds",
+                        limactl,
+                        LIMACTL_CHECK_TIMEOUT.as_secs()
+                    ),
+                )
+            })?
+            .map_err(|e| invalid("limactlPath", format!("failed to run {}: {}", limactl, e)))?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if !output.status.success() || !stdout.contains("limactl") {
+            return Err(invalid(
+                "limactlPath",
+                format!("{} does not look like limactl", limactl),
+            ));
+        }
+    }
+
+    if let Some(lima_home) = &settings.lima_home {
+        let path = PathBuf::from(lima_home);
+        if !path.is_absolute() {
+            return Err(invalid("limaHome", "must be an absolute path"));
+        }
+        if path.exists() {
+            if !path.is_dir() {
+                return Err(invalid(
+                    "limaHome",
+                    format!("{} is not a directory", lima_home),
+                ));
+            }
+        } else if !path.parent().is_some_and(Path::is_dir) {
+            // Lima creates LIMA_HOME itself, but not its parents
+            return Err(invalid(
+                "limaHome",
+                format!("parent directory of {} does not exist", lima_home),
+            ));
+        }
+    }
+
+    if let Some(brew) = &settings.brew_path {
+        validate_executable("brewPath", brew)?;
+    }
+
+    Ok(())
+}
+
+fn validate_executable(field: &str, value: &str) -> Result<(), AppError> {
+    let path = Path::new(value);
+    if !path.is_absolute() {
+        return Err(invalid(field, "must be an absolute path"));
+    }
+    let metadata =
+        std::fs::metadata(path).map_err(|e| invalid(field, format!("{}: {}", value, e)))?;
+    if !metadata.is_file() || metadata.permissions().mode() & 0o111 == 0 {
+        return Err(invalid(
+            field,
+            format!("{} is not an executable file", value),
+        ));
+    }
+    Ok(())
+}
+
+fn invalid(field: &str, message: impl Into<String>) -> AppError {
+    AppError::InvalidSetting {
+        field: field.to_string(),
+        message: message.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("0ma-settings-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_normalized_drops_empty_and_expands_home() {
+        let settings = LimaPathSettings {
+            limactl_path: Some("  ".to_string()),
+            lima_home: Some("~/vms/lima ".to_string()),
+            brew_path: None,
+        }
+        .normalized(Some(Path::new("/Users/dev")));
+
+        assert_eq!(settings.limactl_path, None);
+        assert_eq!(settings.lima_home.as_deref(), Some("/Users/dev/vms/lima"));
+        assert_eq!(settings.brew_path, None);
+    }
+
+    #[test]
+    fn test_lifecycle_settings_defaults_and_validation() {
+        // Missing fields keep their defaults
+        let settings: LifecycleSettings =
+            serde_json::from_value(serde_json::json!({ "stopTimeoutSecs": 30 })).unwrap();
+        assert_eq!(settings.stop_timeout_secs, 30);
+        assert_eq!(settings.delete_timeout_secs, 60);
+        assert!(validate_lifecycle_settings(&settings).is_ok());
+
+        let zero = LifecycleSettings {
+            delete_timeout_secs: 0,
+            ..settings
+        };
+        match validate_lifecycle_settings(&zero).unwrap_err() {
+            AppError::InvalidSetting { field, .. } => assert_eq!(field, "deleteTimeoutSecs"),
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_default_launch_policy_is_not_stored() {
+        let mut policies = BTreeMap::new();
+        let auto_start = LaunchPolicy {
+            start_on_launch: true,
+            stop_on_quit: false,
+        };
+        set_launch_policy(&mut policies, "k0s", auto_start);
+        set_launch_policy(&mut policies, "docker", LaunchPolicy::default());
+        assert_eq!(policies.len(), 1);
+        assert_eq!(
+            serde_json::to_value(&policies).unwrap(),
+            serde_json::json!({ "k0s": { "startOnLaunch": true, "stopOnQuit": false } })
+        );
+
+        set_launch_policy(&mut policies, "k0s", LaunchPolicy::default());
+        assert!(policies.is_empty());
+
+        // Fields missing from older stores default to off
+        let policy: LaunchPolicy =
+            serde_json::from_value(serde_json::json!({ "stopOnQuit": true })).unwrap();
+        assert!(!policy.start_on_launch && policy.stop_on_quit);
+    }
+
+    #[test]
+    fn test_idle_policy_defaults_and_validation() {
+        let policy: IdlePolicy =
+            serde_json::from_value(serde_json::json!({ "idleMinutes": 10 })).unwrap();
+        assert_eq!(policy.idle_minutes, 10);
+        assert_eq!(policy.max_load, 0.2);
+        assert!(validate_idle_policy(&policy).is_ok());
+
+        let never = IdlePolicy {
+            idle_minutes: 0,
+            ..policy
+        };
+        match validate_idle_policy(&never).unwrap_err() {
+            AppError::InvalidSetting { field, .. } => assert_eq!(field, "idleMinutes"),
+            other => panic!("unexpected error: {:?}", other),
+        }
+        let negative = IdlePolicy {
+            max_load: -1.0,
+            ..policy
+        };
+        assert!(validate_idle_policy(&negative).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_validate_lima_path_settings() {
+        let dir = temp_dir();
+
+        assert!(validate_lima_path_settings(&LimaPathSettings::default())
+            .await
+            .is_ok());
+
+        // LIMA_HOME may not exist yet, as long as its parent does
+        let ok = LimaPathSettings {
+            lima_home: Some(dir.join("lima").to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        assert!(validate_lima_path_settings(&ok).await.is_ok());
+
+        let relative = LimaPathSettings {
+            lima_home: Some("lima".to_string()),
+            ..Default::default()
+        };
+        let err = validate_lima_path_settings(&relative).await.unwrap_err();
+        assert_eq!(err.kind(), "invalidSetting");
+
+        // A non-executable file is rejected
+        let not_exec = dir.join("limactl");
+        std::fs::write(&not_exec, "").unwrap();
+        let settings = LimaPathSettings {
+            limactl_path: Some(not_exec.to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        match validate_lima_path_settings(&settings).await.unwrap_err() {
+            AppError::InvalidSetting { field, .. } => assert_eq!(field, "limactlPath"),
+            other => panic!("unexpected error: {:?}", other),
+        }
+
+        // An executable that is not limactl is rejected too
+        let fake = dir.join("fake-limactl");
+        std::fs::write(&fake, "#!/bin/sh\necho hello\n").unwrap();
+        std::fs::set_permissions(&fake, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let settings = LimaPathSettings {
+            limactl_path: Some(fake.to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        assert!(validate_lima_path_settings(&settings).await.is_err());
+
+        std::fs::write(&fake, "#!/bin/sh\necho limactl version 2.0.0\n").unwrap();
+        assert!(validate_lima_path_settings(&settings).await.is_ok());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,42 @@
+use crate::error::AppError;
+use crate::snapshot_service::{self, Snapshot};
+use tauri::AppHandle;
+
+/// List the snapshots of an instance, oldest first
+#[tauri::command]
+pub async fn list_snapshots_cmd(
+    app: AppHandle,
+    instance_name: String,
+) -> Result<Vec<Snapshot>, AppError> {
+    snapshot_service::list_snapshots(&app, &instance_name).await
+}
+
+/// Snapshot an instance under a new tag
+#[tauri::command]
+pub async fn create_snapshot_cmd(
+    app: AppHandle,
+    instance_name: String,
+    tag: String,
+) -> Result<Snapshot, AppError> {
+    snapshot_service::create_snapshot(&app, &instance_name, &tag).await
+}
+
+/// Roll an instance back to a snapshot
+#[tauri::command]
+pub async fn apply_snapshot_cmd(
+    app: AppHandle,
+    instance_name: String,
+    tag: String,
+) -> Result<(), AppError> {
+    snapshot_service::apply_snapshot(&app, &instance_name, &tag).await
+}
+
+/// Delete a snapshot
+#[tauri::command]
+pub async fn delete_snapshot_cmd(
+    app: AppHandle,
+    instance_name: String,
+    tag: String,
+) -> Result<(), AppError> {
+    snapshot_service::delete_snapshot(&app, &instance_name, &tag).await
+}
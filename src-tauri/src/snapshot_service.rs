@@ -0,0 +1,546 @@
+use crate::error::AppError;
+use crate::instance_registry_service;
+use crate::lima_backend::{lima_backend, LimaBackend};
+use crate::lima_config::host_default_vm_type;
+use crate::lima_instance_service::read_instance_config;
+use crate::operation_service::{register_operation, OperationKind};
+use serde::{Deserialize, Serialize};
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Runtime};
+
+/// Directory inside an instance dir holding its disk-copy snapshots, one subdirectory per tag
+pub const SNAPSHOT_DIR: &str = "0ma-snapshots";
+
+/// Metadata written last into a disk-copy snapshot; directories without it are partial copies
+const SNAPSHOT_METADATA_FILE: &str = "snapshot.json";
+
+/// Instance files captured by a disk-copy snapshot. lima.yaml is left alone, like
+/// `limactl snapshot` does, so config edits, renames and clones survive an apply.
+const SNAPSHOT_FILES: &[&str] = &["diffdisk"];
+
+/// How a snapshot is stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SnapshotMethod {
+    /// `limactl snapshot`, i.e. qcow2 internal snapshots of the qemu driver
+    Limactl,
+    /// Copy of the stopped instance's disk under `SNAPSHOT_DIR`, for drivers without snapshots
+    DiskCopy,
+}
+
+/// A snapshot of an instance as reported to the frontend
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snapshot {
+    pub tag: String,
+    /// Seconds since the epoch, `None` if limactl reported a date we cannot read
+    pub created_at: Option<u64>,
+    /// Bytes on disk for disk copies, saved VM state for `limactl` snapshots
+    pub size_bytes: Option<u64>,
+    pub method: SnapshotMethod,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SnapshotMetadata {
+    tag: String,
+    created_at: u64,
+}
+
+/// `limactl snapshot` only works with qemu; an instance without `vmType` runs the
+/// host's default driver
+fn snapshot_method<R: Runtime>(app: &AppHandle<R>, instance_name: &str) -> SnapshotMethod {
+    let vm_type = read_instance_config(app, instance_name).and_then(|config| config.vm_type);
+    if vm_type.as_deref().unwrap_or(host_default_vm_type()) == "qemu" {
+        SnapshotMethod::Limactl
+    } else {
+        SnapshotMethod::DiskCopy
+    }
+}
+
+/// Snapshot tags double as directory names: letters, digits, '-', '_' and '.',
+/// starting with a letter or digit
+fn validate_snapshot_tag(tag: &str) -> Result<(), AppError> {
+    let mut chars = tag.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphanumeric())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::InvalidSnapshotTag {
+            tag: tag.to_string(),
+        })
+    }
+}
+
+/// The instance's disk-copy snapshot dir; fails if the instance does not exist
+fn snapshots_dir<R: Runtime>(app: &AppHandle<R>, instance_name: &str) -> Result<PathBuf, AppError> {
+    let instance_dir = crate::yaml_handler::get_instance_dir(app, instance_name)?;
+    if !instance_dir.is_dir() {
+        return Err(AppError::InstanceNotFound {
+            instance_name: instance_name.to_string(),
+        });
+    }
+    Ok(instance_dir.join(SNAPSHOT_DIR))
+}
+
+/// Disk copies can only be taken or applied while the VM is not writing to the disk
+async fn require_stopped(backend: &dyn LimaBackend, instance_name: &str) -> Result<(), AppError> {
+    let instances = instance_registry_service::get_all_lima_instances(backend).await?;
+    let instance = instances
+        .iter()
+        .find(|i| i.name == instance_name)
+        .ok_or_else(|| AppError::InstanceNotFound {
+            instance_name: instance_name.to_string(),
+        })?;
+    if instance.status != "Stopped" {
+        return Err(AppError::InstanceNotStopped {
+            instance_name: instance_name.to_string(),
+            status: instance.status.clone(),
+        });
+    }
+    Ok(())
+}
+
+/// Run `limactl snapshot <action>`, turning a non-zero exit into an error
+async fn run_limactl_snapshot(
+    backend: &dyn LimaBackend,
+    action: &str,
+    instance_name: &str,
+    tag: Option<&str>,
+) -> Result<String, AppError> {
+    let output = backend.snapshot(action, instance_name, tag).await?;
+    if !output.success() {
+        return Err(AppError::command_failed(
+            &format!("limactl snapshot {}", action),
+            output.code,
+            &output.stderr,
+        ));
+    }
+    Ok(output.stdout)
+}
+
+/// Parse the `qemu-img snapshot --list` table printed by `limactl snapshot list`:
+///
+/// ```text
+/// Snapshot list:
+/// ID        TAG               VM SIZE                DATE     VM CLOCK     ICOUNT
+/// 1         base             1.5 MiB 2025-01-01 10:00:00 00:00:12.345          0
+/// ```
+///
+/// The date is in the host's local time zone.
+fn parse_limactl_snapshot_list(output: &str) -> Vec<Snapshot> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            // ID TAG SIZE UNIT DATE TIME CLOCK [ICOUNT]; headers have a non-numeric ID
+            if fields.len() < 7 || fields[0].parse::<u64>().is_err() {
+                return None;
+            }
+            Some(Snapshot {
+                tag: fields[1].to_string(),
+                created_at: parse_local_datetime(fields[4], fields[5]),
+                size_bytes: parse_size(fields[2], fields[3]),
+                method: SnapshotMethod::Limactl,
+            })
+        })
+        .collect()
+}
+
+/// "1.5" "MiB" -> bytes
+fn parse_size(value: &str, unit: &str) -> Option<u64> {
+    let value: f64 = value.parse().ok()?;
+    let multiplier = match unit {
+        "B" => 1u64,
+        "KiB" => 1 << 10,
+        "MiB" => 1 << 20,
+        "GiB" => 1 << 30,
+        "TiB" => 1 << 40,
+        _ => return None,
+    };
+    Some((value * multiplier as f64) as u64)
+}
+
+/// "2025-01-01" "10:00:00" in local time -> seconds since the epoch
+fn parse_local_datetime(date: &str, time: &str) -> Option<u64> {
+    let parse = |s: &str, sep: char| -> Option<Vec<i32>> {
+        let parts: Vec<i32> = s
+            .split(sep)
+            .map(|p| p.parse().ok())
+            .collect::<Option<_>>()?;
+        (parts.len() == 3).then_some(parts)
+    };
+    let (date, time) = (parse(date, '-')?, parse(time, ':')?);
+    // SAFETY: tm is plain old data; mktime only reads and normalizes it
+    let seconds = unsafe {
+        let mut tm: libc::tm = std::mem::zeroed();
+        tm.tm_year = date[0] - 1900;
+        tm.tm_mon = date[1] - 1;
+        tm.tm_mday = date[2];
+        tm.tm_hour = time[0];
+        tm.tm_min = time[1];
+        tm.tm_sec = time[2];
+        tm.tm_isdst = -1;
+        libc::mktime(&mut tm)
+    };
+    u64::try_from(seconds).ok()
+}
+
+/// Bytes actually allocated for the files in `dir`; disk images are sparse
+fn allocated_size(dir: &Path) -> std::io::Result<u64> {
+    let mut size = 0;
+    for entry in std::fs::read_dir(dir)? {
+        size += entry?.metadata()?.blocks() * 512;
+    }
+    Ok(size)
+}
+
+fn list_disk_snapshots(snapshots_dir: &Path) -> Result<Vec<Snapshot>, AppError> {
+    let entries = match std::fs::read_dir(snapshots_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(AppError::io("Failed to read snapshots", e)),
+    };
+    let mut snapshots = Vec::new();
+    for entry in entries {
+        let dir = entry?.path();
+        let Ok(metadata) = std::fs::read_to_string(dir.join(SNAPSHOT_METADATA_FILE)) else {
+            continue;
+        };
+        let metadata: SnapshotMetadata = serde_json::from_str(&metadata)
+            .map_err(|e| AppError::parse("Failed to parse snapshot metadata", e))?;
+        snapshots.push(Snapshot {
+            tag: metadata.tag,
+            created_at: Some(metadata.created_at),
+            size_bytes: allocated_size(&dir).ok(),
+            method: SnapshotMethod::DiskCopy,
+        });
+    }
+    snapshots.sort_by(|a, b| (a.created_at, &a.tag).cmp(&(b.created_at, &b.tag)));
+    Ok(snapshots)
+}
+
+/// Copy the snapshot files into `<SNAPSHOT_DIR>/<tag>`. `std::fs::copy` clones the
+/// files on APFS, so this is cheap on macOS. A failed copy is removed again.
+fn create_disk_snapshot(instance_dir: &Path, tag: &str) -> Result<(), AppError> {
+    let snapshot_dir = instance_dir.join(SNAPSHOT_DIR).join(tag);
+    std::fs::create_dir_all(&snapshot_dir)
+        .map_err(|e| AppError::io("Failed to create snapshot dir", e))?;
+    let result = (|| {
+        for file in SNAPSHOT_FILES {
+            std::fs::copy(instance_dir.join(file), snapshot_dir.join(file))
+                .map_err(|e| AppError::io(&format!("Failed to snapshot {}", file), e))?;
+        }
+        let created_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let metadata = serde_json::to_string(&SnapshotMetadata {
+            tag: tag.to_string(),
+            created_at,
+        })?;
+        std::fs::write(snapshot_dir.join(SNAPSHOT_METADATA_FILE), metadata)
+            .map_err(|e| AppError::io("Failed to write snapshot metadata", e))
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_dir_all(&snapshot_dir);
+    }
+    result
+}
+
+/// Copy the snapshot files back over the instance's. Each file is copied next to its
+/// target and renamed into place, so an interrupted apply leaves the old disk intact.
+fn apply_disk_snapshot(instance_dir: &Path, tag: &str) -> Result<(), AppError> {
+    let snapshot_dir = instance_dir.join(SNAPSHOT_DIR).join(tag);
+    for file in SNAPSHOT_FILES {
+        let target = instance_dir.join(file);
+        let staged = instance_dir.join(format!("{}.0ma-restore", file));
+        std::fs::copy(snapshot_dir.join(file), &staged)
+            .and_then(|_| std::fs::rename(&staged, &target))
+            .map_err(|e| {
+                let _ = std::fs::remove_file(&staged);
+                AppError::io(&format!("Failed to restore {}", file), e)
+            })?;
+    }
+    Ok(())
+}
+
+async fn list_snapshots_with<R: Runtime>(
+    app: &AppHandle<R>,
+    instance_name: &str,
+    method: SnapshotMethod,
+) -> Result<Vec<Snapshot>, AppError> {
+    match method {
+        SnapshotMethod::Limactl => {
+            let backend = lima_backend(app);
+            let stdout =
+                run_limactl_snapshot(backend.as_ref(), "list", instance_name, None).await?;
+            Ok(parse_limactl_snapshot_list(&stdout))
+        }
+        SnapshotMethod::DiskCopy => {
+            let dir = snapshots_dir(app, instance_name)?;
+            tokio::task::spawn_blocking(move || list_disk_snapshots(&dir))
+                .await
+                .map_err(|e| AppError::io("Failed to list snapshots", e))?
+        }
+    }
+}
+
+async fn find_snapshot<R: Runtime>(
+    app: &AppHandle<R>,
+    instance_name: &str,
+    method: SnapshotMethod,
+    tag: &str,
+) -> Result<Option<Snapshot>, AppError> {
+    Ok(list_snapshots_with(app, instance_name, method)
+        .await?
+        .into_iter()
+        .find(|s| s.tag == tag))
+}
+
+/// Snapshots of an instance, oldest first
+pub async fn list_snapshots<R: Runtime>(
+    app: &AppHandle<R>,
+    instance_name: &str,
+) -> Result<Vec<Snapshot>, AppError> {
+    list_snapshots_with(app, instance_name, snapshot_method(app, instance_name)).await
+}
+
+/// Take a snapshot tagged `tag`. Disk copies need the instance to be stopped.
+pub async fn create_snapshot<R: Runtime>(
+    app: &AppHandle<R>,
+    instance_name: &str,
+    tag: &str,
+) -> Result<Snapshot, AppError> {
+    validate_snapshot_tag(tag)?;
+    let _operation = register_operation(app, instance_name, OperationKind::Snapshot)?;
+    let method = snapshot_method(app, instance_name);
+    let backend = lima_backend(app);
+    if method == SnapshotMethod::DiskCopy {
+        require_stopped(backend.as_ref(), instance_name).await?;
+    }
+    if find_snapshot(app, instance_name, method, tag)
+        .await?
+        .is_some()
+    {
+        return Err(AppError::SnapshotAlreadyExists {
+            instance_name: instance_name.to_string(),
+            tag: tag.to_string(),
+        });
+    }
+
+    match method {
+        SnapshotMethod::Limactl => {
+            run_limactl_snapshot(backend.as_ref(), "create", instance_name, Some(tag)).await?;
+        }
+        SnapshotMethod::DiskCopy => {
+            let instance_dir = crate::yaml_handler::get_instance_dir(app, instance_name)?;
+            let tag = tag.to_string();
+            tokio::task::spawn_blocking(move || create_disk_snapshot(&instance_dir, &tag))
+                .await
+                .map_err(|e| AppError::io("Failed to create snapshot", e))??;
+        }
+    }
+
+    find_snapshot(app, instance_name, method, tag)
+        .await?
+        .ok_or_else(|| AppError::SnapshotNotFound {
+            instance_name: instance_name.to_string(),
+            tag: tag.to_string(),
+        })
+}
+
+/// Roll the instance's disk back to the snapshot tagged `tag`.
+/// Disk copies need the instance to be stopped.
+pub async fn apply_snapshot<R: Runtime>(
+    app: &AppHandle<R>,
+    instance_name: &str,
+    tag: &str,
+) -> Result<(), AppError> {
+    let _operation = register_operation(app, instance_name, OperationKind::Snapshot)?;
+    let method = snapshot_method(app, instance_name);
+    let backend = lima_backend(app);
+    if method == SnapshotMethod::DiskCopy {
+        require_stopped(backend.as_ref(), instance_name).await?;
+    }
+    require_snapshot(app, instance_name, method, tag).await?;
+
+    match method {
+        SnapshotMethod::Limactl => {
+            run_limactl_snapshot(backend.as_ref(), "apply", instance_name, Some(tag)).await?;
+        }
+        SnapshotMethod::DiskCopy => {
+            let instance_dir = crate::yaml_handler::get_instance_dir(app, instance_name)?;
+            let tag = tag.to_string();
+            tokio::task::spawn_blocking(move || apply_disk_snapshot(&instance_dir, &tag))
+                .await
+                .map_err(|e| AppError::io("Failed to apply snapshot", e))??;
+        }
+    }
+    Ok(())
+}
+
+/// Delete the snapshot tagged `tag`
+pub async fn delete_snapshot<R: Runtime>(
+    app: &AppHandle<R>,
+    instance_name: &str,
+    tag: &str,
+) -> Result<(), AppError> {
+    let _operation = register_operation(app, instance_name, OperationKind::Snapshot)?;
+    let method = snapshot_method(app, instance_name);
+    require_snapshot(app, instance_name, method, tag).await?;
+
+    match method {
+        SnapshotMethod::Limactl => {
+            let backend = lima_backend(app);
+            run_limactl_snapshot(backend.as_ref(), "delete", instance_name, Some(tag)).await?;
+        }
+        SnapshotMethod::DiskCopy => {
+            let snapshot_dir = snapshots_dir(app, instance_name)?.join(tag);
+            tokio::task::spawn_blocking(move || std::fs::remove_dir_all(snapshot_dir))
+                .await
+                .map_err(|e| AppError::io("Failed to delete snapshot", e))?
+                .map_err(|e| AppError::io("Failed to delete snapshot", e))?;
+        }
+    }
+    Ok(())
+}
+
+async fn require_snapshot<R: Runtime>(
+    app: &AppHandle<R>,
+    instance_name: &str,
+    method: SnapshotMethod,
+    tag: &str,
+) -> Result<(), AppError> {
+    match find_snapshot(app, instance_name, method, tag).await? {
+        Some(_) => Ok(()),
+        None => Err(AppError::SnapshotNotFound {
+            instance_name: instance_name.to_string(),
+            tag: tag.to_string(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_lima_backend::FakeLimaBackend;
+    use crate::lima_backend::LimaBackendState;
+    use std::sync::Arc;
+    use tauri::Manager;
+
+    /// Mock app with one stopped instance whose lima.yaml uses `vm_type`
+    fn mock_app_with_instance(
+        vm_type: &str,
+    ) -> (tauri::App<tauri::test::MockRuntime>, Arc<FakeLimaBackend>) {
+        let backend = Arc::new(FakeLimaBackend::new().with_instance("dev", "Stopped"));
+        let dir = backend.lima_home().unwrap().join("dev");
+        std::fs::write(dir.join("lima.yaml"), format!("vmType: {}\n", vm_type)).unwrap();
+        std::fs::write(dir.join("diffdisk"), "disk v1").unwrap();
+        let app = tauri::test::mock_app();
+        app.manage(LimaBackendState(backend.clone()));
+        (app, backend)
+    }
+
+    #[test]
+    fn test_parse_limactl_snapshot_list() {
+        let output = "Snapshot list:\n\
+            ID        TAG               VM SIZE                DATE     VM CLOCK     ICOUNT\n\
+            1         base                 0 B 2025-01-01 10:00:00 00:00:00.000          0\n\
+            2         after-k8s        1.5 MiB 2025-01-02 10:00:00 00:01:12.345          0\n";
+        let snapshots = parse_limactl_snapshot_list(output);
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots[0].tag, "base");
+        assert_eq!(snapshots[0].size_bytes, Some(0));
+        assert_eq!(snapshots[1].tag, "after-k8s");
+        assert_eq!(snapshots[1].size_bytes, Some(3 << 19));
+        // A day apart, whatever the local time zone
+        let (first, second) = (snapshots[0].created_at, snapshots[1].created_at);
+        assert_eq!(second.unwrap() - first.unwrap(), 24 * 60 * 60);
+        assert!(parse_limactl_snapshot_list("").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_disk_copy_snapshot_lifecycle() {
+        let (app, backend) = mock_app_with_instance("vz");
+        let app = app.handle();
+        let disk = backend.lima_home().unwrap().join("dev").join("diffdisk");
+
+        let snapshot = create_snapshot(app, "dev", "base").await.unwrap();
+        assert_eq!(snapshot.method, SnapshotMethod::DiskCopy);
+        assert!(snapshot.created_at.is_some());
+        assert_eq!(
+            create_snapshot(app, "dev", "base")
+                .await
+                .unwrap_err()
+                .kind(),
+            "snapshotAlreadyExists"
+        );
+        assert_eq!(
+            create_snapshot(app, "dev", "../x")
+                .await
+                .unwrap_err()
+                .kind(),
+            "invalidSnapshotTag"
+        );
+
+        std::fs::write(&disk, "disk v2").unwrap();
+        apply_snapshot(app, "dev", "base").await.unwrap();
+        assert_eq!(std::fs::read_to_string(&disk).unwrap(), "disk v1");
+
+        delete_snapshot(app, "dev", "base").await.unwrap();
+        assert!(list_snapshots(app, "dev").await.unwrap().is_empty());
+        assert_eq!(
+            apply_snapshot(app, "dev", "base").await.unwrap_err().kind(),
+            "snapshotNotFound"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_disk_copy_snapshot_requires_stopped_instance() {
+        let backend = Arc::new(FakeLimaBackend::new().with_instance("dev", "Running"));
+        let dir = backend.lima_home().unwrap().join("dev");
+        std::fs::write(dir.join("lima.yaml"), "vmType: vz\n").unwrap();
+        let app = tauri::test::mock_app();
+        app.manage(LimaBackendState(backend.clone()));
+
+        let err = create_snapshot(app.handle(), "dev", "base")
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), "instanceNotStopped");
+        assert!(!dir.join(SNAPSHOT_DIR).exists());
+    }
+
+    #[tokio::test]
+    async fn test_limactl_snapshot_lifecycle() {
+        let (app, backend) = mock_app_with_instance("qemu");
+        let app = app.handle();
+
+        let snapshot = create_snapshot(app, "dev", "base").await.unwrap();
+        assert_eq!(snapshot.method, SnapshotMethod::Limactl);
+        create_snapshot(app, "dev", "second").await.unwrap();
+        let tags: Vec<String> = list_snapshots(app, "dev")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|s| s.tag)
+            .collect();
+        assert_eq!(tags, ["base", "second"]);
+
+        apply_snapshot(app, "dev", "base").await.unwrap();
+        backend.fail_next("snapshot delete", "snapshot is in use");
+        let err = delete_snapshot(app, "dev", "base").await.unwrap_err();
+        assert_eq!(err.kind(), "commandFailed");
+        delete_snapshot(app, "dev", "base").await.unwrap();
+        assert_eq!(list_snapshots(app, "dev").await.unwrap().len(), 1);
+        // No disk copies are made for qemu
+        assert!(!backend
+            .lima_home()
+            .unwrap()
+            .join("dev")
+            .join(SNAPSHOT_DIR)
+            .exists());
+    }
+}
@@ -88,18 +88,12 @@ mod tests {
     fn test_on_window_close_requested() {
         let state = create_test_state();
         state.on_window_close_requested();
-        assert_eq!(
-            state
-                .is_window_visible
-                .load(std::sync::atomic::Ordering::Relaxed),
-            false
-        );
-        assert_eq!(
-            state
-                .is_window_focused
-                .load(std::sync::atomic::Ordering::Relaxed),
-            false
-        );
+        assert!(!state
+            .is_window_visible
+            .load(std::sync::atomic::Ordering::Relaxed));
+        assert!(!state
+            .is_window_focused
+            .load(std::sync::atomic::Ordering::Relaxed));
     }
 
     #[test]
@@ -108,37 +102,25 @@ mod tests {
 
         // Test: Lost focus
         state.on_window_focused(false);
-        assert_eq!(
-            state
-                .is_window_focused
-                .load(std::sync::atomic::Ordering::Relaxed),
-            false
-        );
+        assert!(!state
+            .is_window_focused
+            .load(std::sync::atomic::Ordering::Relaxed));
         // Losing focus should NOT hide the window
-        assert_eq!(
-            state
-                .is_window_visible
-                .load(std::sync::atomic::Ordering::Relaxed),
-            true
-        );
+        assert!(state
+            .is_window_visible
+            .load(std::sync::atomic::Ordering::Relaxed));
 
         // Test: Gained focus
         state
             .is_window_visible
             .store(false, std::sync::atomic::Ordering::Relaxed); // Start hidden to verify it becomes visible
         state.on_window_focused(true);
-        assert_eq!(
-            state
-                .is_window_focused
-                .load(std::sync::atomic::Ordering::Relaxed),
-            true
-        );
-        assert_eq!(
-            state
-                .is_window_visible
-                .load(std::sync::atomic::Ordering::Relaxed),
-            true
-        );
+        assert!(state
+            .is_window_focused
+            .load(std::sync::atomic::Ordering::Relaxed));
+        assert!(state
+            .is_window_visible
+            .load(std::sync::atomic::Ordering::Relaxed));
     }
 
     #[test]
@@ -152,36 +134,24 @@ mod tests {
             .store(false, std::sync::atomic::Ordering::Relaxed);
 
         state.on_tray_dashboard_click();
-        assert_eq!(
-            state
-                .is_window_visible
-                .load(std::sync::atomic::Ordering::Relaxed),
-            true
-        );
-        assert_eq!(
-            state
-                .is_window_focused
-                .load(std::sync::atomic::Ordering::Relaxed),
-            true
-        );
+        assert!(state
+            .is_window_visible
+            .load(std::sync::atomic::Ordering::Relaxed));
+        assert!(state
+            .is_window_focused
+            .load(std::sync::atomic::Ordering::Relaxed));
     }
 
     #[test]
     fn test_on_tray_hide_click() {
         let state = create_test_state();
         state.on_tray_hide_click();
-        assert_eq!(
-            state
-                .is_window_visible
-                .load(std::sync::atomic::Ordering::Relaxed),
-            false
-        );
-        assert_eq!(
-            state
-                .is_window_focused
-                .load(std::sync::atomic::Ordering::Relaxed),
-            false
-        );
+        assert!(!state
+            .is_window_visible
+            .load(std::sync::atomic::Ordering::Relaxed));
+        assert!(!state
+            .is_window_focused
+            .load(std::sync::atomic::Ordering::Relaxed));
     }
 
     #[test]
@@ -193,16 +163,16 @@ mod tests {
             *last = Instant::now() - Duration::from_secs(10);
         }
         // Should refresh
-        assert_eq!(state.should_refresh_tray(), true);
+        assert!(state.should_refresh_tray());
 
         // 2. Now call it again immediately. The previous call checks updated the timestamp to Now.
         // Should be debounced (false)
-        assert_eq!(state.should_refresh_tray(), false);
+        assert!(!state.should_refresh_tray());
 
         // 3. Force last refresh to be ALMOST ready (4 seconds ago)
         if let Ok(mut last) = state.last_tray_menu_refresh.lock() {
             *last = Instant::now() - Duration::from_secs(4);
         }
-        assert_eq!(state.should_refresh_tray(), false);
+        assert!(!state.should_refresh_tray());
     }
 }
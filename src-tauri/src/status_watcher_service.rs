@@ -0,0 +1,217 @@
+use crate::instance_registry_service;
+use crate::lima_backend::{lima_backend, process_exists};
+use crate::yaml_handler::get_lima_home;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+/// How often the instance dirs are scanned for host agent changes
+const SCAN_INTERVAL: Duration = Duration::from_secs(2);
+
+/// `limactl list` runs at least this often, for changes that leave no trace on disk
+const LIST_INTERVAL: Duration = Duration::from_secs(30);
+
+/// What an instance dir tells about its host agent, without asking limactl
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct InstanceDirState {
+    /// Pid from `ha.pid` and whether that process is alive
+    host_agent: Option<(i32, bool)>,
+    /// Whether the host agent socket `ha.sock` exists
+    socket: bool,
+}
+
+/// Payload of `instance-status-changed`. A `None` status means the instance did not
+/// exist before, or no longer exists.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InstanceStatusChanged {
+    pub instance_name: String,
+    pub old_status: Option<String>,
+    pub new_status: Option<String>,
+}
+
+/// Managed last known status of every instance; `None` until the first listing
+#[derive(Default)]
+pub struct StatusWatcherState(Mutex<Option<BTreeMap<String, String>>>);
+
+/// Host agent state of every instance dir in LIMA_HOME. Lima keeps its own data
+/// (`_config`, `_disks`, ...) in dirs starting with an underscore.
+fn scan_instance_dirs(lima_home: &Path) -> BTreeMap<String, InstanceDirState> {
+    let Ok(entries) = std::fs::read_dir(lima_home) else {
+        return BTreeMap::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            if name.starts_with('_') {
+                return None;
+            }
+            let dir = entry.path();
+            let host_agent = std::fs::read_to_string(dir.join("ha.pid"))
+                .ok()
+                .and_then(|pid| pid.trim().parse().ok())
+                .map(|pid| (pid, process_exists(pid)));
+            let socket = dir.join("ha.sock").exists();
+            Some((name, InstanceDirState { host_agent, socket }))
+        })
+        .collect()
+}
+
+/// Status changes between two listings, in instance name order
+fn diff_statuses(
+    old: &BTreeMap<String, String>,
+    new: &BTreeMap<String, String>,
+) -> Vec<InstanceStatusChanged> {
+    let names: std::collections::BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    names
+        .into_iter()
+        .filter(|name| old.get(*name) != new.get(*name))
+        .map(|name| InstanceStatusChanged {
+            instance_name: name.clone(),
+            old_status: old.get(name).cloned(),
+            new_status: new.get(name).cloned(),
+        })
+        .collect()
+}
+
+/// List the instances and emit `instance-status-changed` for each status that differs
+/// from the last listing. The first listing only records the statuses.
+async fn refresh_statuses<R: Runtime>(app: &AppHandle<R>) -> Vec<InstanceStatusChanged> {
+    let Some(state) = app.try_state::<StatusWatcherState>() else {
+        return Vec::new();
+    };
+    let instances =
+        match instance_registry_service::get_all_lima_instances(lima_backend(app).as_ref()).await {
+            Ok(instances) => instances,
+            Err(e) => {
+                log::debug!("Status watcher failed to list instances: {}", e);
+                return Vec::new();
+            }
+        };
+    let statuses: BTreeMap<String, String> = instances
+        .into_iter()
+        .map(|instance| (instance.name, instance.status))
+        .collect();
+
+    let changes = {
+        let Ok(mut known) = state.0.lock() else {
+            return Vec::new();
+        };
+        let changes = known
+            .as_ref()
+            .map(|known| diff_statuses(known, &statuses))
+            .unwrap_or_default();
+        *known = Some(statuses);
+        changes
+    };
+    for change in &changes {
+        log::info!(
+            "Instance '{}' status changed: {:?} -> {:?}",
+            change.instance_name,
+            change.old_status,
+            change.new_status
+        );
+        let _ = app.emit("instance-status-changed", change);
+    }
+    changes
+}
+
+/// Watch the instance dirs and list the instances whenever a host agent comes or
+/// goes, so VMs that crash or are started and stopped outside the app are noticed.
+/// Called from the `setup` hook.
+pub fn start_status_watcher<R: Runtime>(app: &AppHandle<R>) {
+    app.manage(StatusWatcherState::default());
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut dirs = None;
+        let mut last_list: Option<Instant> = None;
+        loop {
+            let scanned = get_lima_home(&app)
+                .ok()
+                .map(|lima_home| scan_instance_dirs(&lima_home));
+            let list_due = last_list.is_none_or(|at| at.elapsed() >= LIST_INTERVAL);
+            if scanned != dirs || list_due {
+                refresh_statuses(&app).await;
+                last_list = Some(Instant::now());
+                dirs = scanned;
+            }
+            tokio::time::sleep(SCAN_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_lima_backend::FakeLimaBackend;
+    use crate::lima_backend::{LimaBackend, LimaBackendState};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_scan_instance_dirs() {
+        let lima_home = std::env::temp_dir().join(format!("0ma-status-{}", uuid::Uuid::new_v4()));
+        let dev = lima_home.join("dev");
+        std::fs::create_dir_all(&dev).unwrap();
+        std::fs::write(dev.join("ha.pid"), format!("{}\n", std::process::id())).unwrap();
+        std::fs::write(dev.join("ha.sock"), "").unwrap();
+        std::fs::create_dir_all(lima_home.join("stopped")).unwrap();
+        std::fs::create_dir_all(lima_home.join("_config")).unwrap();
+
+        let dirs = scan_instance_dirs(&lima_home);
+        assert_eq!(dirs.len(), 2);
+        assert_eq!(
+            dirs["dev"],
+            InstanceDirState {
+                host_agent: Some((std::process::id() as i32, true)),
+                socket: true,
+            }
+        );
+        assert_eq!(
+            dirs["stopped"],
+            InstanceDirState {
+                host_agent: None,
+                socket: false,
+            }
+        );
+        let _ = std::fs::remove_dir_all(&lima_home);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_reports_changes_after_the_first_listing() {
+        let backend = Arc::new(
+            FakeLimaBackend::new()
+                .with_instance("dev", "Stopped")
+                .with_instance("old", "Running"),
+        );
+        let app = tauri::test::mock_app();
+        app.manage(LimaBackendState(backend.clone()));
+        app.manage(StatusWatcherState::default());
+
+        assert!(refresh_statuses(app.handle()).await.is_empty());
+
+        // Started from the CLI, behind the app's back
+        backend.start("dev").unwrap();
+        backend.delete("old", true).unwrap();
+        let changes = refresh_statuses(app.handle()).await;
+        assert_eq!(
+            changes,
+            [
+                InstanceStatusChanged {
+                    instance_name: "dev".to_string(),
+                    old_status: Some("Stopped".to_string()),
+                    new_status: Some("Running".to_string()),
+                },
+                InstanceStatusChanged {
+                    instance_name: "old".to_string(),
+                    old_status: Some("Running".to_string()),
+                    new_status: None,
+                },
+            ]
+        );
+        assert!(refresh_statuses(app.handle()).await.is_empty());
+    }
+}
@@ -0,0 +1,13 @@
+use crate::error::AppError;
+use crate::template_service::{self, TemplatePreview};
+use tauri::AppHandle;
+
+/// Resolve `template://<name>`, a template URL or a local YAML file (with its
+/// `base:` templates) into a config for `create_lima_instance_cmd`
+#[tauri::command]
+pub async fn resolve_template_cmd(
+    app: AppHandle,
+    source: String,
+) -> Result<TemplatePreview, AppError> {
+    template_service::resolve_template(&app, &source).await
+}
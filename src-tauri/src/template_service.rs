@@ -0,0 +1,472 @@
+use crate::error::AppError;
+use crate::http_client::http_get;
+use crate::lima_config::LimaConfig;
+use crate::lima_info_service::{self, TemplateInfo};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Deepest `base:` chain followed; also stops templates that inherit from themselves
+const MAX_BASE_DEPTH: usize = 16;
+
+/// A template resolved into a config that can be passed to `create_lima_instance`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplatePreview {
+    /// The template with all of its `base:` templates applied
+    pub config: LimaConfig,
+    /// Every template that was read, the requested one first
+    pub sources: Vec<TemplateSource>,
+}
+
+/// One template file read while resolving
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateSource {
+    /// File path or URL
+    pub location: String,
+    /// The download failed and the copy cached by an earlier download was used
+    pub from_cache: bool,
+}
+
+/// Where a template lives once `template://` names are looked up
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Location {
+    File(PathBuf),
+    Url(reqwest::Url),
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Location::File(path) => write!(f, "{}", path.display()),
+            Location::Url(url) => write!(f, "{}", url),
+        }
+    }
+}
+
+/// `base:` is a reference or a list of them; earlier bases take precedence
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BaseField {
+    One(BaseRef),
+    Many(Vec<BaseRef>),
+}
+
+/// `base: template://docker` or `base: {url: ..., digest: "sha256:..."}`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BaseRef {
+    Url(String),
+    Locator { url: String, digest: Option<String> },
+}
+
+impl BaseRef {
+    fn into_parts(self) -> (String, Option<String>) {
+        match self {
+            BaseRef::Url(url) => (url, None),
+            BaseRef::Locator { url, digest } => (url, digest),
+        }
+    }
+}
+
+/// The part of a template `LimaConfig` does not model but resolving needs
+#[derive(Deserialize)]
+struct TemplateHeader {
+    #[serde(default)]
+    base: Option<BaseField>,
+}
+
+/// Path of the YAML file behind `template://<name>`. Names Lima does not list
+/// (e.g. `_images/ubuntu-lts`) are looked up in the directory of the listed ones.
+fn builtin_template_path(templates: &[TemplateInfo], name: &str) -> Option<PathBuf> {
+    if let Some(template) = templates.iter().find(|t| t.name == name) {
+        return Some(PathBuf::from(&template.location));
+    }
+    let dir = templates
+        .iter()
+        .find_map(|t| t.location.strip_suffix(&format!("{}.yaml", t.name)))?;
+    Some(Path::new(dir).join(format!("{}.yaml", name)))
+}
+
+/// File the download of `url` is cached in
+fn cache_path(cache_dir: &Path, url: &reqwest::Url) -> PathBuf {
+    cache_dir.join(format!(
+        "{:x}.yaml",
+        Sha256::digest(url.as_str().as_bytes())
+    ))
+}
+
+/// Fail with `ChecksumMismatch` unless `content` matches a `sha256:<hex>` digest
+fn verify_digest(content: &str, digest: &str) -> Result<(), AppError> {
+    let Some(expected) = digest.strip_prefix("sha256:") else {
+        return Err(AppError::ParseError {
+            message: format!("Unsupported template digest: {}", digest),
+        });
+    };
+    let expected = expected.trim().to_lowercase();
+    let actual = format!("{:x}", Sha256::digest(content.as_bytes()));
+    if actual != expected {
+        return Err(AppError::ChecksumMismatch { expected, actual });
+    }
+    Ok(())
+}
+
+struct Resolver<'a, R: Runtime> {
+    app: &'a AppHandle<R>,
+    /// Where downloaded templates are kept for offline use
+    cache_dir: Option<PathBuf>,
+    /// Built-in templates from `limactl info`, queried on the first `template://`
+    templates: Option<Vec<TemplateInfo>>,
+    sources: Vec<TemplateSource>,
+}
+
+impl<R: Runtime> Resolver<'_, R> {
+    /// Turn a reference into a location; relative ones are taken relative to `parent`
+    async fn locate(
+        &mut self,
+        reference: &str,
+        parent: Option<&Location>,
+    ) -> Result<Location, AppError> {
+        let invalid = |e: &dyn std::fmt::Display| AppError::ParseError {
+            message: format!("Invalid template location '{}': {}", reference, e),
+        };
+
+        if let Some(name) = reference.strip_prefix("template://") {
+            if self.templates.is_none() {
+                self.templates = Some(
+                    lima_info_service::get_host_lima_info(self.app)
+                        .await?
+                        .templates,
+                );
+            }
+            let templates = self.templates.as_deref().unwrap_or_default();
+            let path = builtin_template_path(templates, name.trim_end_matches(".yaml"))
+                .ok_or_else(|| AppError::TemplateNotFound {
+                    location: reference.to_string(),
+                })?;
+            return Ok(Location::File(path));
+        }
+        if reference.starts_with("http://") || reference.starts_with("https://") {
+            return reqwest::Url::parse(reference)
+                .map(Location::Url)
+                .map_err(|e| invalid(&e));
+        }
+        if reference.starts_with("file://") {
+            let url = reqwest::Url::parse(reference).map_err(|e| invalid(&e))?;
+            return url
+                .to_file_path()
+                .map(Location::File)
+                .map_err(|_| invalid(&"not a local path"));
+        }
+
+        let path = match reference.strip_prefix("~/") {
+            Some(rest) => self
+                .app
+                .path()
+                .home_dir()
+                .map_err(|e| AppError::io("Failed to get home directory", e))?
+                .join(rest),
+            None => PathBuf::from(reference),
+        };
+        if path.is_absolute() {
+            return Ok(Location::File(path));
+        }
+        match parent {
+            Some(Location::File(parent)) => Ok(Location::File(
+                parent.parent().unwrap_or(Path::new("/")).join(path),
+            )),
+            Some(Location::Url(parent)) => parent
+                .join(reference)
+                .map(Location::Url)
+                .map_err(|e| invalid(&e)),
+            None => Err(invalid(&"a local template path must be absolute")),
+        }
+    }
+
+    /// Read a template, downloading URLs and falling back to their cached copy offline
+    async fn read(&mut self, location: &Location) -> Result<String, AppError> {
+        let (content, from_cache) = match location {
+            Location::File(path) => {
+                let content = tokio::fs::read_to_string(path).await.map_err(|e| {
+                    if e.kind() == std::io::ErrorKind::NotFound {
+                        AppError::TemplateNotFound {
+                            location: location.to_string(),
+                        }
+                    } else {
+                        AppError::io(&format!("Failed to read {}", path.display()), e)
+                    }
+                })?;
+                (content, false)
+            }
+            Location::Url(url) => self.download(url).await?,
+        };
+        self.sources.push(TemplateSource {
+            location: location.to_string(),
+            from_cache,
+        });
+        Ok(content)
+    }
+
+    async fn download(&self, url: &reqwest::Url) -> Result<(String, bool), AppError> {
+        let downloaded = match http_get(url.as_str()).await {
+            Ok(response) => response
+                .text()
+                .await
+                .map_err(|e| AppError::io(&format!("Failed to download {}", url), e)),
+            Err(e) => Err(e),
+        };
+        let Some(cache_dir) = self.cache_dir.as_deref() else {
+            return downloaded.map(|content| (content, false));
+        };
+        let cache = cache_path(cache_dir, url);
+
+        match downloaded {
+            Ok(content) => {
+                let cached = std::fs::create_dir_all(cache_dir)
+                    .and_then(|_| std::fs::write(&cache, &content));
+                if let Err(e) = cached {
+                    log::warn!("Failed to cache template {}: {}", url, e);
+                }
+                Ok((content, false))
+            }
+            Err(e) => match std::fs::read_to_string(&cache) {
+                Ok(content) => {
+                    log::warn!("Using cached template for {}: {}", url, e);
+                    Ok((content, true))
+                }
+                Err(_) => Err(e),
+            },
+        }
+    }
+
+    /// Read the template at `location` and apply its `base:` templates, depth first
+    fn resolve<'s>(
+        &'s mut self,
+        location: Location,
+        digest: Option<String>,
+        chain: Vec<Location>,
+    ) -> Pin<Box<dyn Future<Output = Result<LimaConfig, AppError>> + Send + 's>> {
+        Box::pin(async move {
+            if chain.contains(&location) {
+                return Err(AppError::ParseError {
+                    message: format!("Template {} inherits from itself", location),
+                });
+            }
+            if chain.len() >= MAX_BASE_DEPTH {
+                return Err(AppError::ParseError {
+                    message: format!("Template {} nests base templates too deeply", location),
+                });
+            }
+
+            let content = self.read(&location).await?;
+            if let Some(digest) = digest {
+                verify_digest(&content, &digest)?;
+            }
+            let context = format!("Failed to parse template {}", location);
+            let header: TemplateHeader =
+                serde_yml::from_str(&content).map_err(|e| AppError::parse(&context, e))?;
+            let mut config =
+                LimaConfig::from_yaml(&content).map_err(|e| AppError::parse(&context, e))?;
+
+            let bases = match header.base {
+                None => Vec::new(),
+                Some(BaseField::One(base)) => vec![base],
+                Some(BaseField::Many(bases)) => bases,
+            };
+            let mut chain = chain;
+            chain.push(location);
+            for base in bases {
+                let (reference, digest) = base.into_parts();
+                let base_location = self.locate(&reference, chain.last()).await?;
+                let base = self.resolve(base_location, digest, chain.clone()).await?;
+                config = config.inherit(base);
+            }
+            Ok(config)
+        })
+    }
+}
+
+async fn resolve_template_with_cache<R: Runtime>(
+    app: &AppHandle<R>,
+    source: &str,
+    cache_dir: Option<PathBuf>,
+) -> Result<TemplatePreview, AppError> {
+    let mut resolver = Resolver {
+        app,
+        cache_dir,
+        templates: None,
+        sources: Vec::new(),
+    };
+    let location = resolver.locate(source.trim(), None).await?;
+    let config = resolver.resolve(location, None, Vec::new()).await?;
+    Ok(TemplatePreview {
+        config,
+        sources: resolver.sources,
+    })
+}
+
+/// Resolve a template into a `LimaConfig` preview. `source` is a Lima built-in
+/// (`template://docker`), an http(s) URL or a local YAML file (absolute, `~/` or
+/// `file://`). `base:` templates are applied the way Lima does. Downloads are
+/// cached in the app cache dir and used when the network is unavailable.
+pub async fn resolve_template<R: Runtime>(
+    app: &AppHandle<R>,
+    source: &str,
+) -> Result<TemplatePreview, AppError> {
+    let cache_dir = match app.path().app_cache_dir() {
+        Ok(dir) => Some(dir.join("templates")),
+        Err(e) => {
+            log::warn!("Downloaded templates will not be cached: {}", e);
+            None
+        }
+    };
+    resolve_template_with_cache(app, source, cache_dir).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_lima_backend::FakeLimaBackend;
+    use crate::lima_backend::LimaBackendState;
+    use std::sync::Arc;
+
+    fn mock_app(backend: FakeLimaBackend) -> tauri::App<tauri::test::MockRuntime> {
+        let app = tauri::test::mock_app();
+        app.manage(LimaBackendState(Arc::new(backend)));
+        app
+    }
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("0ma-templates-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_builtin_template_path() {
+        let templates = vec![TemplateInfo {
+            name: "docker".to_string(),
+            location: "/opt/lima/share/lima/templates/docker.yaml".to_string(),
+        }];
+        assert_eq!(
+            builtin_template_path(&templates, "docker").unwrap(),
+            Path::new("/opt/lima/share/lima/templates/docker.yaml")
+        );
+        assert_eq!(
+            builtin_template_path(&templates, "_images/ubuntu-lts").unwrap(),
+            Path::new("/opt/lima/share/lima/templates/_images/ubuntu-lts.yaml")
+        );
+        assert!(builtin_template_path(&[], "docker").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_local_template_inherits_bases() {
+        let backend = FakeLimaBackend::new().with_template(
+            "_images/ubuntu",
+            "images:\n- location: https://example.com/ubuntu.img\n  arch: x86_64\ncpus: 2\nmemory: 2GiB\n",
+        );
+        let app = mock_app(backend);
+        let dir = temp_dir();
+        std::fs::create_dir_all(dir.join("common")).unwrap();
+        std::fs::write(
+            dir.join("common/docker.yaml"),
+            "base: template://_images/ubuntu\ncontainerd:\n  system: false\n  user: false\nprovision:\n- mode: system\n  script: install-docker\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("team.yaml"),
+            "base:\n- common/docker.yaml\ncpus: 8\nprovision:\n- mode: user\n  script: setup-team\n",
+        )
+        .unwrap();
+
+        let preview = resolve_template_with_cache(
+            app.handle(),
+            &dir.join("team.yaml").display().to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+        let config = preview.config;
+        assert_eq!(config.cpus, Some(8));
+        assert_eq!(config.memory.as_deref(), Some("2GiB"));
+        assert_eq!(config.images.unwrap()[0].arch.as_deref(), Some("x86_64"));
+        let scripts: Vec<_> = config
+            .provision
+            .unwrap()
+            .into_iter()
+            .map(|p| p.script)
+            .collect();
+        assert_eq!(scripts, ["install-docker", "setup-team"]);
+        assert_eq!(preview.sources.len(), 3);
+        assert!(preview.sources[2].location.ends_with("_images/ubuntu.yaml"));
+
+        assert_eq!(
+            resolve_template_with_cache(app.handle(), "template://nope", None)
+                .await
+                .unwrap_err()
+                .kind(),
+            "templateNotFound"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_base_cycle_is_rejected() {
+        let app = mock_app(FakeLimaBackend::new());
+        let dir = temp_dir();
+        std::fs::write(dir.join("a.yaml"), "base: b.yaml\n").unwrap();
+        std::fs::write(dir.join("b.yaml"), "base: ./a.yaml\n").unwrap();
+
+        let err = resolve_template_with_cache(
+            app.handle(),
+            &format!("file://{}", dir.join("a.yaml").display()),
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.kind(), "parseError");
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_url_uses_cached_copy() {
+        let app = mock_app(FakeLimaBackend::new());
+        let cache_dir = temp_dir();
+        // Nothing listens on the discard port, so the download fails at once
+        let source = "http://127.0.0.1:9/templates/k8s.yaml";
+        assert!(
+            resolve_template_with_cache(app.handle(), source, Some(cache_dir.clone()))
+                .await
+                .is_err()
+        );
+
+        std::fs::write(
+            cache_path(&cache_dir, &reqwest::Url::parse(source).unwrap()),
+            "cpus: 6\n",
+        )
+        .unwrap();
+        let preview = resolve_template_with_cache(app.handle(), source, Some(cache_dir))
+            .await
+            .unwrap();
+        assert_eq!(preview.config.cpus, Some(6));
+        assert_eq!(
+            preview.sources,
+            [TemplateSource {
+                location: source.to_string(),
+                from_cache: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_verify_digest() {
+        let digest = format!("sha256:{:x}", Sha256::digest(b"cpus: 2\n"));
+        assert!(verify_digest("cpus: 2\n", &digest).is_ok());
+        assert_eq!(
+            verify_digest("cpus: 4\n", &digest).unwrap_err().kind(),
+            "checksumMismatch"
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::error::AppError;
 use portable_pty::{CommandBuilder, MasterPty, NativePtySystem, PtySize, PtySystem};
 use serde::Serialize;
 use std::{
@@ -25,6 +26,8 @@ struct PtySession {
     subscribers: Arc<Mutex<Vec<Channel<PtyEvent>>>>,
     history: Arc<Mutex<Vec<u8>>>,
     cwd: Arc<Mutex<Option<String>>>,
+    /// Lima instance the session is a `limactl shell` into
+    instance_name: Option<String>,
 }
 
 impl PtySession {
@@ -32,8 +35,10 @@ impl PtySession {
         master: Box<dyn MasterPty + Send>,
         writer: Box<dyn Write + Send>,
         initial_cwd: Option<String>,
+        instance_name: Option<String>,
     ) -> Self {
         Self {
+            instance_name,
             master,
             writer: Arc::new(Mutex::new(writer)),
             subscribers: Arc::new(Mutex::new(Vec::new())),
@@ -43,6 +48,28 @@ impl PtySession {
     }
 }
 
+/// Instance of a `limactl shell [flags] <instance> ...` command line
+fn shell_instance(command: &str, args: &[String]) -> Option<String> {
+    if command != "limactl" && !command.ends_with("/limactl") {
+        return None;
+    }
+    let mut args = args
+        .iter()
+        .skip_while(|arg| arg.as_str() != "shell")
+        .skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            // Flags of `limactl shell` that take a separate value
+            "--workdir" | "--shell" => {
+                args.next();
+            }
+            flag if flag.starts_with('-') => {}
+            instance_name => return Some(instance_name.to_string()),
+        }
+    }
+    None
+}
+
 // -- PTY Manager --
 
 #[derive(Clone)]
@@ -65,7 +92,7 @@ impl PtyManager {
         cwd: Option<String>,
         rows: u16,
         cols: u16,
-    ) -> Result<String, String> {
+    ) -> Result<String, AppError> {
         let pty_system = NativePtySystem::default();
         let pair = pty_system
             .openpty(PtySize {
@@ -74,10 +101,20 @@ impl PtyManager {
                 pixel_width: 0,
                 pixel_height: 0,
             })
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::io("Failed to open PTY", e))?;
+
+        // Run limactl from the configured location against the configured LIMA_HOME
+        let lima_paths = (command == "limactl").then(|| crate::lima_service::lima_paths(app));
+        let program = lima_paths
+            .as_ref()
+            .and_then(|p| p.limactl.clone())
+            .unwrap_or_else(|| command.to_string());
 
-        let mut cmd = CommandBuilder::new(command);
+        let mut cmd = CommandBuilder::new(program);
         cmd.env("TERM", "xterm-256color");
+        if let Some(lima_home) = lima_paths.and_then(|p| p.lima_home) {
+            cmd.env("LIMA_HOME", lima_home);
+        }
         // Suppress zsh's partial-line indicator (%) that appears before every prompt.
         // xterm.js correctly renders the standout attribute zsh uses for this marker,
         // making it visible — unlike some custom terminal renderers that don't.
@@ -100,17 +137,26 @@ impl PtyManager {
             cmd.cwd(dir);
         }
 
-        let child = pair.slave.spawn_command(cmd).map_err(|e| e.to_string())?;
+        let child = pair
+            .slave
+            .spawn_command(cmd)
+            .map_err(|e| AppError::io("Failed to spawn PTY command", e))?;
         let child_pid = child.process_id();
         drop(child);
 
         // We drop the slave explicitly so that we don't hold an open handle that prevents EOF
         drop(pair.slave);
 
-        let reader = pair.master.try_clone_reader().map_err(|e| e.to_string())?;
-        let writer = pair.master.take_writer().map_err(|e| e.to_string())?;
+        let reader = pair
+            .master
+            .try_clone_reader()
+            .map_err(|e| AppError::io("Failed to clone PTY reader", e))?;
+        let writer = pair
+            .master
+            .take_writer()
+            .map_err(|e| AppError::io("Failed to take PTY writer", e))?;
 
-        let session = PtySession::new(pair.master, writer, cwd);
+        let session = PtySession::new(pair.master, writer, cwd, shell_instance(command, args));
         let session_id = uuid::Uuid::new_v4().to_string();
 
         // Clone Arcs for the reader thread
@@ -246,7 +292,7 @@ impl PtyManager {
 
         self.sessions
             .lock()
-            .map_err(|e| e.to_string())?
+            .map_err(|e| AppError::io("Failed to lock terminal state", e))?
             .insert(session_id.clone(), session);
         Ok(session_id)
     }
@@ -254,10 +300,10 @@ impl PtyManager {
     /// Create the shell integration directory with `.zshenv` and `.zshrc` files.
     /// These files make zsh emit OSC escape sequences for the terminal title
     /// while transparently sourcing the user's own dotfiles.
-    fn ensure_zsh_integration<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
-        let data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    fn ensure_zsh_integration<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, AppError> {
+        let data_dir = app.path().app_data_dir()?;
         let dir = data_dir.join("shell-integration").join("zsh");
-        std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        std::fs::create_dir_all(&dir)?;
 
         // .zshenv: runs first — source user's .zshenv, keep ZDOTDIR pointed here
         // so zsh continues to read our .zshrc next.
@@ -266,7 +312,7 @@ impl PtyManager {
             "[[ -f \"${_0MA_ORIG_ZDOTDIR}/.zshenv\" ]] && ",
             "builtin source \"${_0MA_ORIG_ZDOTDIR}/.zshenv\"\n",
         );
-        std::fs::write(&zshenv_path, zshenv).map_err(|e| e.to_string())?;
+        std::fs::write(&zshenv_path, zshenv)?;
 
         // .zprofile: forward to user's .zprofile (important on macOS for PATH setup)
         let zprofile_path = dir.join(".zprofile");
@@ -274,7 +320,7 @@ impl PtyManager {
             "[[ -f \"${_0MA_ORIG_ZDOTDIR}/.zprofile\" ]] && ",
             "builtin source \"${_0MA_ORIG_ZDOTDIR}/.zprofile\"\n",
         );
-        std::fs::write(&zprofile_path, zprofile).map_err(|e| e.to_string())?;
+        std::fs::write(&zprofile_path, zprofile)?;
 
         // .zshrc: restore ZDOTDIR, source user's .zshrc, then add title hooks.
         let zshrc_path = dir.join(".zshrc");
@@ -289,64 +335,103 @@ impl PtyManager {
             "precmd_functions+=(__0ma_precmd)\n",
             "preexec_functions+=(__0ma_preexec)\n",
         );
-        std::fs::write(&zshrc_path, zshrc).map_err(|e| e.to_string())?;
+        std::fs::write(&zshrc_path, zshrc)?;
 
         Ok(dir)
     }
 
-    pub fn close(&self, session_id: &str) -> Result<(), String> {
-        let mut sessions = self.sessions.lock().map_err(|e| e.to_string())?;
+    /// Number of open `limactl shell` sessions into the instance
+    pub fn instance_session_count(&self, instance_name: &str) -> usize {
+        self.sessions
+            .lock()
+            .map(|sessions| {
+                sessions
+                    .values()
+                    .filter(|s| s.instance_name.as_deref() == Some(instance_name))
+                    .count()
+            })
+            .unwrap_or(0)
+    }
+
+    pub fn close(&self, session_id: &str) -> Result<(), AppError> {
+        let mut sessions = self
+            .sessions
+            .lock()
+            .map_err(|e| AppError::io("Failed to lock terminal state", e))?;
         if sessions.remove(session_id).is_some() {
             log::info!("Terminal session closed: {}", session_id);
             Ok(())
         } else {
-            Err("Session not found".to_string())
+            Err(AppError::SessionNotFound {
+                session_id: session_id.to_string(),
+            })
         }
     }
 
-    pub fn attach(&self, session_id: &str, channel: Channel<PtyEvent>) -> Result<(), String> {
-        let sessions = self.sessions.lock().map_err(|e| e.to_string())?;
+    pub fn attach(&self, session_id: &str, channel: Channel<PtyEvent>) -> Result<(), AppError> {
+        let sessions = self
+            .sessions
+            .lock()
+            .map_err(|e| AppError::io("Failed to lock terminal state", e))?;
         if let Some(session) = sessions.get(session_id) {
             // 1. Send History immediately
-            let hist = session.history.lock().map_err(|e| e.to_string())?;
+            let hist = session
+                .history
+                .lock()
+                .map_err(|e| AppError::io("Failed to lock terminal state", e))?;
             if !hist.is_empty() {
                 let text = String::from_utf8_lossy(&hist).to_string();
                 let _ = channel.send(PtyEvent { data: text });
             }
 
             // 2. Replace subscribers (clear stale channels from HMR/reconnect)
-            let mut subs = session.subscribers.lock().map_err(|e| e.to_string())?;
+            let mut subs = session
+                .subscribers
+                .lock()
+                .map_err(|e| AppError::io("Failed to lock terminal state", e))?;
             subs.clear();
             subs.push(channel);
             Ok(())
         } else {
-            Err("Session not found".to_string())
+            Err(AppError::SessionNotFound {
+                session_id: session_id.to_string(),
+            })
         }
     }
 
-    pub fn write(&self, session_id: &str, data: &str) -> Result<(), String> {
+    pub fn write(&self, session_id: &str, data: &str) -> Result<(), AppError> {
         // Clone the writer Arc and release the sessions lock immediately.
         // This minimizes lock contention by not holding the sessions lock during I/O.
         let writer = {
-            let sessions = self.sessions.lock().map_err(|e| e.to_string())?;
+            let sessions = self
+                .sessions
+                .lock()
+                .map_err(|e| AppError::io("Failed to lock terminal state", e))?;
             sessions
                 .get(session_id)
-                .ok_or_else(|| "Session not found".to_string())?
+                .ok_or_else(|| AppError::SessionNotFound {
+                    session_id: session_id.to_string(),
+                })?
                 .writer
                 .clone()
         };
         // Now only the writer lock is held during the actual I/O
-        let mut writer = writer.lock().map_err(|e| e.to_string())?;
-        write!(writer, "{}", data).map_err(|e| e.to_string())?;
+        let mut writer = writer
+            .lock()
+            .map_err(|e| AppError::io("Failed to lock terminal state", e))?;
+        write!(writer, "{}", data)?;
         // Flush immediately to avoid buffering delays. Without this, keystrokes
         // may accumulate in the buffer before being sent to the PTY, causing
         // noticeable input lag when typing quickly as the shell echo is delayed.
-        writer.flush().map_err(|e| e.to_string())?;
+        writer.flush()?;
         Ok(())
     }
 
-    pub fn resize(&self, session_id: &str, rows: u16, cols: u16) -> Result<(), String> {
-        let sessions = self.sessions.lock().map_err(|e| e.to_string())?;
+    pub fn resize(&self, session_id: &str, rows: u16, cols: u16) -> Result<(), AppError> {
+        let sessions = self
+            .sessions
+            .lock()
+            .map_err(|e| AppError::io("Failed to lock terminal state", e))?;
         if let Some(session) = sessions.get(session_id) {
             session
                 .master
@@ -356,10 +441,12 @@ impl PtyManager {
                     pixel_width: 0,
                     pixel_height: 0,
                 })
-                .map_err(|e| e.to_string())?;
+                .map_err(|e| AppError::io("Failed to resize PTY", e))?;
             Ok(())
         } else {
-            Err("Session not found".to_string())
+            Err(AppError::SessionNotFound {
+                session_id: session_id.to_string(),
+            })
         }
     }
 }
@@ -375,7 +462,7 @@ pub async fn spawn_pty_cmd(
     cwd: Option<String>,
     rows: u16,
     cols: u16,
-) -> Result<String, String> {
+) -> Result<String, AppError> {
     manager.spawn(&app, &command, &args, cwd, rows, cols)
 }
 
@@ -384,7 +471,7 @@ pub async fn attach_pty_cmd(
     manager: tauri::State<'_, PtyManager>,
     session_id: String,
     channel: Channel<PtyEvent>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     manager.attach(&session_id, channel)
 }
 
@@ -393,7 +480,7 @@ pub async fn write_pty_cmd(
     manager: tauri::State<'_, PtyManager>,
     session_id: String,
     data: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     manager.write(&session_id, &data)
 }
 
@@ -403,7 +490,7 @@ pub async fn resize_pty_cmd(
     session_id: String,
     rows: u16,
     cols: u16,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     manager.resize(&session_id, rows, cols)
 }
 
@@ -411,6 +498,33 @@ pub async fn resize_pty_cmd(
 pub async fn close_pty_cmd(
     manager: tauri::State<'_, PtyManager>,
     session_id: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     manager.close(&session_id)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_shell_instance() {
+        assert_eq!(
+            shell_instance("limactl", &args(&["shell", "k0s"])).as_deref(),
+            Some("k0s")
+        );
+        assert_eq!(
+            shell_instance(
+                "/opt/homebrew/bin/limactl",
+                &args(&["shell", "--workdir", "/tmp", "--tty=false", "dev", "ls"])
+            )
+            .as_deref(),
+            Some("dev")
+        );
+        assert_eq!(shell_instance("limactl", &args(&["list"])), None);
+        assert_eq!(shell_instance("zsh", &args(&["shell", "dev"])), None);
+    }
+}
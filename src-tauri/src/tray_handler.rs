@@ -1,5 +1,9 @@
+use crate::bulk_operation_service::{self, BulkAction};
 use crate::instance_registry_service;
+use crate::launch_policy_service;
+use crate::lima_backend::lima_backend;
 use crate::lima_instance_service;
+use crate::operation_service;
 use crate::state::AppState;
 use std::time::Instant;
 use tauri::image::Image;
@@ -34,9 +38,13 @@ pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
                 "quit" => {
                     // Notify frontend to flush its persisted state
                     let _ = tauri::Emitter::emit(app, "app-will-quit", ());
-                    // Brief pause so frontend can persist
-                    std::thread::sleep(std::time::Duration::from_millis(200));
-                    std::process::exit(0);
+                    let handle = app.clone();
+                    tauri::async_runtime::spawn(async move {
+                        launch_policy_service::stop_instances_on_quit(&handle).await;
+                        // Brief pause so frontend can persist
+                        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+                        std::process::exit(0);
+                    });
                 }
                 "dashboard" => {
                     if let Some(window) = app.get_webview_window("main") {
@@ -66,32 +74,81 @@ pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
                         });
                     }
                 }
+                "start-all" | "stop-all" => {
+                    let action = if id == "start-all" {
+                        BulkAction::Start
+                    } else {
+                        BulkAction::Stop
+                    };
+                    log::debug!("Tray action: {:?} all instances", action);
+                    let handle = app.clone();
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) =
+                            bulk_operation_service::run_bulk_operation(&handle, action, None).await
+                        {
+                            log::warn!("Tray action: failed to {:?} all: {}", action, e);
+                        }
+                    });
+                }
                 _ => {
                     if let Some(name) = id.strip_prefix("start:") {
                         let name = name.to_string();
                         log::debug!("Tray action: Starting instance '{}'", name);
                         let handle = app.clone();
                         tauri::async_runtime::spawn(async move {
-                            let _ =
-                                lima_instance_service::start_lima_instance(handle.clone(), name)
-                                    .await;
+                            if let Err(e) = lima_instance_service::start_lima_instance(
+                                handle.clone(),
+                                name.clone(),
+                            )
+                            .await
+                            {
+                                log::warn!("Tray action: failed to start '{}': {}", name, e);
+                            }
                         });
                     } else if let Some(name) = id.strip_prefix("stop:") {
                         let name = name.to_string();
                         log::debug!("Tray action: Stopping instance '{}'", name);
                         let handle = app.clone();
                         tauri::async_runtime::spawn(async move {
-                            let _ = lima_instance_service::stop_lima_instance(handle.clone(), name)
-                                .await;
+                            if let Err(e) = lima_instance_service::stop_lima_instance(
+                                handle.clone(),
+                                name.clone(),
+                                false,
+                            )
+                            .await
+                            {
+                                log::warn!("Tray action: failed to stop '{}': {}", name, e);
+                            }
+                        });
+                    } else if let Some(name) = id.strip_prefix("restart:") {
+                        let name = name.to_string();
+                        log::debug!("Tray action: Restarting instance '{}'", name);
+                        let handle = app.clone();
+                        tauri::async_runtime::spawn(async move {
+                            if let Err(e) = lima_instance_service::restart_lima_instance(
+                                handle.clone(),
+                                name.clone(),
+                                None,
+                            )
+                            .await
+                            {
+                                log::warn!("Tray action: failed to restart '{}': {}", name, e);
+                            }
                         });
                     } else if let Some(name) = id.strip_prefix("delete:") {
                         let name = name.to_string();
                         log::debug!("Tray action: Deleting instance '{}'", name);
                         let handle = app.clone();
                         tauri::async_runtime::spawn(async move {
-                            let _ =
-                                lima_instance_service::delete_lima_instance(handle.clone(), name)
-                                    .await;
+                            if let Err(e) = lima_instance_service::delete_lima_instance(
+                                handle.clone(),
+                                name.clone(),
+                                false,
+                            )
+                            .await
+                            {
+                                log::warn!("Tray action: failed to delete '{}': {}", name, e);
+                            }
                         });
                     }
                 }
@@ -125,6 +182,14 @@ pub fn setup_listeners(app: &tauri::App) {
         "lima-instance-start-success",
         "lima-instance-stop-success",
         "lima-instance-delete-success",
+        "lima-instance-restart-success",
+        "lima-instance-recreate-success",
+        "lima-instance-clone-success",
+        "lima-instance-rename-success",
+        // Busy instances get their actions disabled
+        "instance-operation-changed",
+        // Instances crashing or started/stopped outside the app
+        "instance-status-changed",
     ];
 
     for event in events {
@@ -147,7 +212,7 @@ pub async fn refresh_tray_menu(app: &AppHandle) -> Result<(), Box<dyn std::error
         *last_refresh = Instant::now();
     }
 
-    let instances = instance_registry_service::get_all_lima_instances()
+    let instances = instance_registry_service::get_all_lima_instances(lima_backend(app).as_ref())
         .await
         .unwrap_or_default();
 
@@ -176,10 +241,32 @@ pub async fn refresh_tray_menu(app: &AppHandle) -> Result<(), Box<dyn std::error
 
     menu.append(&PredefinedMenuItem::separator(app)?)?;
 
+    if !instances.is_empty() {
+        let any_idle = |running: bool| {
+            instances.iter().any(|i| {
+                (i.status == "Running") == running
+                    && operation_service::current_operation(app, &i.name).is_none()
+            })
+        };
+        let start_all_i =
+            MenuItem::with_id(app, "start-all", "Start all", any_idle(false), None::<&str>)?;
+        let stop_all_i =
+            MenuItem::with_id(app, "stop-all", "Stop all", any_idle(true), None::<&str>)?;
+        menu.append(&start_all_i)?;
+        menu.append(&stop_all_i)?;
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+    }
+
     for instance in instances {
         let instance_name = instance.name.clone();
         let status = instance.status.clone();
-        let display_name = format!("{} ({})", instance_name, status);
+        let operation = operation_service::current_operation(app, &instance_name);
+        let display_name = match &operation {
+            Some(op) => format!("{} ({}…)", instance_name, op.kind.progress_label()),
+            None => format!("{} ({})", instance_name, status),
+        };
+        // Only one lifecycle operation may run per instance
+        let is_idle = operation.is_none();
 
         let instance_submenu = Submenu::with_id(
             app,
@@ -193,14 +280,21 @@ pub async fn refresh_tray_menu(app: &AppHandle) -> Result<(), Box<dyn std::error
             app,
             format!("start:{}", instance_name),
             "Start",
-            !is_running,
+            is_idle && !is_running,
             None::<&str>,
         )?;
         let stop_i = MenuItem::with_id(
             app,
             format!("stop:{}", instance_name),
             "Stop",
-            is_running,
+            is_idle && is_running,
+            None::<&str>,
+        )?;
+        let restart_i = MenuItem::with_id(
+            app,
+            format!("restart:{}", instance_name),
+            "Restart",
+            is_idle && is_running,
             None::<&str>,
         )?;
         let is_stopped = status == "Stopped";
@@ -208,11 +302,11 @@ pub async fn refresh_tray_menu(app: &AppHandle) -> Result<(), Box<dyn std::error
             app,
             format!("delete:{}", instance_name),
             "Delete",
-            is_stopped,
+            is_idle && is_stopped,
             None::<&str>,
         )?;
 
-        instance_submenu.append_items(&[&start_i, &stop_i, &delete_i])?;
+        instance_submenu.append_items(&[&start_i, &stop_i, &restart_i, &delete_i])?;
         menu.append(&instance_submenu)?;
     }
 
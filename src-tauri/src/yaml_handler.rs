@@ -1,31 +1,32 @@
-use crate::find_lima_executable;
-use serde_json::Value;
+use crate::error::AppError;
+use crate::lima_backend::lima_backend;
 use std::fs;
 use std::path::PathBuf;
-use std::process::Command;
 use tauri::{AppHandle, Manager};
 
-/// Get Lima home directory from limactl info or fallback to ~/.lima
-pub(crate) fn get_lima_home<R: tauri::Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
-    // Try to get from limactl info
-    if let Some(lima_cmd) = find_lima_executable() {
-        if let Ok(output) = Command::new(&lima_cmd).args(["info"]).output() {
-            if output.status.success() {
-                if let Ok(json) = serde_json::from_slice::<Value>(&output.stdout) {
-                    if let Some(lima_home) = json["limaHome"].as_str() {
-                        return Ok(PathBuf::from(lima_home));
-                    }
-                }
-            }
-        }
+/// Managed state overriding the user's home directory, whose shell profiles and
+/// `~/.kube` the env integration edits
+pub struct HomeDirState(pub PathBuf);
+
+/// Get the home directory registered in managed state, falling back to the user's
+pub(crate) fn get_home_dir<R: tauri::Runtime>(app: &AppHandle<R>) -> Result<PathBuf, AppError> {
+    if let Some(state) = app.try_state::<HomeDirState>() {
+        return Ok(state.0.clone());
+    }
+    app.path()
+        .home_dir()
+        .map_err(|e| AppError::io("Failed to get home directory", e))
+}
+
+/// Get Lima home directory from the Lima backend (limactl info) or fallback to ~/.lima
+pub(crate) fn get_lima_home<R: tauri::Runtime>(app: &AppHandle<R>) -> Result<PathBuf, AppError> {
+    // Try to get from the backend
+    if let Some(lima_home) = lima_backend(app).lima_home() {
+        return Ok(lima_home);
     }
 
     // Fallback to default: ~/.lima (what Lima uses by default)
-    let home = app
-        .path()
-        .home_dir()
-        .map_err(|e| format!("Failed to get home directory: {}", e))?;
-    Ok(home.join(".lima"))
+    Ok(get_home_dir(app)?.join(".lima"))
 }
 
 /// Get the instance directory path from Lima's directory structure
@@ -33,7 +34,7 @@ pub(crate) fn get_lima_home<R: tauri::Runtime>(app: &AppHandle<R>) -> Result<Pat
 pub(crate) fn get_instance_dir<R: tauri::Runtime>(
     app: &AppHandle<R>,
     instance_name: &str,
-) -> Result<PathBuf, String> {
+) -> Result<PathBuf, AppError> {
     let lima_home = get_lima_home(app)?;
     let instance_dir = lima_home.join(instance_name);
 
@@ -47,7 +48,7 @@ pub(crate) fn get_yaml_path<R: tauri::Runtime>(
     app: &AppHandle<R>,
     instance_name: &str,
     filename: &str,
-) -> Result<PathBuf, String> {
+) -> Result<PathBuf, AppError> {
     let instance_dir = get_instance_dir(app, instance_name)?;
     Ok(instance_dir.join(filename))
 }
@@ -59,8 +60,9 @@ pub(crate) fn write_yaml<R: tauri::Runtime>(
     instance_name: &str,
     filename: &str,
     content: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let yaml_path = get_yaml_path(app, instance_name, filename)?;
 
-    fs::write(&yaml_path, content).map_err(|e| format!("Failed to write {} file: {}", filename, e))
+    fs::write(&yaml_path, content)
+        .map_err(|e| AppError::io(&format!("Failed to write {} file", filename), e))
 }
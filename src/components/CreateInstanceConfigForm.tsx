@@ -28,6 +28,7 @@ const EMPTY_COPY_TO_HOST: CopyToHost[] = [];
 const EMPTY_PORT_FORWARDS: PortForward[] = [];
 const EMPTY_PROVISION: Provision[] = [];
 const EMPTY_PROBES: Probe[] = [];
+const VM_TYPE_LABELS: Record<string, string> = { krunkit: "Krunkit", qemu: "QEMU", vz: "VZ" };
 
 function LabelWithTooltip({
   htmlFor,
@@ -57,8 +58,15 @@ function LabelWithTooltip({
 export function CreateInstanceConfigForm() {
   const { draftConfig, isLoading, updateField, updateDraftConfig, instanceName, setInstanceName, template, setTemplate, nameExists } =
     useCreateLimaInstanceDraft();
-  const { isKrunkitSupported, krunkitMissingReasons } = useSystemCapabilities();
-  const isKrunkit = draftConfig?.vmType === "krunkit";
+  const {
+    isKrunkitSupported,
+    krunkitMissingReasons,
+    qemuMissingReasons,
+    supportedVmTypes,
+    defaultVmType,
+  } = useSystemCapabilities();
+  const vmType = draftConfig?.vmType || defaultVmType;
+  const isKrunkit = vmType === "krunkit";
   const images = draftConfig?.images ?? EMPTY_IMAGES;
   const mounts = draftConfig?.mounts ?? EMPTY_MOUNTS;
   const copyToHost = draftConfig?.copyToHost ?? EMPTY_COPY_TO_HOST;
@@ -104,7 +112,7 @@ export function CreateInstanceConfigForm() {
       },
       vmType: (value: string | null) => {
         if (!draftConfig) return;
-        const newVmType = (value || "VZ").toLowerCase();
+        const newVmType = (value || defaultVmType).toLowerCase();
         if (newVmType === "krunkit") {
           updateDraftConfig({ ...draftConfig, vmType: newVmType, rosetta: undefined });
         } else {
@@ -112,7 +120,7 @@ export function CreateInstanceConfigForm() {
         }
       },
     }),
-    [draftConfig, setInstanceName, setTemplate, updateDraftConfig, updateField],
+    [defaultVmType, draftConfig, setInstanceName, setTemplate, updateDraftConfig, updateField],
   );
 
   const dialogs = useMemo(
@@ -237,18 +245,28 @@ export function CreateInstanceConfigForm() {
             <Label htmlFor="vmType" className="text-muted-foreground">
               VmType
             </Label>
-            <Select value={{ vz: "VZ", qemu: "QEMU", krunkit: "Krunkit" }[draftConfig?.vmType || "vz"] || "VZ"} onValueChange={handlers.vmType}>
+            <Select value={VM_TYPE_LABELS[vmType] ?? vmType} onValueChange={handlers.vmType}>
               <SelectTrigger id="vmType" className="w-full min-w-0" size="sm">
                 <SelectValue placeholder="Select Type" />
               </SelectTrigger>
               <SelectContent>
-                <SelectItem value="VZ">VZ</SelectItem>
-                <SelectItem value="QEMU">QEMU</SelectItem>
-                <SelectItem value="Krunkit">Krunkit</SelectItem>
+                {supportedVmTypes.map((type) => (
+                  <SelectItem key={type} value={VM_TYPE_LABELS[type] ?? type}>
+                    {VM_TYPE_LABELS[type] ?? type}
+                  </SelectItem>
+                ))}
               </SelectContent>
             </Select>
           </div>
 
+          {vmType === "qemu" && qemuMissingReasons.length > 0 && (
+            <ul className="text-xs text-amber-500 ml-[86px] list-disc list-inside">
+              {qemuMissingReasons.map((reason) => (
+                <li key={reason}>{reason}</li>
+              ))}
+            </ul>
+          )}
+
           {isKrunkit && !isKrunkitSupported && krunkitMissingReasons.length > 0 && (
             <ul className="text-xs text-amber-500 ml-[86px] list-disc list-inside">
               {krunkitMissingReasons.map((reason) => (
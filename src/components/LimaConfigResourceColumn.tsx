@@ -37,7 +37,7 @@ function LabelWithTooltip({
 
 export function LimaConfigResourceColumn() {
   const { draftConfig, actualConfig, isLoading, updateField } = useUpdateLimaInstanceDraft();
-  const { isKrunkitSupported, krunkitMissingReasons } = useSystemCapabilities();
+  const { isKrunkitSupported, krunkitMissingReasons, defaultVmType } = useSystemCapabilities();
   const isKrunkit = actualConfig?.vmType === "krunkit";
 
   const handleCpuChange = useCallback(
@@ -133,7 +133,7 @@ export function LimaConfigResourceColumn() {
           <Item variant="muted">
             <ItemContent>
               <ItemTitle>VM Type</ItemTitle>
-              <ItemDescription>{(actualConfig?.vmType || "vz").toUpperCase()}</ItemDescription>
+              <ItemDescription>{(actualConfig?.vmType || defaultVmType).toUpperCase()}</ItemDescription>
             </ItemContent>
           </Item>
         </div>
@@ -1,24 +1,38 @@
-import { useState } from "react";
+import { useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { useQueryClient } from "@tanstack/react-query";
 import { Button } from "src/components/ui/button";
 import { Spinner } from "src/components/ui/spinner";
 import { AlertTriangle, Download } from "lucide-react";
+import { errorMessage } from "src/types/AppError";
 
 export function LimaNotInstalledBanner() {
   const queryClient = useQueryClient();
   const [isInstalling, setIsInstalling] = useState(false);
   const [installError, setInstallError] = useState<string | null>(null);
+  const [installProgress, setInstallProgress] = useState<string | null>(null);
+
+  // Progress of the release archive installer (used when Homebrew is missing)
+  useEffect(() => {
+    const unlisten = listen<{ message: string }>("lima-install-progress", (event) => {
+      setInstallProgress(event.payload.message);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
 
   const handleInstall = async () => {
     setIsInstalling(true);
     setInstallError(null);
+    setInstallProgress(null);
     try {
       await invoke<string>("install_lima_cmd");
       await queryClient.invalidateQueries({ queryKey: ["lima_installed"] });
       await queryClient.invalidateQueries({ queryKey: ["instances"] });
     } catch (err) {
-      setInstallError(String(err));
+      setInstallError(errorMessage(err));
     } finally {
       setIsInstalling(false);
     }
@@ -51,7 +65,7 @@ export function LimaNotInstalledBanner() {
           ) : (
             <>
               <Download className="size-3" />
-              Install Lima via Homebrew
+              Install Lima
             </>
           )}
         </Button>
@@ -59,6 +73,10 @@ export function LimaNotInstalledBanner() {
         <code className="text-xs text-muted-foreground bg-muted px-3 py-1.5 rounded">
           brew install lima
         </code>
+
+        {isInstalling && installProgress && (
+          <p className="text-xs text-muted-foreground max-w-[280px]">{installProgress}</p>
+        )}
       </div>
 
       {installError && (
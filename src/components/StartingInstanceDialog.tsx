@@ -10,6 +10,7 @@ import {
 } from "./ui/dialog";
 import { LogViewer } from "./LogViewer";
 import { useOnLimaStartLogs } from "src/hooks/useOnLimaStartLogs";
+import { bootPhaseLabel } from "src/types/BootProgress";
 
 import { Loader2 } from "lucide-react";
 
@@ -60,6 +61,7 @@ export function StartingInstanceDialog({
       <StartingInstanceDialogContent
         isReady={logState.isReady}
         isSuccess={logState.isSuccess}
+        phase={logState.progress ? bootPhaseLabel(logState.progress) : undefined}
         isError={logState.error.length > 0}
         onClose={handleClose}
       >
@@ -74,12 +76,14 @@ function StartingInstanceDialogContent({
   isReady,
   isSuccess,
   isError,
+  phase,
   onClose,
 }: {
   children: React.ReactNode;
   isReady?: boolean;
   isSuccess?: boolean;
   isError?: boolean;
+  phase?: string;
   onClose: () => void;
 }) {
   return (
@@ -94,7 +98,9 @@ function StartingInstanceDialogContent({
             ? "The instance has started successfully."
             : isReady
               ? "The instance is ready, you can close the dialog"
-              : "Please wait while the instance starts."}
+              : phase
+                ? `${phase}...`
+                : "Please wait while the instance starts."}
         </DialogDescription>
       </DialogHeader>
       {children}
@@ -146,6 +146,30 @@ describe("StopInstanceDialogs", () => {
     expect(stopButton).not.toBeDisabled();
   });
 
+  it("disables stop button while another operation runs on the instance", async () => {
+    renderComponent();
+    const stopButton = screen.getByLabelText("Stop Lima instance");
+    expect(stopButton).not.toBeDisabled();
+
+    act(() => {
+      emitEvent("instance-operation-changed", {
+        instanceName: "test-instance",
+        operation: {
+          instanceName: "test-instance",
+          kind: "start",
+          operationId: "op-1",
+          startedAt: "0",
+        },
+      });
+    });
+    await waitFor(() => expect(stopButton).toBeDisabled());
+
+    act(() => {
+      emitEvent("instance-operation-changed", { instanceName: "test-instance", operation: null });
+    });
+    await waitFor(() => expect(stopButton).not.toBeDisabled());
+  });
+
   it("shows confirmation dialog on Stop click and cancels", async () => {
     renderComponent();
     const stopButton = screen.getByLabelText("Stop Lima instance");
@@ -3,6 +3,7 @@ import { useSelectedInstance } from "src/hooks/useSelectedInstance";
 import { StopInstanceDialog } from "./StopInstanceDialog";
 import { StoppingInstanceDialog } from "./StoppingInstanceDialog";
 import { useLimaInstance } from "src/hooks/useLimaInstance";
+import { useInstanceOperation } from "src/hooks/useInstanceOperation";
 import { PlayIcon, StopCircleIcon } from "lucide-react";
 import { Button } from "./ui/button";
 import { InstanceStatus } from "src/types/InstanceStatus";
@@ -17,6 +18,7 @@ export function StopInstanceDialogs({
 }) {
   const { selectedInstance, selectedName, isLoading } = useSelectedInstance();
   const { stopInstance, startInstance } = useLimaInstance();
+  const { isBusy } = useInstanceOperation(selectedName);
 
   const [stopInstanceDialogOpen, setStopInstanceDialogOpen] = useState(false);
   const [stoppingInstanceDialogOpen, setStoppingInstanceDialogOpen] = useState(false);
@@ -69,7 +71,7 @@ export function StopInstanceDialogs({
           variant="outline"
           aria-label="Start Lima instance"
           className="cursor-pointer"
-          disabled={isLoading}
+          disabled={isLoading || isBusy}
           onClick={handleStartClick}
         >
           <PlayIcon className="md:hidden" />
@@ -80,7 +82,7 @@ export function StopInstanceDialogs({
           variant="secondary"
           aria-label="Stop Lima instance"
           className="cursor-pointer"
-          disabled={!isRunning || isLoading}
+          disabled={!isRunning || isLoading || isBusy}
           onClick={handleStopClick}
         >
           <StopCircleIcon className="md:hidden" />
@@ -11,6 +11,7 @@ import {
 import { Button } from "src/components/ui/button";
 import { useSelectedInstance } from "src/hooks/useSelectedInstance";
 import { useLimaInstance } from "src/hooks/useLimaInstance";
+import { useInstanceOperation } from "src/hooks/useInstanceOperation";
 import { Spinner } from "./ui/spinner";
 import type { LimaInstance } from "src/types/LimaInstance";
 import { useLimaInstances } from "src/hooks/useLimaInstances";
@@ -104,6 +105,7 @@ export function DeleteInstanceButton({
 }) {
   const { selectedName, isLoading } = useSelectedInstance();
   const { deleteInstance } = useLimaInstance();
+  const { isBusy } = useInstanceOperation(selectedName);
 
   const [confirmOpen, setConfirmOpen] = useState(false);
   const [deletingOpen, setDeletingOpen] = useState(false);
@@ -143,7 +145,7 @@ export function DeleteInstanceButton({
         size="icon"
         aria-label="Delete Lima instance"
         className={`cursor-pointer ${className ?? ""}`}
-        disabled={!selectedName || isLoading}
+        disabled={!selectedName || isLoading || isBusy}
         onClick={handleClick}
       >
         <TrashIcon />
@@ -4,6 +4,7 @@ import { useLimaInstance } from "./useLimaInstance";
 import { useSelectedInstance } from "./useSelectedInstance";
 import { useOnLimaStopLogs } from "./useOnLimaStopLogs";
 import { useOnLimaStartLogs } from "./useOnLimaStartLogs";
+import { errorMessage } from "src/types/AppError";
 
 export type ApplyPhase = "idle" | "stopping" | "writing" | "starting" | "done" | "error";
 
@@ -33,7 +34,7 @@ export function useApplyDraftWithRestart() {
         }
       } catch (e) {
         setPhase("error");
-        setError(e instanceof Error ? e.message : String(e));
+        setError(errorMessage(e));
         applyInProgressRef.current = false;
       }
     },
@@ -11,12 +11,14 @@ const LIFECYCLE_EVENTS = [
   "lima-instance-stop-success",
   "lima-instance-delete",
   "lima-instance-delete-success",
+  // From the background watcher, also for changes made outside the app
+  "instance-status-changed",
 ];
 
 /**
  * Global listener that invalidates the instances query whenever any
  * instance lifecycle operation starts or completes — regardless of whether
- * it was triggered from the UI or the system tray.
+ * it was triggered from the UI, the system tray or the CLI.
  *
  * Mount once at the app root.
  */
@@ -0,0 +1,36 @@
+import { useEffect } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { invoke } from "@tauri-apps/api/core";
+import { useQuery, useQueryClient } from "@tanstack/react-query";
+import type { InstanceOperationChanged, OperationInfo } from "src/types/Operation";
+
+const getOperationQueryKey = (instanceName: string) => ["instance-operation", instanceName];
+
+/**
+ * The lifecycle operation currently running on an instance, or null when idle.
+ * Only one operation runs per instance, so actions should be disabled while this is set.
+ */
+export function useInstanceOperation(instanceName: string | null | undefined) {
+  const queryClient = useQueryClient();
+
+  const { data } = useQuery({
+    queryKey: getOperationQueryKey(instanceName ?? ""),
+    queryFn: async () =>
+      (await invoke<OperationInfo | null>("get_instance_operation_cmd", { instanceName })) ?? null,
+    enabled: Boolean(instanceName),
+    staleTime: Infinity, // kept current by instance-operation-changed
+  });
+
+  useEffect(() => {
+    const unlisten = listen<InstanceOperationChanged>("instance-operation-changed", (event) => {
+      const { instanceName: name, operation } = event.payload;
+      queryClient.setQueryData(getOperationQueryKey(name), operation);
+    });
+    return () => {
+      void unlisten.then((fn) => fn()).catch(() => {});
+    };
+  }, [queryClient]);
+
+  const operation = data ?? null;
+  return { isBusy: operation !== null, operation };
+}
@@ -44,11 +44,77 @@ export function useLimaInstance() {
     },
   });
 
+  const restartMutation = useMutation({
+    mutationFn: async ({ config, instanceName }: { config?: LimaConfig; instanceName: string }) =>
+      await invoke<string>("restart_lima_instance_cmd", { config, instanceName }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["instances"] });
+    },
+  });
+
+  // Delete and create again from the current lima.yaml, keeping env setup
+  const recreateMutation = useMutation({
+    mutationFn: async (instanceName: string) =>
+      await invoke<string>("recreate_lima_instance_cmd", { instanceName }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["instances"] });
+    },
+  });
+
+  const cloneMutation = useMutation({
+    mutationFn: async ({ sourceName, targetName }: { sourceName: string; targetName: string }) =>
+      await invoke<string>("clone_lima_instance_cmd", { sourceName, targetName }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["instances"] });
+    },
+  });
+
+  const renameMutation = useMutation({
+    mutationFn: async ({ newName, oldName }: { newName: string; oldName: string }) =>
+      await invoke<void>("rename_lima_instance_cmd", { newName, oldName }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["instances"] });
+    },
+  });
+
+  // Skip the graceful stop/delete and go straight to `--force`
+  const forceStopMutation = useMutation({
+    mutationFn: async (instanceName: string) =>
+      await invoke<string>("stop_lima_instance_cmd", { force: true, instanceName }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["instances"] });
+    },
+  });
+
+  const forceDeleteMutation = useMutation({
+    mutationFn: async (instanceName: string) =>
+      await invoke<string>("delete_lima_instance_cmd", { force: true, instanceName }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["instances"] });
+    },
+  });
+
+  // Cancel an in-flight create/start/stop/delete by the operation id from its events
+  const cancelMutation = useMutation({
+    mutationFn: async (operationId: string) =>
+      await invoke("cancel_operation_cmd", { operationId }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["instances"] });
+    },
+  });
+
   const clearStatus = () => {
     createMutation.reset();
     startMutation.reset();
     stopMutation.reset();
     deleteMutation.reset();
+    restartMutation.reset();
+    recreateMutation.reset();
+    cloneMutation.reset();
+    renameMutation.reset();
+    forceStopMutation.reset();
+    forceDeleteMutation.reset();
+    cancelMutation.reset();
   };
 
   return {
@@ -68,6 +134,32 @@ export function useLimaInstance() {
     deleteInstance: deleteMutation.mutate,
     deleteError: deleteMutation.error,
 
+    // Restart instance mutation, optionally rewriting lima.yaml in between
+    restartInstance: restartMutation.mutate,
+    restartError: restartMutation.error,
+
+    // Recreate instance mutation, preserving env files, profile lines and kube symlink
+    recreateInstance: recreateMutation.mutate,
+    recreateError: recreateMutation.error,
+
+    // Clone a stopped instance to a new name
+    cloneInstance: cloneMutation.mutate,
+    cloneError: cloneMutation.error,
+
+    // Rename a stopped instance, moving its env integration along
+    renameInstance: renameMutation.mutate,
+    renameError: renameMutation.error,
+
+    // Forced stop/delete mutations
+    forceStopInstance: forceStopMutation.mutate,
+    forceStopError: forceStopMutation.error,
+    forceDeleteInstance: forceDeleteMutation.mutate,
+    forceDeleteError: forceDeleteMutation.error,
+
+    // Cancel operation mutation
+    cancelOperation: cancelMutation.mutate,
+    cancelError: cancelMutation.error,
+
     // General utilities
     clearStatus,
   };
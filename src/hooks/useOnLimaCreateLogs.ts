@@ -9,6 +9,7 @@ interface LimaCreatePayload {
   message: string;
   message_id: string;
   timestamp: string;
+  operation_id: string;
 }
 
 const DEFAULT_LIMA_CREATE_STATE: LogState = {
@@ -76,6 +77,7 @@ export function useOnLimaCreateLogs(instanceName: string, options?: { onSuccess?
           // Reset all logs
           ...DEFAULT_LIMA_CREATE_STATE,
           isLoading: true,
+          operationId: event.payload.operation_id,
         }));
       }),
     );
@@ -174,6 +176,22 @@ export function useOnLimaCreateLogs(instanceName: string, options?: { onSuccess?
       }),
     );
 
+    // Cancelled through cancel_operation_cmd
+    unlistenPromises.push(
+      listen<LimaCreatePayload>("lima-instance-create-cancelled", (event) => {
+        if (event.payload.instance_name !== instanceName) {
+          return;
+        }
+
+        updateCache((prev) => ({
+          ...prev,
+          isCancelled: true,
+          isLoading: false,
+        }));
+        queryClient.invalidateQueries({ queryKey: ["instances"] });
+      }),
+    );
+
     return () => {
       active = false;
       void Promise.allSettled(unlistenPromises).then((results) => {
@@ -198,8 +216,10 @@ export function useOnLimaCreateLogs(instanceName: string, options?: { onSuccess?
 
   return {
     error: data?.error ?? [],
+    isCancelled: data?.isCancelled,
     isLoading: data?.isLoading ?? false,
     isSuccess: data?.isSuccess,
+    operationId: data?.operationId,
     reset: () => queryClient.setQueryData(queryKey, DEFAULT_LIMA_CREATE_STATE),
     stderr: data?.stderr ?? [],
     stdout: data?.stdout ?? [],
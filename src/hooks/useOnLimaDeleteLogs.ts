@@ -9,6 +9,7 @@ interface LimaLogPayload {
   message: string;
   message_id: string;
   timestamp: string;
+  operation_id: string;
 }
 
 const DEFAULT_LIMA_DELETE_STATE: LogState = {
@@ -70,6 +71,7 @@ export function useOnLimaDeleteLogs(instanceName: string, options?: { onSuccess?
         updateCache(() => ({
           ...DEFAULT_LIMA_DELETE_STATE,
           isLoading: true,
+          operationId: event.payload.operation_id,
           stdout: [newLog],
         }));
       }),
@@ -153,6 +155,41 @@ export function useOnLimaDeleteLogs(instanceName: string, options?: { onSuccess?
       }),
     );
 
+    // Graceful delete timed out, retrying with --force
+    unlistenPromises.push(
+      listen<LimaLogPayload>("lima-instance-delete-escalated", (event) => {
+        const { instance_name, message, message_id, timestamp } = event.payload;
+        if (instance_name !== instanceName) {
+          return;
+        }
+
+        updateCache((prev) => {
+          if (prev.stderr.some((l) => l.id === message_id)) {
+            return prev;
+          }
+
+          const newLog: Log = { id: message_id, message, timestamp };
+          return { ...prev, isEscalated: true, stderr: insertLog(prev.stderr, newLog) };
+        });
+      }),
+    );
+
+    // Cancelled through cancel_operation_cmd
+    unlistenPromises.push(
+      listen<LimaLogPayload>("lima-instance-delete-cancelled", (event) => {
+        if (event.payload.instance_name !== instanceName) {
+          return;
+        }
+
+        updateCache((prev) => ({
+          ...prev,
+          isCancelled: true,
+          isLoading: false,
+        }));
+        queryClient.invalidateQueries({ queryKey: ["instances"] });
+      }),
+    );
+
     return () => {
       active = false;
       void Promise.allSettled(unlistenPromises).then((results) => {
@@ -176,8 +213,11 @@ export function useOnLimaDeleteLogs(instanceName: string, options?: { onSuccess?
 
   return {
     error: data?.error ?? [],
+    isCancelled: data?.isCancelled,
+    isEscalated: data?.isEscalated,
     isLoading: data?.isLoading ?? false,
     isSuccess: data?.isSuccess,
+    operationId: data?.operationId,
     reset: () => queryClient.setQueryData(queryKey, DEFAULT_LIMA_DELETE_STATE),
     stderr: data?.stderr ?? [],
     stdout: data?.stdout ?? [],
@@ -3,16 +3,20 @@ import { listen } from "@tauri-apps/api/event";
 import { useQuery, useQueryClient } from "@tanstack/react-query";
 import type { Log, LogState } from "src/types/Log";
 import { insertLog } from "src/services/insertLog";
+import type { BootProgressPayload } from "src/types/BootProgress";
 
 interface LimaLogPayload {
   instance_name: string;
   message: string;
   message_id: string;
   timestamp: string;
+  operation_id: string;
 }
 
 type StartLogState = LogState & {
   isReady?: boolean;
+  /** Latest boot phase transition */
+  progress?: BootProgressPayload;
 };
 const DEFAULT_LIMA_START_STATE: StartLogState = {
   error: [],
@@ -87,6 +91,7 @@ export function useOnLimaStartLogs(
           // Reset all logs
           ...DEFAULT_LIMA_START_STATE,
           isLoading: true,
+          operationId: event.payload.operation_id,
         }));
       }),
     );
@@ -129,6 +134,43 @@ export function useOnLimaStartLogs(
       }),
     );
 
+    // 2.5b Started, but readiness conditions failed or timed out; show which ones
+    unlistenPromises.push(
+      listen<LimaLogPayload>("lima-instance-start-not-ready", (event) => {
+        const { instance_name, message, message_id, timestamp } = event.payload;
+        if (instance_name !== instanceName) {
+          return;
+        }
+
+        updateCache((prev) => {
+          if (prev.stderr.some((l) => l.id === message_id)) {
+            return prev;
+          }
+
+          const newLog: Log = { id: message_id, message, timestamp };
+          return {
+            ...prev,
+            isReady: false,
+            stderr: insertLog(prev.stderr, newLog),
+          };
+        });
+      }),
+    );
+
+    // 2.6 Boot phases parsed from limactl output
+    unlistenPromises.push(
+      listen<BootProgressPayload>("lima-instance-start-progress", (event) => {
+        if (event.payload.instance_name !== instanceName) {
+          return;
+        }
+
+        updateCache((prev) => ({
+          ...prev,
+          progress: event.payload,
+        }));
+      }),
+    );
+
     // 3. Stderr
     unlistenPromises.push(
       listen<LimaLogPayload>("lima-instance-start-stderr", (event) => {
@@ -191,6 +233,22 @@ export function useOnLimaStartLogs(
       }),
     );
 
+    // Cancelled through cancel_operation_cmd
+    unlistenPromises.push(
+      listen<LimaLogPayload>("lima-instance-start-cancelled", (event) => {
+        if (event.payload.instance_name !== instanceName) {
+          return;
+        }
+
+        updateCache((prev) => ({
+          ...prev,
+          isCancelled: true,
+          isLoading: false,
+        }));
+        queryClient.invalidateQueries({ queryKey: ["instances"] });
+      }),
+    );
+
     return () => {
       active = false;
       void Promise.allSettled(unlistenPromises).then((results) => {
@@ -215,9 +273,12 @@ export function useOnLimaStartLogs(
 
   return {
     error: data?.error ?? [],
+    isCancelled: data?.isCancelled,
     isLoading: data?.isLoading ?? false,
     isReady: data?.isReady,
     isSuccess: data?.isSuccess,
+    operationId: data?.operationId,
+    progress: data?.progress,
     reset: () => queryClient.setQueryData(queryKey, DEFAULT_LIMA_START_STATE),
     stderr: data?.stderr ?? [],
     stdout: data?.stdout ?? [],
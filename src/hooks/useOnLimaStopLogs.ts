@@ -9,6 +9,7 @@ interface LimaLogPayload {
   message: string;
   message_id: string;
   timestamp: string;
+  operation_id: string;
 }
 
 const DEFAULT_LIMA_STOP_STATE: LogState = {
@@ -76,6 +77,7 @@ export function useOnLimaStopLogs(instanceName: string, options?: { onSuccess?:
           // Reset all logs
           ...DEFAULT_LIMA_STOP_STATE,
           isLoading: true,
+          operationId: event.payload.operation_id,
         }));
       }),
     );
@@ -161,6 +163,41 @@ export function useOnLimaStopLogs(instanceName: string, options?: { onSuccess?:
       }),
     );
 
+    // Graceful stop timed out, retrying with --force
+    unlistenPromises.push(
+      listen<LimaLogPayload>("lima-instance-stop-escalated", (event) => {
+        const { instance_name, message, message_id, timestamp } = event.payload;
+        if (instance_name !== instanceName) {
+          return;
+        }
+
+        updateCache((prev) => {
+          if (prev.stderr.some((l) => l.id === message_id)) {
+            return prev;
+          }
+
+          const newLog: Log = { id: message_id, message, timestamp };
+          return { ...prev, isEscalated: true, stderr: insertLog(prev.stderr, newLog) };
+        });
+      }),
+    );
+
+    // Cancelled through cancel_operation_cmd
+    unlistenPromises.push(
+      listen<LimaLogPayload>("lima-instance-stop-cancelled", (event) => {
+        if (event.payload.instance_name !== instanceName) {
+          return;
+        }
+
+        updateCache((prev) => ({
+          ...prev,
+          isCancelled: true,
+          isLoading: false,
+        }));
+        queryClient.invalidateQueries({ queryKey: ["instances"] });
+      }),
+    );
+
     return () => {
       active = false;
       void Promise.allSettled(unlistenPromises).then((results) => {
@@ -184,8 +221,11 @@ export function useOnLimaStopLogs(instanceName: string, options?: { onSuccess?:
 
   return {
     error: data?.error ?? [],
+    isCancelled: data?.isCancelled,
+    isEscalated: data?.isEscalated,
     isLoading: data?.isLoading ?? false,
     isSuccess: data?.isSuccess,
+    operationId: data?.operationId,
     reset: () => queryClient.setQueryData(queryKey, DEFAULT_LIMA_STOP_STATE),
     stderr: data?.stderr ?? [],
     stdout: data?.stdout ?? [],
@@ -2,12 +2,20 @@ import { useQuery } from "@tanstack/react-query";
 import { invoke } from "@tauri-apps/api/core";
 
 interface SystemCapabilities {
+  os?: string;
   arch: string;
   macosVersion: string;
+  osVersion?: string;
   krunkitAvailable: boolean;
   krunkitDriverAvailable: boolean;
+  kvmAvailable?: boolean;
+  qemuAvailable?: boolean;
+  defaultVmType?: string;
+  supportedVmTypes?: string[];
 }
 
+const MACOS_VM_TYPES = ["vz", "qemu", "krunkit"];
+
 export function useSystemCapabilities() {
   const { data, isLoading } = useQuery({
     queryKey: ["system_capabilities"],
@@ -33,5 +41,26 @@ export function useSystemCapabilities() {
     if (!data.krunkitDriverAvailable) krunkitMissingReasons.push("Lima krunkit driver not found");
   }
 
-  return { capabilities: data, isLoading, isKrunkitSupported, krunkitMissingReasons };
+  const isLinux = data?.os === "linux";
+  const supportedVmTypes = data?.supportedVmTypes ?? MACOS_VM_TYPES;
+  const defaultVmType = data?.defaultVmType ?? "vz";
+
+  // On Linux, qemu needs the emulator binary and an accessible /dev/kvm
+  const qemuMissingReasons: string[] = [];
+  if (data && isLinux) {
+    if (!data.qemuAvailable) qemuMissingReasons.push(`qemu not installed (qemu-system-${data.arch})`);
+    if (!data.kvmAvailable)
+      qemuMissingReasons.push("/dev/kvm not accessible (add your user to the kvm group)");
+  }
+
+  return {
+    capabilities: data,
+    isLoading,
+    isLinux,
+    supportedVmTypes,
+    defaultVmType,
+    isKrunkitSupported,
+    krunkitMissingReasons,
+    qemuMissingReasons,
+  };
 }
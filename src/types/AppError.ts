@@ -0,0 +1,71 @@
+/** Error object rejected by Tauri commands (serialized `AppError` from the backend). */
+export interface AppError {
+  kind:
+    | "limaNotFound"
+    | "instanceNotFound"
+    | "instanceAlreadyExists"
+    | "invalidInstanceName"
+    | "instanceNotStopped"
+    | "snapshotNotFound"
+    | "snapshotAlreadyExists"
+    | "invalidSnapshotTag"
+    | "diskNotFound"
+    | "diskAlreadyExists"
+    | "diskInUse"
+    | "templateNotFound"
+    | "sessionNotFound"
+    | "operationNotFound"
+    | "operationInProgress"
+    | "commandFailed"
+    | "parseError"
+    | "io"
+    | "timeout"
+    | "limaIncompatible"
+    | "checksumMismatch"
+    | "invalidSetting"
+    | "insufficientResources";
+  message: string;
+  instanceName?: string;
+  status?: string;
+  tag?: string;
+  diskName?: string;
+  location?: string;
+  sessionId?: string;
+  operationId?: string;
+  runningOperation?: string;
+  command?: string;
+  exitCode?: number | null;
+  stderr?: string;
+  operation?: string;
+  seconds?: number;
+  field?: string;
+  installedVersion?: string;
+  requiredVersion?: string | null;
+  /** `CompatibilityIssue[]` for limaIncompatible, `ResourceIssue[]` for insufficientResources */
+  issues?: CompatibilityIssue[] | ResourceIssue[];
+  expected?: string;
+  actual?: string;
+}
+
+export interface CompatibilityIssue {
+  field: string;
+  requiredVersion: string;
+  message: string;
+}
+
+export interface ResourceIssue {
+  resource: "cpus" | "memory" | "disk";
+  /** CPUs, or bytes for memory and disk */
+  requested: number;
+  available: number;
+  message: string;
+}
+
+/** Human readable message for anything thrown by `invoke` or plain JS code. */
+export function errorMessage(err: unknown): string {
+  if (err instanceof Error) return err.message;
+  if (typeof err === "object" && err !== null && "message" in err) {
+    return String((err as { message: unknown }).message);
+  }
+  return String(err);
+}
@@ -0,0 +1,47 @@
+export type BootPhaseStatus = "started" | "progress" | "completed" | "failed";
+
+/** Boot phase parsed from limactl output; `phase` is the discriminator. */
+export type BootPhase =
+  | { phase: "image_download"; percent: number | null }
+  | { phase: "vm_boot" }
+  | { phase: "ssh" }
+  | { phase: "provision"; mode: string; index: number }
+  | { phase: "probe"; index: number; total: number; description: string }
+  | { phase: "requirement"; kind: string; index: number; total: number; description: string }
+  | { phase: "port_forward"; guest: string; host: string }
+  | { phase: "ready" };
+
+/** Payload of `lima-instance-create-progress` and `lima-instance-start-progress`. */
+export type BootProgressPayload = BootPhase & {
+  instance_name: string;
+  message: string;
+  message_id: string;
+  timestamp: string;
+  operation_id: string;
+  status: BootPhaseStatus;
+  level: string | null;
+};
+
+/** Short human readable description of a phase, e.g. "Downloading image (42%)". */
+export function bootPhaseLabel(progress: BootPhase): string {
+  switch (progress.phase) {
+    case "image_download":
+      return progress.percent === null
+        ? "Downloading image"
+        : `Downloading image (${Math.round(progress.percent)}%)`;
+    case "vm_boot":
+      return "Booting VM";
+    case "ssh":
+      return "Waiting for SSH";
+    case "provision":
+      return `Running ${progress.mode} provision script ${progress.index + 1}`;
+    case "probe":
+      return `Running probe ${progress.index} of ${progress.total}`;
+    case "requirement":
+      return `Waiting for ${progress.description}`;
+    case "port_forward":
+      return `Forwarding ${progress.guest} to ${progress.host}`;
+    case "ready":
+      return "Ready";
+  }
+}
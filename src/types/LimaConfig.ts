@@ -46,6 +46,14 @@ export interface PortForward {
   ignore?: boolean;
 }
 
+/** Disk from `limactl disk create`, mounted at `/mnt/lima-<name>` */
+export interface AdditionalDisk {
+  name: string;
+  format?: boolean;
+  fsType?: string;
+  fsArgs?: string[];
+}
+
 export function isSocketForward(pf: PortForward): boolean {
   return !!pf.guestSocket || !!pf.hostSocket;
 }
@@ -58,6 +66,7 @@ export interface LimaConfig {
   cpus?: number;
   memory?: string;
   disk?: string;
+  additionalDisks?: AdditionalDisk[];
   images?: Image[];
   mounts?: Mount[];
   containerd?: ContainerdConfig;
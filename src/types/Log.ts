@@ -10,4 +10,9 @@ export interface LogState {
   error: Log[];
   isLoading: boolean;
   isSuccess?: boolean;
+  isCancelled?: boolean;
+  /** The graceful stop/delete timed out and was retried with `--force` */
+  isEscalated?: boolean;
+  /** Id of the running operation, for `cancel_operation_cmd` */
+  operationId?: string;
 }
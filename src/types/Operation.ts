@@ -0,0 +1,16 @@
+export type OperationKind = "create" | "start" | "stop" | "delete" | "restart" | "clone" | "rename" | "snapshot" | "recreate";
+
+/** An in-flight lifecycle operation (`OperationInfo` on the backend). */
+export interface OperationInfo {
+  operationId: string;
+  instanceName: string;
+  kind: OperationKind;
+  /** Nanoseconds since the epoch */
+  startedAt: string;
+}
+
+/** Payload of the `instance-operation-changed` event. */
+export interface InstanceOperationChanged {
+  instanceName: string;
+  operation: OperationInfo | null;
+}